@@ -0,0 +1,138 @@
+use std::{hint::black_box, io, sync::Arc};
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+};
+use raytracing::{
+    cross, dot, random_spheres, seed_rng, unit_vector, BvhNode, CameraBuilder, Color, Dielectric,
+    HitRecord, Hittable, Interval, Lambertian, Material, Metal, Point3, Ray, Sphere, Vec3,
+    INFINITY,
+};
+
+const SEED: u64 = 42;
+
+fn ray_t() -> Interval {
+    Interval::new(0.001, INFINITY)
+}
+
+fn vec3(c: &mut Criterion) {
+    let a = Vec3::new(0.3, -1.2, 2.5);
+    let b = Vec3::new(-0.7, 0.4, 1.1);
+
+    let mut group = c.benchmark_group("vec3");
+    group.bench_function("add_mul", |bench| {
+        bench.iter(|| black_box(a) + black_box(b) * 0.5)
+    });
+    group.bench_function("dot", |bench| {
+        bench.iter(|| dot(black_box(a), black_box(b)))
+    });
+    group.bench_function("cross", |bench| {
+        bench.iter(|| cross(black_box(a), black_box(b)))
+    });
+    group.bench_function("unit_vector", |bench| {
+        bench.iter(|| unit_vector(black_box(a)))
+    });
+    group.finish();
+}
+
+fn sphere_hit(c: &mut Criterion) {
+    let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let sphere = Sphere::new(&Point3::new(0., 0., -1.), 0.5, material).unwrap();
+    let hit = Ray::new(Point3::default(), Vec3::new(0., 0., -1.));
+    let miss = Ray::new(Point3::default(), Vec3::new(0., 1., -1.));
+
+    let mut group = c.benchmark_group("sphere_hit");
+    for (name, ray) in [("hit", hit), ("miss", miss)] {
+        group.bench_function(name, |bench| {
+            let mut record = HitRecord::default();
+            bench.iter(|| sphere.hit(black_box(&ray), ray_t(), &mut record))
+        });
+    }
+    group.finish();
+}
+
+fn list_hit(c: &mut Criterion) {
+    seed_rng(SEED);
+    let world = random_spheres().unwrap();
+    let ray = Ray::new(Point3::new(13., 2., 3.), Vec3::new(-13., -2., -3.));
+
+    let mut group = c.benchmark_group("list_hit");
+    group.bench_function("hittable_list", |bench| {
+        let mut record = HitRecord::default();
+        bench.iter(|| world.hit(black_box(&ray), ray_t(), &mut record))
+    });
+
+    let bvh = BvhNode::new(world);
+    group.bench_function("bvh", |bench| {
+        let mut record = HitRecord::default();
+        bench.iter(|| bvh.hit(black_box(&ray), ray_t(), &mut record))
+    });
+    group.finish();
+}
+
+fn scatter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scatter");
+    bench_scatter(
+        &mut group,
+        "lambertian",
+        Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+    );
+    bench_scatter(
+        &mut group,
+        "metal",
+        Metal::new(Color::new(0.8, 0.6, 0.2), 0.3).unwrap(),
+    );
+    bench_scatter(&mut group, "dielectric", Dielectric::new(1.5).unwrap());
+    group.finish();
+}
+
+fn bench_scatter(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    material: impl Material + 'static,
+) {
+    let sphere = Sphere::new(&Point3::new(0., 0., -1.), 0.5, Arc::new(material)).unwrap();
+    let ray = Ray::new(Point3::default(), Vec3::new(0.1, 0.05, -1.));
+    let mut record = HitRecord::default();
+    assert!(sphere.hit(&ray, ray_t(), &mut record));
+
+    group.bench_function(name, |bench| {
+        let mut attenuation = Color::default();
+        let mut scattered = Ray::default();
+        bench.iter(|| {
+            let mut ray = ray;
+            record
+                .mat
+                .scatter(&mut ray, &record, &mut attenuation, &mut scattered)
+        })
+    });
+}
+
+fn render(c: &mut Criterion) {
+    seed_rng(SEED);
+    let world = BvhNode::new(random_spheres().unwrap());
+    let mut camera = CameraBuilder::default()
+        .aspect_ratio(16. / 9.)
+        .image_width(80)
+        .samples_per_pixel(4)
+        .max_depth(10)
+        .vfov(20.)
+        .lookfrom(Point3::new(13., 2., 3.))
+        .lookat(Point3::new(0., 0., 0.))
+        .defocus_angle(0.6)
+        .threads(1)
+        .seed(Some(SEED))
+        .quiet(true)
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    group.bench_function("final_scene_80px_4spp", |bench| {
+        bench.iter(|| camera.render(&world, &mut io::sink()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, vec3, sphere_hit, list_hit, scatter, render);
+criterion_main!(benches);
@@ -0,0 +1,234 @@
+use std::{fs, path::Path, path::PathBuf};
+
+use clap::ValueEnum;
+
+use crate::{cross, dot, unit_vector, Camera, Context, Float, Point3, RenderError, Vec3, PI};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub frame: Float,
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    pub vfov: Float,
+    pub focus_dist: Float,
+    pub defocus_angle: Float,
+}
+
+impl Keyframe {
+    pub fn from_camera(frame: Float, camera: &Camera) -> Self {
+        Self {
+            frame,
+            lookfrom: camera.lookfrom,
+            lookat: camera.lookat,
+            vfov: camera.vfov,
+            focus_dist: camera.focus_dist,
+            defocus_angle: camera.defocus_angle,
+        }
+    }
+
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.lookfrom = self.lookfrom;
+        camera.lookat = self.lookat;
+        camera.vfov = self.vfov;
+        camera.focus_dist = self.focus_dist;
+        camera.defocus_angle = self.defocus_angle;
+    }
+
+    fn lerp(&self, other: &Keyframe, t: Float) -> Keyframe {
+        Keyframe {
+            frame: lerp(self.frame, other.frame, t),
+            lookfrom: (1. - t) * self.lookfrom + t * other.lookfrom,
+            lookat: (1. - t) * self.lookat + t * other.lookat,
+            vfov: lerp(self.vfov, other.vfov, t),
+            focus_dist: lerp(self.focus_dist, other.focus_dist, t),
+            defocus_angle: lerp(self.defocus_angle, other.defocus_angle, t),
+        }
+    }
+
+    fn catmull_rom(
+        k0: &Keyframe,
+        k1: &Keyframe,
+        k2: &Keyframe,
+        k3: &Keyframe,
+        t: Float,
+    ) -> Keyframe {
+        let w = catmull_rom_weights(t);
+        let vec =
+            |f: fn(&Keyframe) -> Vec3| w[0] * f(k0) + w[1] * f(k1) + w[2] * f(k2) + w[3] * f(k3);
+        let scalar =
+            |f: fn(&Keyframe) -> Float| w[0] * f(k0) + w[1] * f(k1) + w[2] * f(k2) + w[3] * f(k3);
+
+        Keyframe {
+            frame: lerp(k1.frame, k2.frame, t),
+            lookfrom: vec(|k| k.lookfrom),
+            lookat: vec(|k| k.lookat),
+            vfov: scalar(|k| k.vfov),
+            focus_dist: scalar(|k| k.focus_dist),
+            defocus_angle: scalar(|k| k.defocus_angle),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    CatmullRom,
+}
+
+pub struct CameraTrack {
+    keyframes: Vec<Keyframe>,
+    interpolation: Interpolation,
+}
+
+impl CameraTrack {
+    pub fn new(mut keyframes: Vec<Keyframe>, interpolation: Interpolation) -> Self {
+        keyframes.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+        Self {
+            keyframes,
+            interpolation,
+        }
+    }
+
+    // Reads one keyframe per line in the form `frame look-from look-at fov focus-dist
+    // defocus-angle`, with vectors written as `x,y,z` like on the command line. Blank lines and
+    // lines starting with `#` are ignored.
+    pub fn load(path: impl AsRef<Path>, interpolation: Interpolation) -> Result<Self, RenderError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut keyframes = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |message: String| RenderError::InvalidKeyframe {
+                line: number + 1,
+                message,
+            };
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [frame, lookfrom, lookat, vfov, focus_dist, defocus_angle] = fields[..] else {
+                return Err(invalid(format!(
+                    "expected 6 fields, found {}",
+                    fields.len()
+                )));
+            };
+
+            let number = |s: &str| s.parse::<Float>().map_err(|e| invalid(e.to_string()));
+            keyframes.push(Keyframe {
+                frame: number(frame)?,
+                lookfrom: lookfrom
+                    .parse()
+                    .map_err(|e: RenderError| invalid(e.to_string()))?,
+                lookat: lookat
+                    .parse()
+                    .map_err(|e: RenderError| invalid(e.to_string()))?,
+                vfov: number(vfov)?,
+                focus_dist: number(focus_dist)?,
+                defocus_angle: number(defocus_angle)?,
+            });
+        }
+
+        if keyframes.is_empty() {
+            return Err(RenderError::InvalidKeyframe {
+                line: 0,
+                message: format!("{} contains no keyframes", path.display()),
+            });
+        }
+
+        Ok(Self::new(keyframes, interpolation))
+    }
+
+    // A full orbit of `start.lookfrom` around `start.lookat`, turning about `vup`. Every frame gets
+    // its own keyframe so the camera stays on the circle.
+    pub fn turntable(start: Keyframe, vup: Vec3, frames: u32) -> Self {
+        let axis = unit_vector(vup);
+        let keyframes = (0..=frames)
+            .map(|frame| {
+                let angle = 2. * PI * frame as Float / frames.max(1) as Float;
+                let offset = rotate_about_axis(start.lookfrom - start.lookat, axis, angle);
+                Keyframe {
+                    frame: frame as Float,
+                    lookfrom: start.lookat + offset,
+                    ..start
+                }
+            })
+            .collect();
+
+        Self::new(keyframes, Interpolation::Linear)
+    }
+
+    pub fn at(&self, frame: Float) -> Keyframe {
+        let keys = &self.keyframes;
+        let last = keys.len() - 1;
+
+        if frame <= keys[0].frame {
+            return keys[0];
+        }
+        if frame >= keys[last].frame {
+            return keys[last];
+        }
+
+        let next = keys.partition_point(|k| k.frame <= frame);
+        let (k1, k2) = (&keys[next - 1], &keys[next]);
+        let t = (frame - k1.frame) / (k2.frame - k1.frame);
+
+        match self.interpolation {
+            Interpolation::Linear => k1.lerp(k2, t),
+            Interpolation::CatmullRom => {
+                let k0 = &keys[next.saturating_sub(2)];
+                let k3 = &keys[(next + 1).min(last)];
+                Keyframe::catmull_rom(k0, k1, k2, k3, t)
+            }
+        }
+    }
+}
+
+// Replaces the last run of `#` in `pattern` with the zero-padded frame number, appending the
+// number before the extension when there is none.
+pub fn frame_path(pattern: &str, frame: u32) -> PathBuf {
+    if let Some(end) = pattern.rfind('#') {
+        let start = pattern[..end].trim_end_matches('#').len();
+        let width = end + 1 - start;
+        return PathBuf::from(format!(
+            "{}{:0width$}{}",
+            &pattern[..start],
+            frame,
+            &pattern[end + 1..]
+        ));
+    }
+
+    let path = Path::new(pattern);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}_{frame:04}.{}", extension.to_string_lossy()),
+        None => format!("{stem}_{frame:04}"),
+    };
+    path.with_file_name(name)
+}
+
+#[inline]
+fn lerp(a: Float, b: Float, t: Float) -> Float {
+    a + (b - a) * t
+}
+
+fn catmull_rom_weights(t: Float) -> [Float; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    [
+        0.5 * (-t3 + 2. * t2 - t),
+        0.5 * (3. * t3 - 5. * t2 + 2.),
+        0.5 * (-3. * t3 + 4. * t2 + t),
+        0.5 * (t3 - t2),
+    ]
+}
+
+// Rodrigues' rotation formula.
+pub fn rotate_about_axis(v: Vec3, axis: Vec3, angle: Float) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    v * cos + cross(axis, v) * sin + axis * dot(axis, v) * (1. - cos)
+}
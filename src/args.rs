@@ -1,15 +1,58 @@
-use clap::{command, Parser};
+use std::path::PathBuf;
 
-use crate::{Point3, Vec3};
+use clap::{Parser, Subcommand};
+
+use crate::{
+    seed_rng, Camera, CameraBuilder, CameraSettings, Context, EnvironmentDescription,
+    EnvironmentSpec, Float, HittableList, Interpolation, Point3, Projection, RenderError,
+    ResolvedConfig, SceneFile, ScenePreset, Vec3,
+};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    #[command(about = "Renders the built-in scene or a scene file")]
+    Render(Box<RenderArgs>),
+    #[command(about = "Checks that scene files load and describe a valid scene")]
+    Validate(ValidateArgs),
+    #[command(about = "Prints object, material and bounds statistics for a scene file")]
+    Info(InfoArgs),
+    #[command(about = "Converts between image formats or between scene formats")]
+    Convert(ConvertArgs),
+    #[command(about = "Runs an HTTP server that renders scene files submitted as jobs")]
+    Serve(ServeArgs),
+    #[command(about = "Renders the tiles a coordinator sends over TCP")]
+    Worker(WorkerArgs),
+    #[command(about = "Splits the render of a scene file into tiles for remote workers")]
+    Coordinator(CoordinatorArgs),
+}
+
+// Also the command line of the preview window.
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+pub struct RenderArgs {
+    // A .json or .toml scene file to render instead of the built-in scene. Its camera settings and
+    // environment take precedence over the defaults of the options below, but not over options set
+    // in a config file, the environment or on the command line.
+    #[arg(long = "scene")]
+    pub scene: Option<PathBuf>,
+
+    // One of the built-in demo scenes. Its recommended view and environment take precedence over
+    // the defaults of the options below, like the settings of a scene file.
+    #[arg(long = "scene-preset", value_enum, conflicts_with = "scene")]
+    pub scene_preset: Option<ScenePreset>,
+
     #[arg(long = "ratio-width", default_value_t = 16.)]
-    pub ratio_width: f64,
+    pub ratio_width: Float,
 
     #[arg(long = "ratio-height", default_value_t = 9.)]
-    pub ratio_height: f64,
+    pub ratio_height: Float,
 
     #[arg(long = "image-width", default_value_t = 400)]
     pub image_width: u32,
@@ -20,8 +63,11 @@ pub struct Args {
     #[arg(long = "max-depth", default_value_t = 50)]
     pub max_depth: u32,
 
-    #[arg(long = "field-of-view", default_value_t = 90)]
-    pub vfov: u32,
+    #[arg(long = "rr-min-depth", default_value_t = 5)]
+    pub rr_min_depth: u32,
+
+    #[arg(long = "field-of-view", default_value_t = 90.)]
+    pub vfov: Float,
 
     #[arg(long = "look-from", default_value = "-2,2,1")]
     pub lookfrom: Point3,
@@ -33,8 +79,242 @@ pub struct Args {
     pub vup: Vec3,
 
     #[arg(long = "defocus-angle", default_value_t = 0.)]
-    pub defocus_angle: f64,
+    pub defocus_angle: Float,
 
     #[arg(long = "focus-dist", default_value_t = 10.)]
-    pub focus_dist: f64,
+    pub focus_dist: Float,
+
+    #[arg(long = "projection", value_enum, default_value_t = Projection::Perspective)]
+    pub projection: Projection,
+
+    #[arg(long = "eye-separation", default_value_t = 0.065)]
+    pub eye_separation: Float,
+
+    #[arg(long = "spectral", default_value_t = false)]
+    pub spectral: bool,
+
+    #[arg(long = "environment", default_value = "gradient")]
+    pub environment: EnvironmentSpec,
+
+    #[arg(long = "environment-rotation", default_value_t = 0.)]
+    pub environment_rotation: Float,
+
+    #[arg(long = "environment-intensity", default_value_t = 1.)]
+    pub environment_intensity: Float,
+
+    #[arg(long = "frames")]
+    pub frames: Option<u32>,
+
+    #[arg(long = "keyframes", requires = "frames")]
+    pub keyframes: Option<PathBuf>,
+
+    #[arg(long = "interpolation", value_enum, default_value_t = Interpolation::Linear)]
+    pub interpolation: Interpolation,
+
+    // The extension picks the format: .ppm, .pfm or .hdr. Without it a PPM goes to stdout.
+    #[arg(long = "output", short = 'o')]
+    pub output: Option<String>,
+
+    // 0 uses one thread per available core.
+    #[arg(long = "threads", default_value_t = 0)]
+    pub threads: usize,
+
+    // Makes the scene and the image reproducible.
+    #[arg(long = "seed")]
+    pub seed: Option<u64>,
+
+    // Renders progressively and redraws a downsampled preview in the terminal after every pass.
+    #[arg(long = "preview-terminal", default_value_t = false)]
+    pub preview_terminal: bool,
+
+    #[arg(long = "quiet", short = 'q', default_value_t = false)]
+    pub quiet: bool,
+
+    // Without a value the statistics are printed as a table, with a path they are written there as
+    // JSON.
+    #[arg(long = "stats", value_name = "JSON_FILE", num_args = 0..=1)]
+    pub stats: Option<Option<PathBuf>>,
+
+    // Where each option came from, filled in by `parse_layered`.
+    #[arg(skip)]
+    pub resolved: ResolvedConfig,
+}
+
+#[derive(clap::Args)]
+pub struct ValidateArgs {
+    #[arg(required = true)]
+    pub scenes: Vec<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct InfoArgs {
+    pub scene: PathBuf,
+}
+
+// The kind of conversion follows from the extensions of both files.
+#[derive(clap::Args)]
+pub struct ConvertArgs {
+    pub input: PathBuf,
+    pub output: PathBuf,
+}
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    #[arg(long = "address", default_value = "127.0.0.1:8080")]
+    pub address: String,
+
+    // Number of jobs rendered at the same time; the rest wait in the queue.
+    #[arg(long = "concurrency", default_value_t = 2)]
+    pub concurrency: usize,
+
+    // Render threads per job, 0 uses one per available core.
+    #[arg(long = "threads", default_value_t = 0)]
+    pub threads: usize,
+
+    // Finished jobs kept for their status and image; older ones are forgotten.
+    #[arg(long = "keep-finished", default_value_t = 100)]
+    pub keep_finished: usize,
+}
+
+#[derive(clap::Args)]
+pub struct WorkerArgs {
+    // Port 0 picks a free port; the address actually used is printed on startup.
+    #[arg(long = "address", default_value = "127.0.0.1:9000")]
+    pub address: String,
+
+    // 0 uses one thread per available core.
+    #[arg(long = "threads", default_value_t = 0)]
+    pub threads: usize,
+}
+
+#[derive(clap::Args)]
+pub struct CoordinatorArgs {
+    #[arg(long = "scene")]
+    pub scene: PathBuf,
+
+    #[arg(long = "worker", required = true)]
+    pub workers: Vec<String>,
+
+    #[arg(long = "rows-per-tile", default_value_t = 16)]
+    pub rows_per_tile: u32,
+
+    // Seconds a worker may take to accept a message or to answer one before it counts as failed
+    // and its tile goes to another worker. Raise it for slow scenes or large tiles.
+    #[arg(long = "worker-timeout", default_value_t = 300)]
+    pub worker_timeout: u64,
+
+    #[arg(long = "output", short = 'o')]
+    pub output: Option<PathBuf>,
+
+    #[arg(long = "quiet", short = 'q', default_value_t = false)]
+    pub quiet: bool,
+}
+
+impl RenderArgs {
+    pub fn camera_builder(&self) -> CameraBuilder {
+        CameraBuilder::default()
+            .aspect_ratio(self.ratio_width / self.ratio_height)
+            .image_width(self.image_width)
+            .samples_per_pixel(self.samples_per_pixel)
+            .max_depth(self.max_depth)
+            .russian_roulette_depth(self.rr_min_depth)
+            .vfov(self.vfov)
+            .lookfrom(self.lookfrom)
+            .lookat(self.lookat)
+            .vup(self.vup)
+            .focus_dist(self.focus_dist)
+            .defocus_angle(self.defocus_angle)
+            .spectral(self.spectral)
+            .projection(self.projection)
+            .eye_separation(self.eye_separation)
+            .threads(self.threads)
+            .seed(self.seed)
+            .quiet(self.quiet)
+    }
+
+    // The camera settings that were set explicitly rather than left at their defaults.
+    pub fn camera_overrides(&self) -> CameraSettings {
+        let set = |id| self.resolved.is_explicit(id);
+        let aspect_ratio = set("ratio_width") || set("ratio_height");
+        CameraSettings {
+            aspect_ratio: aspect_ratio.then_some(self.ratio_width / self.ratio_height),
+            image_width: set("image_width").then_some(self.image_width),
+            samples_per_pixel: set("samples_per_pixel").then_some(self.samples_per_pixel),
+            max_depth: set("max_depth").then_some(self.max_depth),
+            rr_min_depth: set("rr_min_depth").then_some(self.rr_min_depth),
+            vfov: set("vfov").then_some(self.vfov),
+            look_from: set("lookfrom").then_some(self.lookfrom),
+            look_at: set("lookat").then_some(self.lookat),
+            v_up: set("vup").then_some(self.vup),
+            defocus_angle: set("defocus_angle").then_some(self.defocus_angle),
+            focus_dist: set("focus_dist").then_some(self.focus_dist),
+            projection: set("projection").then_some(self.projection),
+            eye_separation: set("eye_separation").then_some(self.eye_separation),
+            spectral: set("spectral").then_some(self.spectral),
+            seed: self.seed,
+        }
+    }
+
+    // The camera and the world to render, from `--scene`, `--scene-preset` or the built-in scene.
+    pub fn load_scene(&self) -> Result<(Camera, HittableList), RenderError> {
+        let overrides = self.camera_overrides();
+        if let Some(path) = &self.scene {
+            let mut scene = SceneFile::load(path)?;
+            self.override_environment(&mut scene.environment);
+            let cam = scene.camera(self.camera_builder(), &overrides)?;
+            let world = scene.build().context("failed to build the scene")?;
+            return Ok((cam, world));
+        }
+
+        let (builder, environment) = match self.scene_preset {
+            Some(preset) => {
+                let environment = if self.resolved.is_explicit("environment") {
+                    self.environment.clone()
+                } else {
+                    preset.environment()
+                };
+                (
+                    overrides.apply(preset.camera().apply(self.camera_builder())),
+                    environment,
+                )
+            }
+            None => (self.camera_builder(), self.environment.clone()),
+        };
+        let environment = environment
+            .load(self.environment_rotation, self.environment_intensity)
+            .context("failed to set up the environment")?;
+        let cam = builder
+            .environment(environment)
+            .build()
+            .context("invalid camera configuration")?;
+
+        if let Some(seed) = self.seed {
+            seed_rng(seed);
+        }
+        let world = self
+            .scene_preset
+            .unwrap_or_default()
+            .build()
+            .context("failed to build the scene")?;
+        Ok((cam, world))
+    }
+
+    fn override_environment(&self, environment: &mut EnvironmentDescription) {
+        // Files named on the command line or in a config file are relative to the working
+        // directory, and absolute paths stay as they are when the scene joins them to its own.
+        if self.resolved.is_explicit("environment") {
+            environment.map = match &self.environment {
+                EnvironmentSpec::Image(path) => EnvironmentSpec::Image(
+                    std::path::absolute(path).unwrap_or_else(|_| path.clone()),
+                ),
+                map => map.clone(),
+            };
+        }
+        if self.resolved.is_explicit("environment_rotation") {
+            environment.rotation = self.environment_rotation;
+        }
+        if self.resolved.is_explicit("environment_intensity") {
+            environment.intensity = self.environment_intensity;
+        }
+    }
 }
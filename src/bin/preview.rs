@@ -0,0 +1,168 @@
+// Renders the CLI's scene and camera progressively in a window. Drag with the left mouse button
+// or use the arrow keys to orbit around the look-at point, scroll or press +/- to zoom and [/] to
+// change the field of view. Every change restarts the accumulation.
+
+use std::{io, ops::ControlFlow, process::ExitCode};
+
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+use raytracing::{
+    cross, dot, parse_layered, rotate_about_axis, to_rgb8, unit_vector, BvhNode, Camera, Context,
+    Float, Framebuffer, Hittable, RenderArgs, RenderError,
+};
+
+const ORBIT_STEP: Float = 0.087;
+const MOUSE_ORBIT_SPEED: Float = 0.01;
+const ZOOM_STEP: Float = 0.9;
+const FOV_STEP: Float = 5.;
+
+fn main() -> ExitCode {
+    let result = parse_layered::<RenderArgs>("render").and_then(|layered| {
+        if layered.print_config {
+            print!("{}", layered.resolved);
+            return Ok(());
+        }
+        let mut args = layered.args;
+        args.resolved = layered.resolved;
+        run(&args)
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &RenderArgs) -> Result<(), RenderError> {
+    let (mut cam, world) = args.load_scene()?;
+    cam.quiet = true;
+    world.validate().context("invalid scene")?;
+    let world = BvhNode::new(world);
+
+    let (width, height) = cam.image_size();
+    let (width, height) = (width as usize, height as usize);
+    let mut window = Window::new(
+        "raytracing preview",
+        width,
+        height,
+        WindowOptions::default(),
+    )
+    .map_err(io::Error::other)
+    .context("failed to open the preview window")?;
+    window.set_target_fps(60);
+
+    let mut buffer = vec![0; width * height];
+    let mut drag = None;
+
+    while is_running(&window) {
+        let mut motion = None;
+        cam.render_progressive(&world, |passes, image| {
+            to_buffer(image, &mut buffer);
+            window.set_title(&format!("raytracing preview - {passes} spp"));
+            if window.update_with_buffer(&buffer, width, height).is_err() {
+                return ControlFlow::Break(());
+            }
+
+            motion = Motion::read(&window, &mut drag);
+            if motion.is_some() || !is_running(&window) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        // Keep the finished image on screen until the camera moves.
+        while motion.is_none() && is_running(&window) {
+            window.update();
+            motion = Motion::read(&window, &mut drag);
+        }
+
+        if let Some(motion) = motion {
+            motion.apply(&mut cam);
+        }
+    }
+
+    Ok(())
+}
+
+fn is_running(window: &Window) -> bool {
+    window.is_open() && !window.is_key_down(Key::Escape)
+}
+
+fn to_buffer(image: &Framebuffer, buffer: &mut [u32]) {
+    for (pixel, &color) in buffer.iter_mut().zip(image.pixels()) {
+        let [r, g, b] = to_rgb8(color);
+        *pixel = u32::from_be_bytes([0, r, g, b]);
+    }
+}
+
+#[derive(Default)]
+struct Motion {
+    yaw: Float,
+    pitch: Float,
+    zoom: Float,
+    fov: Float,
+}
+
+impl Motion {
+    fn read(window: &Window, drag: &mut Option<(f32, f32)>) -> Option<Motion> {
+        let mut motion = Motion {
+            zoom: 1.,
+            ..Motion::default()
+        };
+
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            match key {
+                Key::Left => motion.yaw -= ORBIT_STEP,
+                Key::Right => motion.yaw += ORBIT_STEP,
+                Key::Up => motion.pitch -= ORBIT_STEP,
+                Key::Down => motion.pitch += ORBIT_STEP,
+                Key::Equal | Key::NumPadPlus => motion.zoom *= ZOOM_STEP,
+                Key::Minus | Key::NumPadMinus => motion.zoom /= ZOOM_STEP,
+                Key::LeftBracket => motion.fov -= FOV_STEP,
+                Key::RightBracket => motion.fov += FOV_STEP,
+                _ => {}
+            }
+        }
+
+        if let Some((_, scroll)) = window.get_scroll_wheel() {
+            motion.zoom *= if scroll > 0. {
+                ZOOM_STEP
+            } else {
+                1. / ZOOM_STEP
+            };
+        }
+
+        let position = window.get_mouse_pos(MouseMode::Discard);
+        match (window.get_mouse_down(MouseButton::Left), position, *drag) {
+            (true, Some((x, y)), Some((last_x, last_y))) => {
+                motion.yaw -= (x - last_x) as Float * MOUSE_ORBIT_SPEED;
+                motion.pitch -= (y - last_y) as Float * MOUSE_ORBIT_SPEED;
+                *drag = Some((x, y));
+            }
+            (true, position, _) => *drag = position,
+            (false, _, _) => *drag = None,
+        }
+
+        let moved = motion.yaw != 0. || motion.pitch != 0. || motion.zoom != 1. || motion.fov != 0.;
+        moved.then_some(motion)
+    }
+
+    fn apply(&self, cam: &mut Camera) {
+        let up = unit_vector(cam.vup);
+        let mut offset = rotate_about_axis(cam.lookfrom - cam.lookat, up, self.yaw);
+
+        // Stop just short of the poles, where the view direction would become parallel to v-up.
+        let right = unit_vector(cross(up, offset));
+        let pitched = rotate_about_axis(offset, right, self.pitch);
+        if dot(unit_vector(pitched), up).abs() < 0.99 {
+            offset = pitched;
+        }
+
+        cam.lookfrom = cam.lookat + offset * self.zoom;
+        cam.focus_dist *= self.zoom;
+        cam.vfov = (cam.vfov + self.fov).clamp(1., 179.);
+    }
+}
@@ -0,0 +1,98 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use crate::{stats, Aabb, HitRecord, Hittable, HittableList, Interval, Ray, RenderError};
+#[cfg(feature = "simd")]
+use crate::{RayPacket, LANES};
+
+pub struct BvhNode {
+    left: Arc<dyn Hittable>,
+    right: Arc<dyn Hittable>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let mut objects = list.into_objects();
+        if objects.is_empty() {
+            let empty: Arc<dyn Hittable> = Arc::new(HittableList::default());
+            return Self {
+                left: Arc::clone(&empty),
+                right: empty,
+                bbox: Aabb::EMPTY,
+            };
+        }
+
+        Self::build(&mut objects)
+    }
+
+    fn build(objects: &mut [Arc<dyn Hittable>]) -> Self {
+        let bbox = objects.iter().fold(Aabb::EMPTY, |bbox, object| {
+            Aabb::surrounding(&bbox, &object.bounding_box())
+        });
+        let axis = bbox.longest_axis();
+
+        let (left, right): (Arc<dyn Hittable>, Arc<dyn Hittable>) = match objects.len() {
+            1 => (Arc::clone(&objects[0]), Arc::clone(&objects[0])),
+            2 => (Arc::clone(&objects[0]), Arc::clone(&objects[1])),
+            len => {
+                objects.sort_by(|a, b| box_compare(a, b, axis));
+                let (left, right) = objects.split_at_mut(len / 2);
+                (Arc::new(Self::build(left)), Arc::new(Self::build(right)))
+            }
+        };
+
+        Self { left, right, bbox }
+    }
+}
+
+fn box_compare(a: &Arc<dyn Hittable>, b: &Arc<dyn Hittable>, axis: usize) -> Ordering {
+    let a_min = a.bounding_box()[axis].min;
+    let b_min = b.bounding_box()[axis].min;
+    a_min.total_cmp(&b_min)
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.bvh_node_visits += 1);
+        if !self.bbox.hit(ray, ray_t) {
+            return false;
+        }
+
+        let hit_left = self.left.hit(ray, ray_t, record);
+        let hit_right = self.right.hit(
+            ray,
+            Interval::new(ray_t.min, if hit_left { record.t } else { ray_t.max }),
+            record,
+        );
+
+        hit_left || hit_right
+    }
+
+    #[cfg(feature = "simd")]
+    fn hit_packet(
+        &self,
+        packet: &RayPacket,
+        mask: u8,
+        ray_t: &mut [Interval; LANES],
+        records: &mut [HitRecord; LANES],
+    ) -> u8 {
+        stats::count(|s| s.bvh_node_visits += 1);
+        let mask = mask & packet.hit_aabb(&self.bbox, ray_t);
+        if mask == 0 {
+            return 0;
+        }
+
+        // The left child narrows `ray_t`, so the right one only reports closer hits.
+        self.left.hit_packet(packet, mask, ray_t, records)
+            | self.right.hit_packet(packet, mask, ray_t, records)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.left.validate()?;
+        self.right.validate()
+    }
+}
@@ -1,26 +1,54 @@
-use indicatif::{ProgressIterator, ProgressStyle};
+#[cfg(feature = "simd")]
+use std::array;
+use std::{
+    io,
+    io::Write,
+    num::NonZeroUsize,
+    ops::ControlFlow,
+    panic,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
 
 use crate::{
-    cross, degrees_to_radians, random_f64, random_in_unit_disk, unit_vector, write_color, Color,
-    HitRecord, Hittable, Interval, Point3, Ray, Vec3, INFINITY,
+    cie_xyz, color_at_wavelength, cross, degrees_to_radians, ensure_finite, random_f64,
+    random_in_unit_disk, sample_wavelength, seed_rng, self_intersection_epsilon, spectral_to_rgb,
+    stats, take_thread_stats, unit_vector, Color, Environment, Float, Framebuffer,
+    GradientEnvironment, HitRecord, Hittable, Interval, Point3, Projection, Ray, RenderError,
+    RenderStats, ThreadStats, Tile, Vec3, INFINITY, PI,
 };
+#[cfg(feature = "simd")]
+use crate::{RayPacket, LANES};
 
-#[derive(Default)]
 pub struct Camera {
-    pub aspect_ratio: f64,
+    pub aspect_ratio: Float,
     pub image_width: u32,
     pub samples_per_pixel: u32,
     pub max_depth: u32,
-    pub vfov: u32,
+    pub vfov: Float,
     pub lookfrom: Point3,
     pub lookat: Point3,
     pub vup: Vec3,
-    pub focus_dist: f64,
-    pub defocus_angle: f64,
+    pub focus_dist: Float,
+    pub defocus_angle: Float,
+    pub spectral: bool,
+    pub environment: Arc<dyn Environment>,
+    pub projection: Projection,
+    pub eye_separation: Float,
+    pub russian_roulette_depth: u32,
+    pub threads: usize,
+    pub seed: Option<u64>,
+    pub quiet: bool,
 
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
-    pixel_samples_scale: f64,
+    pixel_samples_scale: Float,
     u: Vec3,
     v: Vec3,
     w: Vec3,
@@ -31,71 +59,294 @@ pub struct Camera {
     pixel_delta_v: Vec3,
 }
 
-impl Camera {
-    pub fn setup(
-        aspect_ratio: f64,
-        image_width: u32,
-        samples_per_pixel: u32,
-        max_depth: u32,
-        vfov: u32,
-        lookfrom: Point3,
-        lookat: Point3,
-        vup: Vec3,
-
-        focus_dist: f64,
-        defocus_angle: f64,
-    ) -> Self {
+impl Default for Camera {
+    fn default() -> Self {
         Self {
-            aspect_ratio,
-            image_width,
-            samples_per_pixel,
-            max_depth,
-            vfov,
-            lookat,
-            lookfrom,
-            vup,
-            focus_dist,
-            defocus_angle,
-            ..Default::default()
-        }
-    }
-    pub fn render(&mut self, world: impl Hittable) {
+            aspect_ratio: 0.,
+            image_width: 0,
+            samples_per_pixel: 0,
+            max_depth: 0,
+            vfov: 0.,
+            lookfrom: Point3::default(),
+            lookat: Point3::default(),
+            vup: Vec3::default(),
+            focus_dist: 0.,
+            defocus_angle: 0.,
+            spectral: false,
+            environment: Arc::new(GradientEnvironment::default()),
+            projection: Projection::default(),
+            eye_separation: 0.,
+            russian_roulette_depth: 0,
+            threads: 0,
+            seed: None,
+            quiet: false,
+            defocus_disk_u: Vec3::default(),
+            defocus_disk_v: Vec3::default(),
+            pixel_samples_scale: 0.,
+            u: Vec3::default(),
+            v: Vec3::default(),
+            w: Vec3::default(),
+            image_height: 0,
+            center: Point3::default(),
+            pixel00_loc: Point3::default(),
+            pixel_delta_u: Vec3::default(),
+            pixel_delta_v: Vec3::default(),
+        }
+    }
+}
+
+impl Camera {
+    // The public fields can be changed between renders, e.g. by a `CameraTrack`, so this is checked
+    // again before every frame and not only by `CameraBuilder::build`.
+    pub fn validate(&self) -> Result<(), RenderError> {
+        if self.image_width == 0 {
+            return Err(RenderError::ZeroImageWidth);
+        }
+
+        if !(self.aspect_ratio.is_finite() && self.aspect_ratio > 0.) {
+            return Err(RenderError::InvalidAspectRatio(self.aspect_ratio));
+        }
+
+        if self.samples_per_pixel == 0 {
+            return Err(RenderError::ZeroSamplesPerPixel);
+        }
+
+        ensure_finite(self.lookfrom, "look-from point")?;
+        ensure_finite(self.lookat, "look-at point")?;
+        ensure_finite(self.vup, "v-up vector")?;
+
+        let view_direction = self.lookat - self.lookfrom;
+        if view_direction.near_zero() {
+            return Err(RenderError::LookFromEqualsLookAt(self.lookfrom));
+        }
+
+        if cross(self.vup, view_direction).length_squared()
+            <= 1e-12 * self.vup.length_squared() * view_direction.length_squared()
+        {
+            return Err(RenderError::VupParallelToViewDirection(self.vup));
+        }
+
+        if self.focus_dist <= 0. {
+            return Err(RenderError::NonPositiveFocusDistance(self.focus_dist));
+        }
+
+        Ok(())
+    }
+
+    pub fn render(
+        &mut self,
+        world: &impl Hittable,
+        out: &mut impl Write,
+    ) -> io::Result<RenderStats> {
+        let (image, stats) = self.render_image(world);
+        image.write_ppm(out)?;
+        out.flush()?;
+        Ok(stats)
+    }
+
+    pub fn render_image(&mut self, world: &impl Hittable) -> (Framebuffer, RenderStats) {
+        let progress = if self.quiet {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(0).with_style(ProgressStyle::default_bar())
+        };
+        let image = self.render_image_cancellable(world, &progress, &AtomicBool::new(false));
+        image.expect("the render is never cancelled")
+    }
+
+    // Like `render_image`, but reports the finished rows on `progress`, whose length is set to the
+    // image height, and gives up between rows once `cancel` is set. Returns `None` if it was.
+    pub fn render_image_cancellable(
+        &mut self,
+        world: &impl Hittable,
+        progress: &ProgressBar,
+        cancel: &AtomicBool,
+    ) -> Option<(Framebuffer, RenderStats)> {
         self.initialize();
-        print!("P3\n {}  {}\n255\n", self.image_width, self.image_height);
+        progress.set_length(u64::from(self.image_height));
 
-        let style = ProgressStyle::default_bar();
-        for j in (0..self.image_height).progress_with_style(style) {
-            for i in 0..self.image_width {
-                let mut pixel_color = Color::new(0., 0., 0.);
+        let start = Instant::now();
+        let tile = Tile::rows(0, self.image_height);
+        let (image, threads) = self.render_rows(progress, cancel, 0, tile, |i, j| {
+            self.sample_sum(i, j, world, self.samples_per_pixel) * self.pixel_samples_scale
+        });
+        if cancel.load(Ordering::Relaxed) {
+            progress.abandon();
+            return None;
+        }
+        progress.finish();
 
-                for _ in 0..self.samples_per_pixel {
-                    let mut ray = self.get_ray(i, j);
-                    pixel_color += self.ray_color(&mut ray, self.max_depth, &world);
-                }
+        let stats = RenderStats {
+            threads,
+            elapsed: start.elapsed(),
+        };
+        Some((image, stats))
+    }
+
+    // Renders in passes of one sample per pixel, up to `samples_per_pixel` of them, and hands the
+    // running average to `on_pass` together with the number of passes so far. Returning
+    // `ControlFlow::Break` from it stops the render early, e.g. when the camera is about to move.
+    // Returns the average of the passes that were rendered.
+    pub fn render_progressive(
+        &mut self,
+        world: &impl Hittable,
+        mut on_pass: impl FnMut(u32, &Framebuffer) -> ControlFlow<()>,
+    ) -> (Framebuffer, RenderStats) {
+        self.initialize();
+        let start = Instant::now();
+        let mut stats = RenderStats::default();
+        let (width, height) = self.image_size();
+        let mut sum = Framebuffer::new(width, height);
+        let mut average = sum.clone();
 
-                write_color(pixel_color * self.pixel_samples_scale);
+        let cancel = AtomicBool::new(false);
+        let tile = Tile::rows(0, height);
+        for pass in 0..self.samples_per_pixel {
+            let (image, threads) =
+                self.render_rows(&ProgressBar::hidden(), &cancel, pass, tile, |i, j| {
+                    self.sample_sum(i, j, world, 1)
+                });
+            sum.accumulate(&image);
+            stats.threads.resize(threads.len(), ThreadStats::default());
+            for (total, thread) in stats.threads.iter_mut().zip(&threads) {
+                *total = total.merge(thread);
+            }
+
+            let passes = pass + 1;
+            average = sum.scaled(1. / passes as Float);
+            if on_pass(passes, &average).is_break() {
+                break;
             }
         }
+
+        stats.elapsed = start.elapsed();
+        (average, stats)
     }
 
-    fn initialize(&mut self) {
-        self.image_height = {
-            let image_height = (f64::from(self.image_width) / self.aspect_ratio) as u32;
-            if image_height < 1 {
-                1
-            } else {
-                image_height
+    // Renders only the rows of `tile`, exactly as they come out of a full render with the same
+    // seed.
+    pub fn render_tile(&mut self, world: &impl Hittable, tile: Tile) -> Framebuffer {
+        self.initialize();
+        let (image, _) = self.render_rows(
+            &ProgressBar::hidden(),
+            &AtomicBool::new(false),
+            0,
+            tile,
+            |i, j| self.sample_sum(i, j, world, self.samples_per_pixel) * self.pixel_samples_scale,
+        );
+        image
+    }
+
+    // Rows are handed out to the worker threads one at a time, so threads that get cheap rows
+    // (e.g. sky only) keep pulling more instead of idling.
+    fn render_rows(
+        &self,
+        progress: &ProgressBar,
+        cancel: &AtomicBool,
+        pass: u32,
+        tile: Tile,
+        pixel: impl Fn(u32, u32) -> Color + Sync,
+    ) -> (Framebuffer, Vec<ThreadStats>) {
+        let (width, _) = self.image_size();
+        let end = tile.y + tile.height;
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            threads => threads,
+        }
+        .min(tile.height as usize)
+        .max(1);
+        let next_row = AtomicU32::new(tile.y);
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let start = Instant::now();
+                        let mut rows = Vec::new();
+                        loop {
+                            let j = next_row.fetch_add(1, Ordering::Relaxed);
+                            if j >= end || cancel.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            // Seeding per row keeps seeded renders identical whichever thread
+                            // picks up the row.
+                            if let Some(seed) = self.seed {
+                                let row =
+                                    u64::from(pass) * u64::from(self.image_height) + u64::from(j);
+                                seed_rng(seed.wrapping_add(row));
+                            }
+                            let row: Vec<Color> = (0..width).map(|i| pixel(i, j)).collect();
+                            rows.push((j, row));
+                            progress.inc(1);
+                        }
+
+                        let mut stats = take_thread_stats();
+                        stats.elapsed = start.elapsed();
+                        (rows, stats)
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        });
+
+        let mut image = Framebuffer::new(width, tile.height);
+        let mut stats = Vec::with_capacity(results.len());
+        for (rows, thread_stats) in results {
+            for (j, row) in rows {
+                image.set_row(j - tile.y, &row);
+            }
+            stats.push(thread_stats);
+        }
+
+        (image, stats)
+    }
+
+    // Sum of `samples` samples of pixel (i, j).
+    fn sample_sum(&self, i: u32, j: u32, world: &impl Hittable, samples: u32) -> Color {
+        let mut pixel_color = Color::new(0., 0., 0.);
+
+        #[cfg(feature = "simd")]
+        let samples = {
+            let packets = samples / LANES as u32;
+            for _ in 0..packets {
+                pixel_color += self.sample_pixel_packet(i, j, world);
             }
+            packets * LANES as u32..samples
         };
+        #[cfg(not(feature = "simd"))]
+        let samples = 0..samples;
+
+        for _ in samples {
+            pixel_color += self.sample_pixel(i, j, world);
+        }
+
+        pixel_color
+    }
+
+    // Size of the rendered image, which holds both views side by side for stereo projections.
+    pub fn image_size(&self) -> (u32, u32) {
+        let image_height = (self.image_width as Float / self.aspect_ratio) as u32;
+        (
+            self.image_width * self.projection.views(),
+            image_height.max(1),
+        )
+    }
+
+    fn initialize(&mut self) {
+        self.image_height = self.image_size().1;
 
         self.center = self.lookfrom;
-        self.pixel_samples_scale = 1.0 / f64::from(self.samples_per_pixel);
+        self.pixel_samples_scale = 1.0 / self.samples_per_pixel as Float;
 
-        let theta = degrees_to_radians(f64::from(self.vfov));
-        let h = f64::tan(theta / 2.0);
+        let theta = degrees_to_radians(self.vfov);
+        let h = Float::tan(theta / 2.0);
         let viewport_height = 2.0 * h * self.focus_dist;
         let viewport_width =
-            viewport_height * (f64::from(self.image_width) / f64::from(self.image_height));
+            viewport_height * (self.image_width as Float / self.image_height as Float);
 
         self.w = unit_vector(self.lookfrom - self.lookat);
         self.u = unit_vector(cross(self.vup, self.w));
@@ -104,8 +355,8 @@ impl Camera {
         let viewport_u = viewport_width * self.u;
         let viewport_v = viewport_height * -self.v;
 
-        self.pixel_delta_u = viewport_u / f64::from(self.image_width);
-        self.pixel_delta_v = viewport_v / f64::from(self.image_height);
+        self.pixel_delta_u = viewport_u / self.image_width as Float;
+        self.pixel_delta_v = viewport_v / self.image_height as Float;
 
         let viewport_upper_left =
             self.center - (self.focus_dist * self.w) - viewport_u / 2. - viewport_v / 2.;
@@ -116,61 +367,279 @@ impl Camera {
         self.defocus_disk_u = self.u * defocus_radius;
         self.defocus_disk_v = self.v * defocus_radius;
 
-        eprintln!(
-            "- aspect ratio {}\n- image width {}\n- image height {}",
-            self.aspect_ratio, self.image_width, self.image_height
-        );
+        if !self.quiet {
+            eprintln!(
+                "- aspect ratio {}\n- image width {}\n- image height {}",
+                self.aspect_ratio, self.image_width, self.image_height
+            );
+        }
+    }
+
+    fn sample_pixel(&self, i: u32, j: u32, world: &impl Hittable) -> Color {
+        let wavelength = self.spectral.then(sample_wavelength);
+        let Some(ray) = self.get_ray(i, j, wavelength.map(|(lambda, _)| lambda)) else {
+            return Color::new(0., 0., 0.);
+        };
+        stats::count(|s| {
+            s.primary_rays += 1;
+            s.paths += 1;
+        });
+
+        let radiance = self.ray_color(ray, world);
+        sample_to_rgb(radiance, wavelength)
     }
 
-    fn ray_color(&self, ray: &mut Ray, max_depth: u32, world: &impl Hittable) -> Color {
-        if max_depth == 0 {
+    // Traces `LANES` samples of the pixel as one packet and returns their sum. Only the primary
+    // rays travel together; the bounces after the first hit are traced one by one.
+    #[cfg(feature = "simd")]
+    fn sample_pixel_packet(&self, i: u32, j: u32, world: &impl Hittable) -> Color {
+        if self.max_depth == 0 {
             return Color::new(0., 0., 0.);
         }
 
+        let wavelengths: [_; LANES] = array::from_fn(|_| self.spectral.then(sample_wavelength));
+        let rays = wavelengths.map(|wavelength| self.get_ray(i, j, wavelength.map(|(l, _)| l)));
+        let packet = RayPacket::new(&rays);
+        stats::count(|s| {
+            s.primary_rays += u64::from(packet.active.count_ones());
+            s.paths += u64::from(packet.active.count_ones());
+        });
+
+        let mut ray_t = rays.map(|ray| {
+            let t_min = ray.map_or(0., |ray| self_intersection_epsilon(ray.origin()));
+            Interval::new(t_min, INFINITY)
+        });
+        let mut records = array::from_fn(|_| HitRecord::default());
+        let hits = world.hit_packet(&packet, packet.active, &mut ray_t, &mut records);
+
+        let mut color = Color::new(0., 0., 0.);
+        for ((k, ray), record) in rays.into_iter().enumerate().zip(records) {
+            let Some(ray) = ray else {
+                continue;
+            };
+            let radiance = self.trace_path(ray, hits & 1 << k != 0, record, world);
+            color += sample_to_rgb(radiance, wavelengths[k]);
+        }
+        color
+    }
+
+    fn ray_color(&self, ray: Ray, world: &impl Hittable) -> Color {
         let mut record = HitRecord::default();
+        let hit = self.max_depth > 0 && self.intersect(&ray, world, &mut record);
+        self.trace_path(ray, hit, record, world)
+    }
+
+    // Follows the path starting at `ray`, whose closest intersection (if `hit`) is already in
+    // `record`, and returns the radiance it carries back to the camera. After
+    // `russian_roulette_depth` bounces each path survives with a probability based on its
+    // throughput and is reweighted by it, which keeps the estimate unbiased.
+    fn trace_path(
+        &self,
+        mut ray: Ray,
+        mut hit: bool,
+        mut record: HitRecord,
+        world: &impl Hittable,
+    ) -> Color {
+        let mut radiance = Color::new(0., 0., 0.);
+        let mut throughput = Color::new(1., 1., 1.);
+        let mut scattering_pdf = 0.;
+
+        for depth in 0..self.max_depth {
+            stats::count(|s| s.path_segments += 1);
+            if depth > 0 {
+                stats::count(|s| s.secondary_rays += 1);
+                hit = self.intersect(&ray, world, &mut record);
+            }
+
+            if !hit {
+                let mut emitted =
+                    color_at_wavelength(self.environment.value(ray.direction()), ray.wavelength());
+
+                // Paths that escaped after a non-specular bounce were also reachable through
+                // `sample_environment`, so they only keep their multiple importance sampling
+                // weight.
+                if scattering_pdf > 0. {
+                    let light_pdf = self.environment.pdf(ray.direction());
+                    emitted *= power_heuristic(scattering_pdf, light_pdf);
+                }
+
+                return radiance + throughput * emitted;
+            }
+
+            record.normal = record.mat.shading_normal(&record);
+
+            // Emitters are only found by hitting them, so their light is never weighted down.
+            let emitted = record.mat.emitted(record.u, record.v, &record.p);
+            radiance += throughput * color_at_wavelength(emitted, ray.wavelength());
 
-        if world.hit(ray, Interval::new(0.001, INFINITY), &mut record) {
             let mut scattered = Ray::default();
             let mut attenuation = Color::default();
-
-            if record
+            if !record
                 .mat
-                .scatter(ray, &record, &mut attenuation, &mut scattered)
+                .scatter(&mut ray, &record, &mut attenuation, &mut scattered)
             {
-                return attenuation * self.ray_color(&mut scattered, max_depth - 1, world);
+                break;
             }
 
+            let attenuation = color_at_wavelength(attenuation, ray.wavelength());
+            scattering_pdf = record.mat.scattering_pdf(&ray, &record, &scattered);
+            radiance += throughput * self.sample_environment(&ray, &record, attenuation, world);
+            throughput = throughput * attenuation;
+            ray = scattered;
+
+            if depth + 1 >= self.russian_roulette_depth {
+                let survival = throughput
+                    .x()
+                    .max(throughput.y())
+                    .max(throughput.z())
+                    .min(0.95);
+                if random_f64() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+        }
+
+        radiance
+    }
+
+    fn intersect(&self, ray: &Ray, world: &impl Hittable, record: &mut HitRecord) -> bool {
+        let ray_t = Interval::new(self_intersection_epsilon(ray.origin()), INFINITY);
+        world.hit(ray, ray_t, record)
+    }
+
+    fn sample_environment(
+        &self,
+        ray: &Ray,
+        record: &HitRecord,
+        attenuation: Color,
+        world: &impl Hittable,
+    ) -> Color {
+        let Some((direction, light_pdf)) = self.environment.sample() else {
+            return Color::new(0., 0., 0.);
+        };
+
+        let shadow_ray = Ray::with_wavelength(record.p, direction, ray.wavelength());
+        let scattering_pdf = record.mat.scattering_pdf(ray, record, &shadow_ray);
+        if scattering_pdf <= 0. {
+            return Color::new(0., 0., 0.);
+        }
+
+        stats::count(|s| s.secondary_rays += 1);
+        if self.intersect(&shadow_ray, world, &mut HitRecord::default()) {
             return Color::new(0., 0., 0.);
         }
 
-        let unit_direction = unit_vector(*ray.direction());
-        let a = 0.5 * (unit_direction.y() + 1.0);
-        (1.0 - a) * Color::new(1.0, 1.0, 1.0) + a * Color::new(0.5, 0.7, 1.0)
+        let radiance = color_at_wavelength(self.environment.value(&direction), ray.wavelength());
+        let weight = power_heuristic(light_pdf, scattering_pdf);
+        attenuation * radiance * (scattering_pdf * weight / light_pdf)
     }
 
-    fn get_ray(&self, i: u32, j: u32) -> Ray {
+    // Returns `None` for pixels outside the image circle of the fisheye projections.
+    fn get_ray(&self, i: u32, j: u32, wavelength: Option<Float>) -> Option<Ray> {
         let offset = self.sample_square();
-        let pixel_sample = self.pixel00_loc
-            + ((f64::from(i) + offset.x()) * self.pixel_delta_u)
-            + ((f64::from(j) + offset.y()) * self.pixel_delta_v);
+        let x = i as Float + offset.x();
+        let y = j as Float + offset.y();
 
-        let ray_origin = if self.defocus_angle <= 0. {
-            self.center
-        } else {
-            self.defocus_disk_sample()
+        let (ray_origin, ray_direction) = match self.projection {
+            Projection::Perspective => {
+                let pixel_sample = self.pixel_on_focus_plane(x, y);
+                let ray_origin = self.lens_sample(self.center);
+                (ray_origin, pixel_sample - ray_origin)
+            }
+            Projection::Orthographic => {
+                let pixel_sample = self.pixel_on_focus_plane(x, y);
+                (pixel_sample + self.focus_dist * self.w, -self.w)
+            }
+            Projection::FisheyeEquidistant | Projection::FisheyeEquisolid => {
+                let half_height = self.image_height as Float / 2.;
+                let sx = (x + 0.5 - self.image_width as Float / 2.) / half_height;
+                let sy = (half_height - y - 0.5) / half_height;
+                let r = (sx * sx + sy * sy).sqrt();
+
+                let theta_max = degrees_to_radians(self.vfov) / 2.;
+                let theta = if self.projection == Projection::FisheyeEquidistant {
+                    r * theta_max
+                } else {
+                    let s = r * (theta_max / 2.).sin();
+                    if s > 1. {
+                        return None;
+                    }
+                    2. * s.asin()
+                };
+
+                if r > 1. || theta > PI {
+                    return None;
+                }
+
+                let phi = Float::atan2(sy, sx);
+                let direction =
+                    theta.sin() * (phi.cos() * self.u + phi.sin() * self.v) - theta.cos() * self.w;
+                (self.center, direction)
+            }
+            Projection::Equirectangular => {
+                let longitude = ((x + 0.5) / self.image_width as Float - 0.5) * 2. * PI;
+                let latitude = (0.5 - (y + 0.5) / self.image_height as Float) * PI;
+                let direction = latitude.cos()
+                    * (longitude.sin() * self.u - longitude.cos() * self.w)
+                    + latitude.sin() * self.v;
+                (self.center, direction)
+            }
+            Projection::Stereo => {
+                // Both eyes share the focus plane, giving an off-axis frustum per eye instead of
+                // toeing in towards `lookat`.
+                let eye = i / self.image_width;
+                let x = x - (eye * self.image_width) as Float;
+                let side = if eye == 0 { -0.5 } else { 0.5 };
+                let eye_center = self.center + side * self.eye_separation * self.u;
+
+                let pixel_sample = self.pixel_on_focus_plane(x, y);
+                let ray_origin = self.lens_sample(eye_center);
+                (ray_origin, pixel_sample - ray_origin)
+            }
         };
 
-        let ray_direction = pixel_sample - ray_origin;
+        Some(Ray::with_wavelength(ray_origin, ray_direction, wavelength))
+    }
 
-        Ray::new(ray_origin, ray_direction)
+    fn pixel_on_focus_plane(&self, x: Float, y: Float) -> Point3 {
+        self.pixel00_loc + (x * self.pixel_delta_u) + (y * self.pixel_delta_v)
+    }
+
+    fn lens_sample(&self, center: Point3) -> Point3 {
+        if self.defocus_angle <= 0. {
+            center
+        } else {
+            self.defocus_disk_sample(center)
+        }
     }
 
     fn sample_square(&self) -> Vec3 {
         Vec3::new(random_f64() - 0.5, random_f64() - 0.5, 0.)
     }
 
-    fn defocus_disk_sample(&self) -> Point3 {
+    fn defocus_disk_sample(&self, center: Point3) -> Point3 {
         let p = random_in_unit_disk();
-        self.center + (p.x() * self.defocus_disk_u) + (p.y() * self.defocus_disk_v)
+        center + (p.x() * self.defocus_disk_u) + (p.y() * self.defocus_disk_v)
+    }
+}
+
+// Turns the radiance carried by a sample into RGB. Spectral samples carry a single wavelength,
+// given together with its pdf, in the first component.
+#[inline]
+fn sample_to_rgb(radiance: Color, wavelength: Option<(Float, Float)>) -> Color {
+    match wavelength {
+        Some((lambda, pdf)) => spectral_to_rgb(radiance.x() * cie_xyz(lambda) / pdf),
+        None => radiance,
+    }
+}
+
+#[inline]
+fn power_heuristic(pdf: Float, other_pdf: Float) -> Float {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b > 0. {
+        a / (a + b)
+    } else {
+        0.
     }
 }
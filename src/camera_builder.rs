@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use crate::{
+    Camera, Environment, Float, GradientEnvironment, Point3, Projection, RenderError, Vec3,
+};
+
+pub struct CameraBuilder {
+    aspect_ratio: Float,
+    image_width: u32,
+    samples_per_pixel: u32,
+    max_depth: u32,
+    vfov: Float,
+    lookfrom: Point3,
+    lookat: Point3,
+    vup: Vec3,
+    focus_dist: Float,
+    defocus_angle: Float,
+    spectral: bool,
+    environment: Arc<dyn Environment>,
+    projection: Projection,
+    eye_separation: Float,
+    russian_roulette_depth: u32,
+    threads: usize,
+    seed: Option<u64>,
+    quiet: bool,
+}
+
+impl Default for CameraBuilder {
+    fn default() -> Self {
+        Self {
+            aspect_ratio: 16. / 9.,
+            image_width: 400,
+            samples_per_pixel: 10,
+            max_depth: 50,
+            vfov: 90.,
+            lookfrom: Point3::new(-2., 2., 1.),
+            lookat: Point3::new(0., 0., -1.),
+            vup: Vec3::new(0., 1., 0.),
+            focus_dist: 10.,
+            defocus_angle: 0.,
+            spectral: false,
+            environment: Arc::new(GradientEnvironment::default()),
+            projection: Projection::default(),
+            eye_separation: 0.065,
+            russian_roulette_depth: 5,
+            threads: 0,
+            seed: None,
+            quiet: false,
+        }
+    }
+}
+
+impl CameraBuilder {
+    #[must_use]
+    pub fn aspect_ratio(mut self, aspect_ratio: Float) -> Self {
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    #[must_use]
+    pub fn image_width(mut self, image_width: u32) -> Self {
+        self.image_width = image_width;
+        self
+    }
+
+    #[must_use]
+    pub fn samples_per_pixel(mut self, samples_per_pixel: u32) -> Self {
+        self.samples_per_pixel = samples_per_pixel;
+        self
+    }
+
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    #[must_use]
+    pub fn vfov(mut self, vfov: Float) -> Self {
+        self.vfov = vfov;
+        self
+    }
+
+    #[must_use]
+    pub fn lookfrom(mut self, lookfrom: Point3) -> Self {
+        self.lookfrom = lookfrom;
+        self
+    }
+
+    #[must_use]
+    pub fn lookat(mut self, lookat: Point3) -> Self {
+        self.lookat = lookat;
+        self
+    }
+
+    #[must_use]
+    pub fn vup(mut self, vup: Vec3) -> Self {
+        self.vup = vup;
+        self
+    }
+
+    #[must_use]
+    pub fn focus_dist(mut self, focus_dist: Float) -> Self {
+        self.focus_dist = focus_dist;
+        self
+    }
+
+    #[must_use]
+    pub fn defocus_angle(mut self, defocus_angle: Float) -> Self {
+        self.defocus_angle = defocus_angle;
+        self
+    }
+
+    #[must_use]
+    pub fn spectral(mut self, spectral: bool) -> Self {
+        self.spectral = spectral;
+        self
+    }
+
+    #[must_use]
+    pub fn environment(mut self, environment: Arc<dyn Environment>) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    #[must_use]
+    pub fn projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    #[must_use]
+    pub fn eye_separation(mut self, eye_separation: Float) -> Self {
+        self.eye_separation = eye_separation;
+        self
+    }
+
+    #[must_use]
+    pub fn russian_roulette_depth(mut self, russian_roulette_depth: u32) -> Self {
+        self.russian_roulette_depth = russian_roulette_depth;
+        self
+    }
+
+    #[must_use]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    #[must_use]
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    #[must_use]
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    pub fn build(self) -> Result<Camera, RenderError> {
+        let mut camera = Camera::default();
+        camera.aspect_ratio = self.aspect_ratio;
+        camera.image_width = self.image_width;
+        camera.samples_per_pixel = self.samples_per_pixel;
+        camera.max_depth = self.max_depth;
+        camera.vfov = self.vfov;
+        camera.lookfrom = self.lookfrom;
+        camera.lookat = self.lookat;
+        camera.vup = self.vup;
+        camera.focus_dist = self.focus_dist;
+        camera.defocus_angle = self.defocus_angle;
+        camera.spectral = self.spectral;
+        camera.environment = self.environment;
+        camera.projection = self.projection;
+        camera.eye_separation = self.eye_separation;
+        camera.russian_roulette_depth = self.russian_roulette_depth;
+        camera.threads = self.threads;
+        camera.seed = self.seed;
+        camera.quiet = self.quiet;
+
+        camera.validate()?;
+        Ok(camera)
+    }
+}
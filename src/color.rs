@@ -1,29 +1,41 @@
-use crate::{Interval, Vec3};
+use std::io::{self, Write};
 
-pub type Color = Vec3;
+use crate::{Float, Interval, Vec3};
 
-pub fn write_color(pixel_color: Color) {
-    let r = pixel_color.x();
-    let g = pixel_color.y();
-    let b = pixel_color.z();
+pub type Color = Vec3;
 
-    let r = linear_to_gamma(r);
-    let g = linear_to_gamma(g);
-    let b = linear_to_gamma(b);
+pub fn write_color(out: &mut impl Write, pixel_color: Color) -> io::Result<()> {
+    let [rbyte, gbyte, bbyte] = to_rgb8(pixel_color);
+    writeln!(out, "{rbyte}  {gbyte}  {bbyte}")
+}
 
+// Gamma-encoded 8-bit components, as written to the images.
+#[inline]
+pub fn to_rgb8(pixel_color: Color) -> [u8; 3] {
     let intensity = Interval::new(0.000, 0.999);
-
-    let rbyte = (256. * intensity.clamp(r)) as u64;
-    let gbyte = (256. * intensity.clamp(g)) as u64;
-    let bbyte = (256. * intensity.clamp(b)) as u64;
-
-    println!("{rbyte}  {gbyte}  {bbyte}");
+    let byte = |component: Float| (256. * intensity.clamp(linear_to_gamma(component))) as u8;
+    [
+        byte(pixel_color.x()),
+        byte(pixel_color.y()),
+        byte(pixel_color.z()),
+    ]
 }
 
 #[inline]
-pub fn linear_to_gamma(linear_component: f64) -> f64 {
+pub fn linear_to_gamma(linear_component: Float) -> Float {
     if linear_component > 0. {
         return linear_component.sqrt();
     }
     0.
 }
+
+// The inverse of `linear_to_gamma`, for 8-bit colors read from files.
+#[inline]
+pub fn gamma_to_linear(gamma_component: Float) -> Float {
+    gamma_component * gamma_component
+}
+
+#[inline]
+pub fn luminance(color: Color) -> Float {
+    0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z()
+}
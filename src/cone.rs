@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use crate::{
+    around, closest_boundary, ensure_finite, flat, local_ray, solve_quadratic, spans_between,
+    stats, unit_vector, validate_radius, Aabb, Context, Float, HitRecord, Hittable, Interval,
+    Material, Onb, Point3, Ray, RenderError, Span, Vec3,
+};
+
+// A solid cone standing on a disk of `radius` around `base`, narrowing to a point at `apex`. It
+// is mapped like a cylinder.
+pub struct Cone {
+    base: Point3,
+    height: Float,
+    radius: Float,
+    frame: Onb,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point3,
+        apex: Point3,
+        radius: Float,
+        mat: Arc<dyn Material>,
+    ) -> Result<Self, RenderError> {
+        ensure_finite(base, "cone base")?;
+        ensure_finite(apex, "cone apex")?;
+        validate_radius(radius, "cone radius")?;
+        let axis = apex - base;
+        if axis.near_zero() {
+            return Err(RenderError::DegenerateAxis("cone"));
+        }
+
+        let frame = Onb::new(axis);
+        let extent = frame.circle_extent(radius);
+        let bbox = Aabb::surrounding(
+            &Aabb::from_points(base - extent, base + extent),
+            &Aabb::from_points(apex, apex),
+        );
+        Ok(Self {
+            base,
+            height: axis.length(),
+            radius,
+            frame,
+            mat,
+            bbox,
+        })
+    }
+
+    fn boundaries(&self, ray: &Ray) -> Vec<HitRecord> {
+        let (o, d) = local_ray(&self.frame, self.base, ray);
+        let mut boundaries = Vec::new();
+        let mut add = |t: Float, normal: Vec3, uv: (Float, Float)| {
+            boundaries.push(HitRecord::on_surface(
+                ray,
+                t,
+                self.frame.to_world(normal),
+                uv,
+                &self.mat,
+            ));
+        };
+
+        // The radius at height z is s (h - z).
+        let s2 = (self.radius / self.height).powi(2);
+        let below_apex = self.height - o.z();
+        let a = d.x() * d.x() + d.y() * d.y() - s2 * d.z() * d.z();
+        let b = 2. * (o.x() * d.x() + o.y() * d.y() + s2 * below_apex * d.z());
+        let c = o.x() * o.x() + o.y() * o.y() - s2 * below_apex * below_apex;
+        if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+            // Along the slope the equation turns linear and has a single root.
+            let roots = if t0 == t1 { 1 } else { 2 };
+            for t in [t0, t1].into_iter().take(roots) {
+                let p = o + t * d;
+                if (0. ..=self.height).contains(&p.z()) {
+                    let normal = Vec3::new(p.x(), p.y(), s2 * (self.height - p.z()));
+                    let normal = if normal.near_zero() {
+                        Vec3::new(0., 0., 1.)
+                    } else {
+                        unit_vector(normal)
+                    };
+                    add(t, normal, (around(p), p.z() / self.height));
+                }
+            }
+        }
+
+        if d.z() != 0. {
+            let t = -o.z() / d.z();
+            let p = o + t * d;
+            if p.x() * p.x() + p.y() * p.y() <= self.radius * self.radius {
+                add(t, Vec3::new(0., 0., -1.), flat(p, self.radius));
+            }
+        }
+        boundaries
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        closest_boundary(&spans_between(self.boundaries(ray)), ray, ray_t, record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        Some(spans_between(self.boundaries(ray)))
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("cone material")
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches, Command, Parser};
+
+use crate::{Context, RenderError};
+
+const ENV_PREFIX: &str = "RAYTRACING_";
+const PROJECT_CONFIG: &str = "raytracing.toml";
+
+// Where the value of an option came from, from the lowest to the highest precedence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    ConfigFile(PathBuf),
+    Environment(String),
+    CommandLine,
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::ConfigFile(path) => write!(f, "config file {}", path.display()),
+            Origin::Environment(name) => write!(f, "environment variable {name}"),
+            Origin::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResolvedValue {
+    pub id: String,
+    pub name: String,
+    pub values: Vec<String>,
+    pub multiple: bool,
+    pub origin: Origin,
+}
+
+// The options of one command after layering, printed by `--print-config` as a config file section.
+#[derive(Clone, Debug, Default)]
+pub struct ResolvedConfig {
+    pub section: String,
+    pub values: Vec<ResolvedValue>,
+}
+
+impl ResolvedConfig {
+    pub fn origin(&self, id: &str) -> Option<&Origin> {
+        self.values.iter().find(|v| v.id == id).map(|v| &v.origin)
+    }
+
+    // Whether the option was set anywhere rather than left at its default.
+    pub fn is_explicit(&self, id: &str) -> bool {
+        self.origin(id)
+            .is_some_and(|origin| *origin != Origin::Default)
+    }
+}
+
+impl Display for ResolvedConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[{}]", self.section)?;
+        for value in &self.values {
+            let values: Vec<_> = value.values.iter().map(|v| toml_literal(v)).collect();
+            let literal = match (value.multiple, values.as_slice()) {
+                (true, _) => format!("[{}]", values.join(", ")),
+                (false, [single]) => single.clone(),
+                // Options like `--stats` can be given without a value, which a file cannot express.
+                (false, _) => {
+                    writeln!(
+                        f,
+                        "# {} is set without a value # {}",
+                        value.name, value.origin
+                    )?;
+                    continue;
+                }
+            };
+            writeln!(f, "{} = {literal} # {}", value.name, value.origin)?;
+        }
+        Ok(())
+    }
+}
+
+// Command line arguments layered over defaults, config files and environment variables.
+pub struct Layered<T> {
+    pub args: T,
+    pub resolved: ResolvedConfig,
+    pub print_config: bool,
+}
+
+// A config file holds one table per subcommand, keyed by the long option names:
+//
+//     [render]
+//     image-width = 1920
+//     look-from = [13, 2, 3]
+struct ConfigFile {
+    path: PathBuf,
+    sections: toml::Table,
+}
+
+impl ConfigFile {
+    fn load(path: &Path) -> Result<Self, RenderError> {
+        let text = fs::read_to_string(path)?;
+        let sections =
+            toml::from_str(&text).map_err(|e| RenderError::InvalidConfig(e.to_string()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            sections,
+        })
+    }
+
+    // The file given with `--config` or `RAYTRACING_CONFIG`. Otherwise the user's config file,
+    // followed by the project's, which takes precedence where both set an option.
+    fn discover(explicit: Option<PathBuf>) -> Result<Vec<Self>, RenderError> {
+        if let Some(path) = explicit {
+            let file =
+                Self::load(&path).with_context(|| format!("failed to read {}", path.display()))?;
+            return Ok(vec![file]);
+        }
+
+        let user_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+        let candidates = user_dir
+            .map(|dir| dir.join("raytracing").join("config.toml"))
+            .into_iter()
+            .chain([PathBuf::from(PROJECT_CONFIG)]);
+
+        candidates
+            .filter(|path| path.is_file())
+            .map(|path| {
+                Self::load(&path).with_context(|| format!("failed to read {}", path.display()))
+            })
+            .collect()
+    }
+
+    // Checked up front, so that a misspelled option is not silently ignored.
+    fn check(&self, cmd: &Command, root_section: &str) -> Result<(), RenderError> {
+        for (section, table) in &self.sections {
+            let command = if cmd.has_subcommands() {
+                cmd.find_subcommand(section)
+            } else {
+                (section == root_section).then_some(cmd)
+            };
+            let Some(command) = command else {
+                return Err(RenderError::InvalidConfig(format!(
+                    "unknown section [{section}]"
+                )))
+                .with_context(|| self.path.display().to_string());
+            };
+            let toml::Value::Table(table) = table else {
+                return Err(RenderError::InvalidConfig(format!(
+                    "'{section}' must be a section"
+                )))
+                .with_context(|| self.path.display().to_string());
+            };
+
+            for (name, value) in table {
+                let known = command
+                    .get_arguments()
+                    .any(|arg| is_layered(arg) && arg.get_long() == Some(name));
+                if !known {
+                    return Err(RenderError::InvalidConfig(format!(
+                        "unknown option '{name}' in [{section}]"
+                    )))
+                    .with_context(|| self.path.display().to_string());
+                }
+                if config_values(value).is_none() {
+                    return Err(RenderError::InvalidConfig(format!(
+                        "'{name}' in [{section}] must be a string, a number, a boolean or an array of them"
+                    )))
+                    .with_context(|| self.path.display().to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Parses the command line of `T`. Options not given there are taken from `RAYTRACING_*`
+// environment variables, then from the config files, then from their defaults. `root_section`
+// names the config section of commands without subcommands.
+pub fn parse_layered<T: Parser>(root_section: &str) -> Result<Layered<T>, RenderError> {
+    let args: Vec<OsString> = env::args_os().collect();
+    let files = ConfigFile::discover(config_path(&args))?;
+
+    let mut cmd = T::command()
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("TOML_FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .env("RAYTRACING_CONFIG")
+                .global(true),
+        )
+        .arg(
+            Arg::new("print-config")
+                .long("print-config")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        );
+    for file in &files {
+        file.check(&cmd, root_section)?;
+    }
+
+    // Values from config files become the defaults, later files overriding earlier ones.
+    let mut configured = BTreeMap::new();
+    let sections: Vec<String> = if cmd.has_subcommands() {
+        cmd.get_subcommands()
+            .map(|sub| sub.get_name().to_string())
+            .collect()
+    } else {
+        vec![root_section.to_string()]
+    };
+    for section in &sections {
+        let layer = |command: Command| {
+            let mut origins = BTreeMap::new();
+            let command = command.mut_args(|arg| layer_arg(arg, section, &files, &mut origins));
+            (command, origins)
+        };
+        if cmd.has_subcommands() {
+            let mut origins = BTreeMap::new();
+            cmd = cmd.mut_subcommand(section, |sub| {
+                let (sub, found) = layer(sub);
+                origins = found;
+                sub
+            });
+            configured.insert(section.clone(), origins);
+        } else {
+            let (layered, origins) = layer(cmd);
+            cmd = layered;
+            configured.insert(section.clone(), origins);
+        }
+    }
+
+    let matches = cmd.clone().get_matches_from(args);
+    let parsed = T::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    let (section, command, sub_matches) = match matches.subcommand() {
+        Some((name, sub_matches)) => (
+            name.to_string(),
+            cmd.find_subcommand(name)
+                .expect("matched subcommands exist"),
+            sub_matches,
+        ),
+        None => (root_section.to_string(), &cmd, &matches),
+    };
+    let empty = BTreeMap::new();
+    let configured = configured.get(&section).unwrap_or(&empty);
+    let resolved = ResolvedConfig {
+        values: resolve(command, sub_matches, configured),
+        section,
+    };
+
+    Ok(Layered {
+        args: parsed,
+        resolved,
+        print_config: matches.get_flag("print-config"),
+    })
+}
+
+// Options with a long name are layered; positional arguments only come from the command line.
+fn is_layered(arg: &Arg) -> bool {
+    !arg.is_global_set() && arg.get_long().is_some()
+}
+
+// Looked up before parsing, since the config file decides the defaults the parser uses.
+fn config_path(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--config" {
+            return args.next().map(|path| PathBuf::from(path.as_ref()));
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    env::var_os("RAYTRACING_CONFIG").map(PathBuf::from)
+}
+
+fn layer_arg(
+    mut arg: Arg,
+    section: &str,
+    files: &[ConfigFile],
+    origins: &mut BTreeMap<String, PathBuf>,
+) -> Arg {
+    if !is_layered(&arg) {
+        return arg;
+    }
+    let name = arg.get_long().unwrap_or_default().to_string();
+    arg = arg.env(format!(
+        "{ENV_PREFIX}{}",
+        name.to_uppercase().replace('-', "_")
+    ));
+
+    let multiple = matches!(arg.get_action(), ArgAction::Append);
+    for file in files {
+        let Some(value) = file
+            .sections
+            .get(section)
+            .and_then(|table| table.get(&name))
+        else {
+            continue;
+        };
+        let Some(values) = config_values(value) else {
+            continue;
+        };
+
+        // An array for a single valued option is a vector, as in scene files.
+        arg = if multiple {
+            arg.default_values(values)
+        } else {
+            arg.default_value(values.join(","))
+        }
+        .required(false);
+        origins.insert(arg.get_id().to_string(), file.path.clone());
+    }
+    arg
+}
+
+fn config_values(value: &toml::Value) -> Option<Vec<String>> {
+    match value {
+        toml::Value::String(s) => Some(vec![s.clone()]),
+        toml::Value::Integer(i) => Some(vec![i.to_string()]),
+        toml::Value::Float(x) => Some(vec![x.to_string()]),
+        toml::Value::Boolean(b) => Some(vec![b.to_string()]),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                toml::Value::Array(_) | toml::Value::Table(_) => None,
+                item => config_values(item).map(|mut v| v.remove(0)),
+            })
+            .collect(),
+        toml::Value::Datetime(_) | toml::Value::Table(_) => None,
+    }
+}
+
+fn resolve(
+    command: &Command,
+    matches: &ArgMatches,
+    configured: &BTreeMap<String, PathBuf>,
+) -> Vec<ResolvedValue> {
+    command
+        .get_arguments()
+        .filter(|arg| is_layered(arg))
+        .filter_map(|arg| {
+            let id = arg.get_id().as_str();
+            let origin = match matches.value_source(id)? {
+                ValueSource::DefaultValue => match configured.get(id) {
+                    Some(path) => Origin::ConfigFile(path.clone()),
+                    None => Origin::Default,
+                },
+                ValueSource::EnvVariable => Origin::Environment(
+                    arg.get_env()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                ),
+                _ => Origin::CommandLine,
+            };
+            let values = matches
+                .get_raw(id)?
+                .map(|v| v.to_string_lossy().into_owned())
+                .collect();
+            Some(ResolvedValue {
+                id: id.to_string(),
+                name: arg.get_long().unwrap_or_default().to_string(),
+                values,
+                multiple: matches!(arg.get_action(), ArgAction::Append),
+                origin,
+            })
+        })
+        .collect()
+}
+
+// Numbers and booleans as they are, anything else as a string.
+fn toml_literal(value: &str) -> String {
+    let number = value.parse::<i64>().is_ok() || value.parse::<f64>().is_ok_and(f64::is_finite);
+    if number || value == "true" || value == "false" {
+        value.to_string()
+    } else {
+        toml::Value::String(value.to_string()).to_string()
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::{
+    random_f64, Aabb, Color, Context, Float, HitRecord, Hittable, Interval, Isotropic, Material,
+    Ray, RenderError, Texture, Vec3, INFINITY,
+};
+
+// A volume of uniform density filling a convex boundary, like smoke or fog. Rays travel an
+// exponentially distributed distance through it before scattering.
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable>,
+    neg_inv_density: Float,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Arc<dyn Hittable>, density: Float, albedo: Color) -> Self {
+        Self::with_phase_function(boundary, density, Arc::new(Isotropic::new(albedo)))
+    }
+
+    pub fn from_texture(
+        boundary: Arc<dyn Hittable>,
+        density: Float,
+        texture: Arc<dyn Texture>,
+    ) -> Self {
+        Self::with_phase_function(
+            boundary,
+            density,
+            Arc::new(Isotropic::from_texture(texture)),
+        )
+    }
+
+    fn with_phase_function(
+        boundary: Arc<dyn Hittable>,
+        density: Float,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1. / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        let mut entry = HitRecord::default();
+        let mut exit = HitRecord::default();
+        if !self.boundary.hit(ray, Interval::UNIVERSE, &mut entry) {
+            return false;
+        }
+        if !self
+            .boundary
+            .hit(ray, Interval::new(entry.t + 0.0001, INFINITY), &mut exit)
+        {
+            return false;
+        }
+
+        let t_min = entry.t.max(ray_t.min).max(0.);
+        let t_max = exit.t.min(ray_t.max);
+        if t_min >= t_max {
+            return false;
+        }
+
+        let ray_length = ray.direction().length();
+        let distance_inside = (t_max - t_min) * ray_length;
+        let hit_distance = self.neg_inv_density * random_f64().ln();
+        if hit_distance > distance_inside {
+            return false;
+        }
+
+        record.t = t_min + hit_distance / ray_length;
+        record.p = ray.at(record.t);
+        // Neither is meaningful inside a volume.
+        record.normal = Vec3::new(1., 0., 0.);
+        record.front_face = true;
+        record.mat = self.phase_function.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        if !self.neg_inv_density.is_finite() || self.neg_inv_density >= 0. {
+            return Err(RenderError::InvalidDensity(-1. / self.neg_inv_density));
+        }
+        self.boundary.validate().context("medium boundary")?;
+        self.phase_function.validate().context("medium")
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use crate::{
+    closest_boundary, Aabb, Context, HitRecord, Hittable, Interval, Point3, Ray, RenderError, Span,
+    Vec3,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Operation {
+    fn contains(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            Operation::Union => in_left || in_right,
+            Operation::Intersection => in_left && in_right,
+            Operation::Difference => in_left && !in_right,
+        }
+    }
+
+    fn bounding_box(self, left: &Aabb, right: &Aabb) -> Aabb {
+        let overlap =
+            |a: &Interval, b: &Interval| Interval::new(a.min.max(b.min), a.max.min(b.max));
+        match self {
+            Operation::Union => Aabb::surrounding(left, right),
+            Operation::Intersection => {
+                let (x, y, z) = (
+                    overlap(&left.x, &right.x),
+                    overlap(&left.y, &right.y),
+                    overlap(&left.z, &right.z),
+                );
+                if x.size() < 0. || y.size() < 0. || z.size() < 0. {
+                    Aabb::EMPTY
+                } else {
+                    Aabb::new(x, y, z)
+                }
+            }
+            Operation::Difference => *left,
+        }
+    }
+
+    // Sweeps the boundaries of both operands along the ray and keeps the ones where being inside
+    // the result changes.
+    fn combine(self, left: &[Span], right: &[Span]) -> Vec<Span> {
+        let boundaries = |spans: &[Span], is_left: bool| -> Vec<(HitRecord, bool, bool)> {
+            spans
+                .iter()
+                .flat_map(|span| {
+                    [
+                        (span.entry.clone(), is_left, true),
+                        (span.exit.clone(), is_left, false),
+                    ]
+                })
+                .collect()
+        };
+        let mut events = boundaries(left, true);
+        events.extend(boundaries(right, false));
+        events.sort_by(|a, b| a.0.t.total_cmp(&b.0.t));
+
+        let mut spans = Vec::new();
+        let (mut in_left, mut in_right) = (false, false);
+        let mut left_material = None;
+        let mut entry = None;
+        for (mut boundary, is_left, entering) in events {
+            let was_inside = self.contains(in_left, in_right);
+            if is_left {
+                in_left = entering;
+                if entering {
+                    left_material = Some(boundary.mat.clone());
+                }
+            } else {
+                in_right = entering;
+            }
+            let inside = self.contains(in_left, in_right);
+            if inside == was_inside {
+                continue;
+            }
+
+            // A surface of the right operand carved out of the left one faces into the hole and is
+            // made of the material it was carved from.
+            if self == Operation::Difference && !is_left {
+                boundary.normal = -boundary.normal;
+                if let Some(mat) = &left_material {
+                    boundary.mat = mat.clone();
+                }
+            }
+
+            if inside {
+                entry = Some(boundary);
+            } else if let Some(entry) = entry.take() {
+                spans.push(Span {
+                    entry,
+                    exit: boundary,
+                });
+            }
+        }
+        spans
+    }
+}
+
+// Only objects that report their spans have an inside to combine. A miss still reports an empty
+// list, so a single ray through the bounding box tells them apart.
+fn check_closed(object: &dyn Hittable, operand: &'static str) -> Result<(), RenderError> {
+    let bbox = object.bounding_box();
+    let center = Point3::new(
+        (bbox.x.min + bbox.x.max) / 2.,
+        (bbox.y.min + bbox.y.max) / 2.,
+        (bbox.z.min + bbox.z.max) / 2.,
+    );
+    match object.spans(&Ray::new(center, Vec3::new(1., 0., 0.))) {
+        Some(_) => Ok(()),
+        None => Err(RenderError::OpenCsgOperand(operand)),
+    }
+}
+
+macro_rules! csg {
+    ($name:ident => $operation:expr) => {
+        pub struct $name {
+            left: Arc<dyn Hittable>,
+            right: Arc<dyn Hittable>,
+            bbox: Aabb,
+        }
+
+        impl $name {
+            pub fn new(
+                left: Arc<dyn Hittable>,
+                right: Arc<dyn Hittable>,
+            ) -> Result<Self, RenderError> {
+                check_closed(left.as_ref(), "left")?;
+                check_closed(right.as_ref(), "right")?;
+                let bbox = $operation.bounding_box(&left.bounding_box(), &right.bounding_box());
+                Ok(Self { left, right, bbox })
+            }
+        }
+
+        impl Hittable for $name {
+            fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+                if !self.bbox.hit(ray, ray_t) {
+                    return false;
+                }
+                let spans = self.spans(ray).unwrap_or_default();
+                closest_boundary(&spans, ray, ray_t, record)
+            }
+
+            fn bounding_box(&self) -> Aabb {
+                self.bbox
+            }
+
+            fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+                let left = self.left.spans(ray)?;
+                let right = self.right.spans(ray)?;
+                Some($operation.combine(&left, &right))
+            }
+
+            fn validate(&self) -> Result<(), RenderError> {
+                self.left.validate().context("left operand")?;
+                self.right.validate().context("right operand")
+            }
+        }
+    };
+}
+
+// Everything inside either operand.
+csg!(Union => Operation::Union);
+// Everything inside both operands, like a lens made of two spheres.
+csg!(Intersection => Operation::Intersection);
+// The left operand with the right one cut out of it.
+csg!(Difference => Operation::Difference);
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::{
+    closest_boundary, ensure_finite, stats, Aabb, Context, Float, HitRecord, Hittable, Interval,
+    Material, Point3, Ray, RenderError, Span,
+};
+
+// A solid axis-aligned box. Unlike the six quads of `make_box` it is closed, so it can take part
+// in CSG operations.
+pub struct Cuboid {
+    min: Point3,
+    max: Point3,
+    mat: Arc<dyn Material>,
+}
+
+impl Cuboid {
+    pub fn new(a: Point3, b: Point3, mat: Arc<dyn Material>) -> Result<Self, RenderError> {
+        ensure_finite(a, "box corner")?;
+        ensure_finite(b, "box corner")?;
+        Ok(Self {
+            min: Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z())),
+            max: Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z())),
+            mat,
+        })
+    }
+
+    // The face on `axis` at `side` (0 for the min side, 1 for the max side) hit at `t`.
+    fn boundary(&self, ray: &Ray, t: Float, axis: usize, side: usize) -> HitRecord {
+        let mut record = HitRecord {
+            t,
+            p: ray.at(t),
+            mat: self.mat.clone(),
+            ..HitRecord::default()
+        };
+        record.normal[axis] = if side == 0 { -1. } else { 1. };
+
+        // The face coordinates run along the other two axes.
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        let along = |i: usize| {
+            let extent = self.max[i] - self.min[i];
+            if extent > 0. {
+                (record.p[i] - self.min[i]) / extent
+            } else {
+                0.
+            }
+        };
+        (record.u, record.v) = (along(a), along(b));
+        record
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        let spans = self.spans(ray).unwrap_or_default();
+        closest_boundary(&spans, ray, ray_t, record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(self.min, self.max)
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        stats::count(|s| s.intersection_tests += 1);
+        let origin = ray.origin();
+        let direction = ray.direction();
+
+        let mut near = (Float::NEG_INFINITY, 0, 0);
+        let mut far = (Float::INFINITY, 0, 0);
+        for axis in 0..3 {
+            if direction[axis] == 0. {
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return Some(Vec::new());
+                }
+                continue;
+            }
+
+            let inv = 1. / direction[axis];
+            let t_min = (self.min[axis] - origin[axis]) * inv;
+            let t_max = (self.max[axis] - origin[axis]) * inv;
+            let (t0, t1, side0, side1) = if t_min < t_max {
+                (t_min, t_max, 0, 1)
+            } else {
+                (t_max, t_min, 1, 0)
+            };
+            if t0 > near.0 {
+                near = (t0, axis, side0);
+            }
+            if t1 < far.0 {
+                far = (t1, axis, side1);
+            }
+        }
+
+        if near.0 >= far.0 || !near.0.is_finite() || !far.0.is_finite() {
+            return Some(Vec::new());
+        }
+        Some(vec![Span {
+            entry: self.boundary(ray, near.0, near.1, near.2),
+            exit: self.boundary(ray, far.0, far.1, far.2),
+        }])
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("box material")
+    }
+}
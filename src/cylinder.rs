@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use crate::{
+    closest_boundary, ensure_finite, solve_quadratic, spans_between, stats, validate_radius, Aabb,
+    Context, Float, HitRecord, Hittable, Interval, Material, Onb, Point3, Ray, RenderError, Span,
+    Vec3, PI,
+};
+
+// A solid cylinder from `base` to `top`, closed by flat caps. Its texture coordinates run around
+// the side and up from the base; the caps are mapped flat.
+pub struct Cylinder {
+    base: Point3,
+    height: Float,
+    radius: Float,
+    frame: Onb,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Cylinder {
+    pub fn new(
+        base: Point3,
+        top: Point3,
+        radius: Float,
+        mat: Arc<dyn Material>,
+    ) -> Result<Self, RenderError> {
+        ensure_finite(base, "cylinder base")?;
+        ensure_finite(top, "cylinder top")?;
+        validate_radius(radius, "cylinder radius")?;
+        let axis = top - base;
+        if axis.near_zero() {
+            return Err(RenderError::DegenerateAxis("cylinder"));
+        }
+
+        let frame = Onb::new(axis);
+        let extent = frame.circle_extent(radius);
+        let bbox = Aabb::surrounding(
+            &Aabb::from_points(base - extent, base + extent),
+            &Aabb::from_points(top - extent, top + extent),
+        );
+        Ok(Self {
+            base,
+            height: axis.length(),
+            radius,
+            frame,
+            mat,
+            bbox,
+        })
+    }
+
+    // Every surface along the line of `ray`, with outward normals.
+    fn boundaries(&self, ray: &Ray) -> Vec<HitRecord> {
+        let (o, d) = local_ray(&self.frame, self.base, ray);
+        let mut boundaries = Vec::new();
+        let mut add = |t: Float, normal: Vec3, uv: (Float, Float)| {
+            boundaries.push(HitRecord::on_surface(
+                ray,
+                t,
+                self.frame.to_world(normal),
+                uv,
+                &self.mat,
+            ));
+        };
+
+        let a = d.x() * d.x() + d.y() * d.y();
+        let b = 2. * (o.x() * d.x() + o.y() * d.y());
+        let c = o.x() * o.x() + o.y() * o.y() - self.radius * self.radius;
+        if a > 0. {
+            if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+                for t in [t0, t1] {
+                    let p = o + t * d;
+                    if (0. ..=self.height).contains(&p.z()) {
+                        let normal = Vec3::new(p.x(), p.y(), 0.) / self.radius;
+                        add(t, normal, (around(p), p.z() / self.height));
+                    }
+                }
+            }
+        }
+
+        if d.z() != 0. {
+            for (z, side) in [(0., -1.), (self.height, 1.)] {
+                let t = (z - o.z()) / d.z();
+                let p = o + t * d;
+                if p.x() * p.x() + p.y() * p.y() <= self.radius * self.radius {
+                    add(t, Vec3::new(0., 0., side), flat(p, self.radius));
+                }
+            }
+        }
+        boundaries
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        closest_boundary(&spans_between(self.boundaries(ray)), ray, ray_t, record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        Some(spans_between(self.boundaries(ray)))
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("cylinder material")
+    }
+}
+
+// The ray in the frame of a primitive placed at `origin`. Directions keep their length, so the ray
+// parameter means the same in both frames.
+pub(crate) fn local_ray(frame: &Onb, origin: Point3, ray: &Ray) -> (Point3, Vec3) {
+    (
+        frame.to_local(*ray.origin() - origin),
+        frame.to_local(*ray.direction()),
+    )
+}
+
+// The angle around the local z axis, from 0 to 1.
+pub(crate) fn around(p: Point3) -> Float {
+    (Float::atan2(p.y(), p.x()) + PI) / (2. * PI)
+}
+
+// Maps a point of a disk of `radius` around the local z axis onto the unit square.
+pub(crate) fn flat(p: Point3, radius: Float) -> (Float, Float) {
+    (0.5 + 0.5 * p.x() / radius, 0.5 + 0.5 * p.y() / radius)
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use crate::{
+    around, ensure_finite, local_ray, stats, validate_radius, Aabb, Context, Float, HitRecord,
+    Hittable, Interval, Material, Onb, Point3, Ray, RenderError, Vec3, PI,
+};
+
+// A flat disk facing `normal`, with a hole of `inner_radius` in the middle when it is not zero.
+// Its texture coordinates are the angle around the center and the distance out from the hole.
+pub struct Disk {
+    center: Point3,
+    outer_radius: Float,
+    inner_radius: Float,
+    frame: Onb,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Disk {
+    pub fn new(
+        center: Point3,
+        normal: Vec3,
+        outer_radius: Float,
+        inner_radius: Float,
+        mat: Arc<dyn Material>,
+    ) -> Result<Self, RenderError> {
+        ensure_finite(center, "disk center")?;
+        ensure_finite(normal, "disk normal")?;
+        validate_radius(outer_radius, "disk radius")?;
+        validate_radius(inner_radius, "disk inner radius")?;
+        if normal.near_zero() {
+            return Err(RenderError::DegenerateAxis("disk"));
+        }
+
+        let frame = Onb::new(normal);
+        let extent = frame.circle_extent(outer_radius);
+        Ok(Self {
+            center,
+            outer_radius,
+            inner_radius: inner_radius.min(outer_radius),
+            frame,
+            mat,
+            bbox: Aabb::from_points(center - extent, center + extent),
+        })
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        let (o, d) = local_ray(&self.frame, self.center, ray);
+        if d.z().abs() < 1e-8 {
+            return false;
+        }
+
+        let t = -o.z() / d.z();
+        if !ray_t.contains(t) {
+            return false;
+        }
+        let p = o + t * d;
+        let r = (p.x() * p.x() + p.y() * p.y()).sqrt();
+        if r < self.inner_radius || r > self.outer_radius {
+            return false;
+        }
+
+        let width = self.outer_radius - self.inner_radius;
+        record.t = t;
+        record.p = ray.at(t);
+        record.u = around(p);
+        record.v = if width > 0. {
+            (r - self.inner_radius) / width
+        } else {
+            0.
+        };
+        // u runs once around the center and v across the ring.
+        let radial = if r > 0. {
+            Vec3::new(p.x() / r, p.y() / r, 0.)
+        } else {
+            Vec3::new(1., 0., 0.)
+        };
+        record.dpdu = self.frame.to_world(2. * PI * Vec3::new(-p.y(), p.x(), 0.));
+        record.dpdv = self.frame.to_world(width * radial);
+        record.mat = self.mat.clone();
+        record.set_face_normal(ray, &self.frame.w());
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("disk material")
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Condvar, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BvhNode, Camera, CameraBuilder, CameraSettings, Color, Context, CoordinatorArgs, Framebuffer,
+    Hittable, RenderError, SceneFile, Tile, WorkerArgs,
+};
+
+// Coordinator and workers exchange one JSON message per line. The coordinator sends the scene
+// once, then one `render` per tile, and waits for the `rendered` answer before sending the next.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Message {
+    Scene { scene: Box<SceneFile> },
+    Render { tile: Tile },
+    Rendered { tile: Tile, pixels: Vec<Color> },
+    Error { message: String },
+}
+
+fn send(out: &mut impl Write, message: &Message) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, message)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+fn receive(input: &mut impl BufRead) -> io::Result<Message> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the connection was closed",
+        ));
+    }
+    Ok(serde_json::from_str(&line)?)
+}
+
+fn unexpected(expected: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("expected a '{expected}' message"),
+    )
+}
+
+// Serves coordinators until the process is killed, each connection on its own thread.
+pub fn run_worker(args: &WorkerArgs) -> Result<(), RenderError> {
+    let listener = TcpListener::bind(&args.address)
+        .with_context(|| format!("failed to listen on {}", args.address))?;
+    eprintln!("listening on {}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("failed to accept a connection: {e}");
+                continue;
+            }
+        };
+        let threads = args.threads;
+        thread::spawn(move || {
+            let peer = stream
+                .peer_addr()
+                .map_or_else(|_| "unknown".to_string(), |peer| peer.to_string());
+            if let Err(e) = serve_coordinator(stream, threads) {
+                eprintln!("coordinator {peer}: {e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn serve_coordinator(stream: TcpStream, threads: usize) -> Result<(), RenderError> {
+    let mut input = BufReader::new(stream.try_clone()?);
+    let mut output = BufWriter::new(stream);
+
+    let Message::Scene { scene } = receive(&mut input)? else {
+        return Err(unexpected("scene").into());
+    };
+    let setup = || -> Result<(Camera, BvhNode), RenderError> {
+        let builder = CameraBuilder::default().threads(threads).quiet(true);
+        let cam = scene.camera(builder, &CameraSettings::default())?;
+        let world = scene.build().context("failed to build the scene")?;
+        world.validate().context("invalid scene")?;
+        Ok((cam, BvhNode::new(world)))
+    };
+    let (mut cam, world) = match setup() {
+        Ok(setup) => setup,
+        Err(e) => {
+            let message = e.to_string();
+            send(&mut output, &Message::Error { message })?;
+            return Err(e);
+        }
+    };
+
+    let (_, height) = cam.image_size();
+    loop {
+        let tile = match receive(&mut input) {
+            Ok(Message::Render { tile }) => tile,
+            Ok(_) => return Err(unexpected("render").into()),
+            // The coordinator hangs up once every tile is done.
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        if tile.height == 0
+            || tile
+                .y
+                .checked_add(tile.height)
+                .is_none_or(|end| end > height)
+        {
+            let message = format!("{tile:?} lies outside the {height} rows of the image");
+            send(&mut output, &Message::Error { message })?;
+            continue;
+        }
+
+        let image = cam.render_tile(&world, tile);
+        let pixels = image.pixels().to_vec();
+        send(&mut output, &Message::Rendered { tile, pixels })?;
+    }
+}
+
+// Renders the scene file on the given workers. Every worker pulls tiles from a shared queue;
+// when one fails, its tile goes back to the queue for the others, and the render only fails once
+// no worker is left.
+pub fn run_coordinator(args: &CoordinatorArgs) -> Result<(), RenderError> {
+    // Workers would look for relative paths in their own working directories, so they get the
+    // files by their absolute paths, which a shared file system keeps the same on every machine.
+    let mut scene = SceneFile::load(&args.scene)?;
+    scene.make_paths_absolute()?;
+
+    // Checked here as well, so that a broken scene fails before any worker is contacted.
+    let cam = scene.camera(
+        CameraBuilder::default().quiet(true),
+        &CameraSettings::default(),
+    )?;
+    let world = scene.build().context("failed to build the scene")?;
+    world.validate().context("invalid scene")?;
+
+    let (width, height) = cam.image_size();
+    let tiles = Tile::split(height, args.rows_per_tile);
+    let progress = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(tiles.len() as u64).with_style(ProgressStyle::default_bar())
+    };
+    let queue = TileQueue::new(tiles);
+    let image = Mutex::new(Framebuffer::new(width, height));
+    let timeout = Duration::from_secs(args.worker_timeout.max(1));
+
+    thread::scope(|scope| {
+        for address in &args.workers {
+            let (scene, queue, image, progress) = (&scene, &queue, &image, &progress);
+            scope.spawn(move || {
+                if let Err(e) = drive_worker(address, timeout, scene, queue, image, progress) {
+                    progress.suspend(|| eprintln!("worker {address} failed: {e}"));
+                }
+            });
+        }
+    });
+    progress.finish();
+
+    let remaining = queue.remaining();
+    if remaining > 0 {
+        return Err(RenderError::NoWorkersLeft(remaining));
+    }
+
+    let image = image.into_inner().unwrap_or_else(|e| e.into_inner());
+    match &args.output {
+        Some(path) => File::create(path)
+            .and_then(|file| {
+                let mut out = BufWriter::new(file);
+                image.write_ppm(&mut out)?;
+                out.flush()
+            })
+            .with_context(|| format!("failed to write {}", path.display())),
+        None => {
+            let mut out = io::stdout().lock();
+            image
+                .write_ppm(&mut out)
+                .and_then(|()| out.flush())
+                .context("failed to write the image")
+        }
+    }
+}
+
+fn drive_worker(
+    address: &str,
+    timeout: Duration,
+    scene: &SceneFile,
+    queue: &TileQueue,
+    image: &Mutex<Framebuffer>,
+    progress: &ProgressBar,
+) -> Result<(), RenderError> {
+    let stream = TcpStream::connect(address)?;
+    // A worker that hangs would otherwise hold its tile forever.
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let mut input = BufReader::new(stream.try_clone()?);
+    let mut output = BufWriter::new(stream);
+    let scene = Box::new(scene.clone());
+    send(&mut output, &Message::Scene { scene })?;
+
+    let width = image.lock().unwrap_or_else(|e| e.into_inner()).width();
+    while let Some(tile) = queue.take() {
+        match render_remotely(&mut input, &mut output, tile, width) {
+            Ok(rendered) => {
+                image
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .set_tile(tile, &rendered);
+                queue.finish();
+                progress.inc(1);
+            }
+            Err(e) => {
+                queue.give_back(tile);
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) {
+                    let message = format!("no answer within {timeout:?}");
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, message).into());
+                }
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn render_remotely(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    tile: Tile,
+    width: u32,
+) -> io::Result<Framebuffer> {
+    send(output, &Message::Render { tile })?;
+    match receive(input)? {
+        Message::Rendered {
+            tile: rendered,
+            pixels,
+        } if rendered == tile => Framebuffer::from_pixels(width, tile.height, pixels)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "wrong number of pixels")),
+        Message::Error { message } => Err(io::Error::other(message)),
+        _ => Err(unexpected("rendered")),
+    }
+}
+
+struct TileQueue {
+    // Tiles still to render and the number of tiles currently out with a worker.
+    state: Mutex<(VecDeque<Tile>, usize)>,
+    changed: Condvar,
+}
+
+impl TileQueue {
+    fn new(tiles: Vec<Tile>) -> Self {
+        Self {
+            state: Mutex::new((tiles.into(), 0)),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, (VecDeque<Tile>, usize)> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Returns `None` once every tile is done. While other workers still hold tiles it waits
+    // instead, since any of them may fail and hand its tile back.
+    fn take(&self) -> Option<Tile> {
+        let mut state = self.state();
+        loop {
+            if let Some(tile) = state.0.pop_front() {
+                state.1 += 1;
+                return Some(tile);
+            }
+            if state.1 == 0 {
+                return None;
+            }
+            state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    fn finish(&self) {
+        self.state().1 -= 1;
+        self.changed.notify_all();
+    }
+
+    fn give_back(&self, tile: Tile) {
+        let mut state = self.state();
+        state.0.push_back(tile);
+        state.1 -= 1;
+        drop(state);
+        self.changed.notify_all();
+    }
+
+    fn remaining(&self) -> usize {
+        self.state().0.len()
+    }
+}
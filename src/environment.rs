@@ -0,0 +1,307 @@
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    luminance, random_f64, unit_vector, Color, Context, Float, HdrImage, RenderError, Vec3, PI,
+};
+
+#[derive(Clone, Debug)]
+pub enum EnvironmentSpec {
+    Gradient,
+    Constant(Color),
+    Image(PathBuf),
+}
+
+impl EnvironmentSpec {
+    pub fn load(
+        &self,
+        rotation_degrees: Float,
+        intensity: Float,
+    ) -> Result<Arc<dyn Environment>, RenderError> {
+        Ok(match self {
+            EnvironmentSpec::Gradient => {
+                let gradient = GradientEnvironment::default();
+                Arc::new(GradientEnvironment::new(
+                    intensity * gradient.horizon,
+                    intensity * gradient.zenith,
+                ))
+            }
+            EnvironmentSpec::Constant(color) => {
+                Arc::new(ConstantEnvironment::new(intensity * *color))
+            }
+            EnvironmentSpec::Image(path) => Arc::new(
+                ImageEnvironment::open(path, rotation_degrees, intensity)
+                    .with_context(|| format!("failed to load {}", path.display()))?,
+            ),
+        })
+    }
+}
+
+impl FromStr for EnvironmentSpec {
+    type Err = RenderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "gradient" {
+            return Ok(EnvironmentSpec::Gradient);
+        }
+
+        if let Some(color) = s.strip_prefix("constant:") {
+            return Ok(EnvironmentSpec::Constant(color.parse()?));
+        }
+
+        if s.ends_with(".hdr") {
+            return Ok(EnvironmentSpec::Image(PathBuf::from(s)));
+        }
+
+        Err(RenderError::InvalidEnvironment(s.to_string()))
+    }
+}
+
+impl Display for EnvironmentSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvironmentSpec::Gradient => write!(f, "gradient"),
+            EnvironmentSpec::Constant(color) => {
+                write!(f, "constant:{},{},{}", color.x(), color.y(), color.z())
+            }
+            EnvironmentSpec::Image(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+// Scene files use the same strings as `--environment`.
+impl Serialize for EnvironmentSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for EnvironmentSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+pub trait Environment: Send + Sync {
+    fn value(&self, direction: &Vec3) -> Color;
+
+    // Samples a direction proportionally to the emitted radiance, returning it together with its
+    // solid angle pdf. Environments that cannot be importance sampled return `None`.
+    fn sample(&self) -> Option<(Vec3, Float)> {
+        None
+    }
+
+    fn pdf(&self, _direction: &Vec3) -> Float {
+        0.
+    }
+}
+
+pub struct ConstantEnvironment {
+    color: Color,
+}
+
+impl ConstantEnvironment {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+}
+
+impl Environment for ConstantEnvironment {
+    fn value(&self, _direction: &Vec3) -> Color {
+        self.color
+    }
+}
+
+pub struct GradientEnvironment {
+    horizon: Color,
+    zenith: Color,
+}
+
+impl GradientEnvironment {
+    pub fn new(horizon: Color, zenith: Color) -> Self {
+        Self { horizon, zenith }
+    }
+}
+
+impl Default for GradientEnvironment {
+    fn default() -> Self {
+        Self::new(Color::new(1.0, 1.0, 1.0), Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Environment for GradientEnvironment {
+    fn value(&self, direction: &Vec3) -> Color {
+        let unit_direction = unit_vector(*direction);
+        let a = 0.5 * (unit_direction.y() + 1.0);
+        (1.0 - a) * self.horizon + a * self.zenith
+    }
+}
+
+// Equirectangular environment map. Directions are mapped with +Y at the top row of the image and
+// `rotation` turning the map around the vertical axis.
+pub struct ImageEnvironment {
+    image: HdrImage,
+    rotation: Float,
+    intensity: Float,
+    marginal: Distribution1D,
+    conditional: Vec<Distribution1D>,
+}
+
+impl ImageEnvironment {
+    pub fn new(image: HdrImage, rotation_degrees: Float, intensity: Float) -> Self {
+        let conditional: Vec<Distribution1D> = (0..image.height)
+            .map(|y| {
+                let sin_theta = (PI * (y as Float + 0.5) / image.height as Float).sin();
+                Distribution1D::new(
+                    (0..image.width)
+                        .map(|x| luminance(image.pixel(x, y)) * sin_theta)
+                        .collect(),
+                )
+            })
+            .collect();
+        let marginal = Distribution1D::new(conditional.iter().map(|row| row.integral).collect());
+
+        Self {
+            image,
+            rotation: rotation_degrees.to_radians(),
+            intensity,
+            marginal,
+            conditional,
+        }
+    }
+
+    pub fn open(
+        path: impl AsRef<Path>,
+        rotation_degrees: Float,
+        intensity: Float,
+    ) -> io::Result<Self> {
+        Ok(Self::new(
+            HdrImage::open(path)?,
+            rotation_degrees,
+            intensity,
+        ))
+    }
+
+    fn direction_to_uv(&self, direction: &Vec3) -> (Float, Float) {
+        let d = unit_vector(*direction);
+        // Unlike the arc cosine of y, this keeps its precision near the poles.
+        let theta = Float::atan2(d.x().hypot(d.z()), d.y());
+        let phi = Float::atan2(-d.z(), d.x()) + PI;
+        let u = ((phi + self.rotation) / (2. * PI)).rem_euclid(1.);
+        (u, theta / PI)
+    }
+
+    fn uv_to_direction(&self, u: Float, v: Float) -> Vec3 {
+        let theta = v * PI;
+        let phi = u * 2. * PI - self.rotation - PI;
+        Vec3::new(
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            -theta.sin() * phi.sin(),
+        )
+    }
+
+    fn texel(&self, u: Float, v: Float) -> (usize, usize) {
+        let x = ((u * self.image.width as Float) as usize).min(self.image.width - 1);
+        let y = ((v * self.image.height as Float) as usize).min(self.image.height - 1);
+        (x, y)
+    }
+}
+
+impl Environment for ImageEnvironment {
+    fn value(&self, direction: &Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction);
+        let (x, y) = self.texel(u, v);
+        self.intensity * self.image.pixel(x, y)
+    }
+
+    fn sample(&self) -> Option<(Vec3, Float)> {
+        let (v, pdf_v, y) = self.marginal.sample(random_f64());
+        let (u, pdf_u, _) = self.conditional[y].sample(random_f64());
+
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0. || pdf_u * pdf_v <= 0. {
+            return None;
+        }
+
+        let pdf = pdf_u * pdf_v / (2. * PI * PI * sin_theta);
+        Some((self.uv_to_direction(u, v), pdf))
+    }
+
+    fn pdf(&self, direction: &Vec3) -> Float {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0. || self.marginal.integral <= 0. {
+            return 0.;
+        }
+
+        let (x, y) = self.texel(u, v);
+        self.conditional[y].func[x] / self.marginal.integral / (2. * PI * PI * sin_theta)
+    }
+}
+
+// Piecewise-constant distribution over [0, 1) sampled by inverting its CDF.
+struct Distribution1D {
+    func: Vec<Float>,
+    cdf: Vec<Float>,
+    integral: Float,
+}
+
+impl Distribution1D {
+    fn new(func: Vec<Float>) -> Self {
+        let n = func.len() as Float;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.);
+        for value in &func {
+            cdf.push(cdf.last().unwrap() + value / n);
+        }
+
+        let integral = *cdf.last().unwrap();
+        for (i, c) in cdf.iter_mut().enumerate() {
+            *c = if integral > 0. {
+                *c / integral
+            } else {
+                i as Float / n
+            };
+        }
+
+        Self {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    fn sample(&self, u: Float) -> (Float, Float, usize) {
+        let n = self.func.len();
+        let i = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(n - 1);
+
+        let width = self.cdf[i + 1] - self.cdf[i];
+        let du = if width > 0. {
+            (u - self.cdf[i]) / width
+        } else {
+            0.
+        };
+
+        let pdf = if self.integral > 0. {
+            self.func[i] / self.integral
+        } else {
+            1.
+        };
+
+        ((i as Float + du) / n as Float, pdf, i)
+    }
+}
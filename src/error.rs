@@ -0,0 +1,178 @@
+use std::{error::Error, fmt::Display, io, path::PathBuf};
+
+use crate::{Color, Float, Point3, Vec3};
+
+#[derive(Debug)]
+pub enum RenderError {
+    InvalidVector,
+    InvalidEnvironment(String),
+    InvalidScene(String),
+    InvalidConfig(String),
+    InvalidMesh(String),
+    InvalidGltf(String),
+    UnsupportedFormat {
+        path: PathBuf,
+        expected: &'static str,
+    },
+    InvalidKeyframe {
+        line: usize,
+        message: String,
+    },
+    NonFinite(&'static str),
+    NegativeRadius(Float),
+    DegenerateQuad(Vec3, Vec3),
+    DegenerateAxis(&'static str),
+    OpenCsgOperand(&'static str),
+    InvalidBlend(Float),
+    InvalidFuzz(Float),
+    InvalidRefractiveIndex(Float),
+    InvalidDensity(Float),
+    InvalidAlbedo(Color),
+    ZeroImageWidth,
+    InvalidAspectRatio(Float),
+    ZeroSamplesPerPixel,
+    LookFromEqualsLookAt(Point3),
+    VupParallelToViewDirection(Vec3),
+    NonPositiveFocusDistance(Float),
+    NoWorkersLeft(usize),
+    Io(io::Error),
+    Context {
+        context: String,
+        source: Box<RenderError>,
+    },
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::InvalidVector => {
+                write!(f, "Un vector tiene que estar en el formato 'x,y,z'")
+            }
+            RenderError::InvalidEnvironment(input) => write!(
+                f,
+                "expected 'gradient', 'constant:r,g,b' or a '.hdr' image, got '{input}'"
+            ),
+            RenderError::InvalidScene(message) => write!(f, "invalid scene file: {message}"),
+            RenderError::InvalidConfig(message) => write!(f, "invalid config file: {message}"),
+            RenderError::InvalidMesh(message) => write!(f, "invalid mesh: {message}"),
+            RenderError::InvalidGltf(message) => write!(f, "invalid glTF file: {message}"),
+            RenderError::UnsupportedFormat { path, expected } => write!(
+                f,
+                "cannot tell the format of '{}', expected {expected}",
+                path.display()
+            ),
+            RenderError::InvalidKeyframe { line, message } => {
+                write!(f, "invalid keyframe on line {line}: {message}")
+            }
+            RenderError::NonFinite(what) => write!(f, "the {what} is not a finite value"),
+            RenderError::NegativeRadius(radius) => {
+                write!(f, "the radius must not be negative, got {radius}")
+            }
+            RenderError::DegenerateQuad(u, v) => {
+                write!(f, "the quad edges {u} and {v} are parallel")
+            }
+            RenderError::DegenerateAxis(what) => {
+                write!(f, "the {what} axis has zero length")
+            }
+            RenderError::OpenCsgOperand(operand) => write!(
+                f,
+                "the {operand} operand of a CSG operation is not a closed object"
+            ),
+            RenderError::InvalidBlend(t) => {
+                write!(f, "the blend factor must be between 0 and 1, got {t}")
+            }
+            RenderError::InvalidFuzz(fuzz) => {
+                write!(f, "the fuzz must be between 0 and 1, got {fuzz}")
+            }
+            RenderError::InvalidRefractiveIndex(index) => {
+                write!(f, "the refractive index must be positive, got {index}")
+            }
+            RenderError::InvalidDensity(density) => {
+                write!(f, "the density must be positive and finite, got {density}")
+            }
+            RenderError::InvalidAlbedo(albedo) => {
+                write!(
+                    f,
+                    "the albedo must be finite and not negative, got {albedo}"
+                )
+            }
+            RenderError::ZeroImageWidth => write!(f, "the image width must be at least 1"),
+            RenderError::InvalidAspectRatio(ratio) => {
+                write!(
+                    f,
+                    "the aspect ratio must be positive and finite, got {ratio}"
+                )
+            }
+            RenderError::ZeroSamplesPerPixel => {
+                write!(f, "at least one sample per pixel is required")
+            }
+            RenderError::LookFromEqualsLookAt(point) => {
+                write!(f, "look-from and look-at are the same point ({point})")
+            }
+            RenderError::VupParallelToViewDirection(vup) => {
+                write!(f, "v-up ({vup}) is parallel to the view direction")
+            }
+            RenderError::NonPositiveFocusDistance(distance) => {
+                write!(f, "the focus distance must be positive, got {distance}")
+            }
+            RenderError::NoWorkersLeft(tiles) => {
+                write!(f, "every worker failed with {tiles} tiles left to render")
+            }
+            RenderError::Io(e) => write!(f, "{e}"),
+            RenderError::Context { context, source } => write!(f, "{context}: {source}"),
+        }
+    }
+}
+
+impl Error for RenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RenderError::Io(e) => Some(e),
+            RenderError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RenderError {
+    fn from(e: io::Error) -> Self {
+        RenderError::Io(e)
+    }
+}
+
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, RenderError>;
+
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T, RenderError>;
+}
+
+impl<T, E: Into<RenderError>> Context<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, RenderError> {
+        self.with_context(|| context.into())
+    }
+
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T, RenderError> {
+        self.map_err(|e| RenderError::Context {
+            context: context(),
+            source: Box::new(e.into()),
+        })
+    }
+}
+
+pub(crate) fn ensure_finite(v: Vec3, what: &'static str) -> Result<(), RenderError> {
+    if v.x().is_finite() && v.y().is_finite() && v.z().is_finite() {
+        Ok(())
+    } else {
+        Err(RenderError::NonFinite(what))
+    }
+}
+
+pub(crate) fn validate_radius(radius: Float, what: &'static str) -> Result<(), RenderError> {
+    if !radius.is_finite() {
+        return Err(RenderError::NonFinite(what));
+    }
+    if radius < 0. {
+        return Err(RenderError::NegativeRadius(radius));
+    }
+    Ok(())
+}
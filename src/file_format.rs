@@ -0,0 +1,29 @@
+use std::{io, path::Path};
+
+use crate::RenderError;
+
+// A file format picked by the extension of the path, as images, meshes and scenes are.
+pub trait FileFormat: Sized {
+    // The extensions listed in the error for any other one.
+    const EXTENSIONS: &'static str;
+
+    // `extension` is in lowercase.
+    fn from_extension(extension: &str) -> Option<Self>;
+
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::from_extension(&extension)
+    }
+
+    fn of(path: &Path) -> Result<Self, RenderError> {
+        Self::from_path(path).ok_or_else(|| RenderError::UnsupportedFormat {
+            path: path.to_path_buf(),
+            expected: Self::EXTENSIONS,
+        })
+    }
+}
+
+// The error of the readers for files that do not follow their format.
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
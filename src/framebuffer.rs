@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{write_color, Color, Float};
+
+// A band of whole image rows. Keeping tiles full width means every row is rendered in one piece,
+// so the per-row seeding gives the same pixels however the image is split.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tile {
+    pub y: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    pub fn rows(y: u32, height: u32) -> Tile {
+        Tile { y, height }
+    }
+
+    // Splits `height` rows into tiles of `rows_per_tile` rows; the last one may be shorter.
+    pub fn split(height: u32, rows_per_tile: u32) -> Vec<Tile> {
+        let rows_per_tile = rows_per_tile.max(1);
+        (0..height)
+            .step_by(rows_per_tile as usize)
+            .map(|y| Tile::rows(y, rows_per_tile.min(height - y)))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+impl Framebuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::default(); width as usize * height as usize],
+        }
+    }
+
+    // Returns `None` unless there are exactly `width` x `height` pixels.
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<Color>) -> Option<Self> {
+        (pixels.len() == width as usize * height as usize).then_some(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        self.pixels[self.index(x, y)]
+    }
+
+    #[inline]
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        let index = self.index(x, y);
+        self.pixels[index] = color;
+    }
+
+    pub fn set_row(&mut self, y: u32, row: &[Color]) {
+        let start = self.index(0, y);
+        self.pixels[start..start + self.width as usize].copy_from_slice(row);
+    }
+
+    // Copies `image`, rendered for `tile`, into its rows of this framebuffer.
+    pub fn set_tile(&mut self, tile: Tile, image: &Framebuffer) {
+        let start = self.index(0, tile.y);
+        self.pixels[start..start + image.pixels.len()].copy_from_slice(&image.pixels);
+    }
+
+    pub fn accumulate(&mut self, other: &Framebuffer) {
+        for (pixel, other) in self.pixels.iter_mut().zip(&other.pixels) {
+            *pixel += *other;
+        }
+    }
+
+    #[must_use]
+    pub fn scaled(&self, factor: Float) -> Framebuffer {
+        Framebuffer {
+            pixels: self.pixels.iter().map(|&pixel| pixel * factor).collect(),
+            ..*self
+        }
+    }
+
+    // Box-filters the image down to `width` x `height`, averaging every source pixel that falls
+    // inside a target pixel.
+    #[must_use]
+    pub fn downsampled(&self, width: u32, height: u32) -> Framebuffer {
+        let mut image = Framebuffer::new(width, height);
+        let span = |i: u32, size: u32, source: u32| {
+            let start = (u64::from(i) * u64::from(source) / u64::from(size)) as u32;
+            let end = (u64::from(i + 1) * u64::from(source) / u64::from(size)) as u32;
+            start..end.max(start + 1)
+        };
+
+        for y in 0..height {
+            let rows = span(y, height, self.height);
+            for x in 0..width {
+                let columns = span(x, width, self.width);
+                let mut sum = Color::default();
+                for sy in rows.clone() {
+                    for sx in columns.clone() {
+                        sum += self.pixel(sx, sy);
+                    }
+                }
+                let count = rows.len() * columns.len();
+                image.set_pixel(x, y, sum / count as Float);
+            }
+        }
+
+        image
+    }
+
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P3\n {}  {}\n255\n", self.width, self.height)?;
+        for &pixel in &self.pixels {
+            write_color(out, pixel)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+}
@@ -0,0 +1,861 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    cross, dot, unit_vector, CameraSettings, Color, Context, DetailedMaterial, Dielectric,
+    DiffuseLight, Float, HittableList, ImageTexture, Lambertian, Material, Mesh, Metal, Point3,
+    Projection, RenderError, SolidColor, SurfaceDetail, Texture, TriangleMesh, Vec3,
+};
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const JSON_CHUNK: u32 = 0x4E4F_534A;
+const BIN_CHUNK: u32 = 0x004E_4942;
+// Accessors without a buffer view are all zeros, so nothing in the file bounds their size.
+const MAX_EMPTY_ACCESSOR: usize = 1 << 26;
+
+// A glTF 2.0 asset: a `.gltf` file with its buffers and images beside it or embedded as data
+// URIs, or a binary `.glb` file. Only local files are read.
+pub struct GltfAsset {
+    root: Root,
+    buffers: Vec<Vec<u8>>,
+    dir: PathBuf,
+}
+
+impl GltfAsset {
+    pub fn open(path: &Path) -> Result<Self, RenderError> {
+        Self::load(path).with_context(|| format!("failed to load {}", path.display()))
+    }
+
+    fn load(path: &Path) -> Result<Self, RenderError> {
+        let bytes = fs::read(path)?;
+        let (json, bin) = if bytes.starts_with(GLB_MAGIC) {
+            split_glb(&bytes)?
+        } else {
+            (&bytes[..], None)
+        };
+        let root: Root =
+            serde_json::from_slice(json).map_err(|e| RenderError::InvalidGltf(e.to_string()))?;
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let mut buffers = Vec::with_capacity(root.buffers.len());
+        for (i, buffer) in root.buffers.iter().enumerate() {
+            // The binary chunk of a `.glb` file is the first buffer, which then has no URI.
+            let data = match (&buffer.uri, bin) {
+                (Some(uri), _) => read_uri(&dir, uri)?,
+                (None, Some(bin)) if i == 0 => bin.to_vec(),
+                (None, _) => return Err(invalid(format!("buffer {i} has no data"))),
+            };
+            if data.len() < buffer.byte_length {
+                return Err(invalid(format!(
+                    "buffer {i} holds {} of its {} bytes",
+                    data.len(),
+                    buffer.byte_length
+                )));
+            }
+            buffers.push(data);
+        }
+        Ok(Self { root, buffers, dir })
+    }
+
+    // The first camera of the scene, as seen from where its node places it.
+    pub fn camera(&self) -> Result<Option<CameraSettings>, RenderError> {
+        for (node, transform) in self.nodes()? {
+            let Some(index) = self.root.nodes[node].camera else {
+                continue;
+            };
+            let camera = get(&self.root.cameras, index, "camera")?;
+
+            // glTF cameras look down their -z axis with +y up.
+            let mut settings = CameraSettings {
+                look_from: Some(transform.point(Point3::new(0., 0., 0.))),
+                look_at: Some(transform.point(Point3::new(0., 0., -1.))),
+                v_up: Some(transform.vector(Vec3::new(0., 1., 0.))),
+                defocus_angle: Some(0.),
+                ..CameraSettings::default()
+            };
+            match (&camera.perspective, &camera.orthographic) {
+                (Some(perspective), _) => {
+                    settings.vfov = Some(perspective.yfov.to_degrees());
+                    settings.aspect_ratio = perspective.aspect_ratio;
+                }
+                // The view spans 2 ymag at a focus distance of 1.
+                (None, Some(orthographic)) => {
+                    settings.projection = Some(Projection::Orthographic);
+                    settings.focus_dist = Some(1.);
+                    settings.vfov = Some((2. * orthographic.ymag.atan()).to_degrees());
+                    settings.aspect_ratio = Some(orthographic.xmag / orthographic.ymag);
+                }
+                (None, None) => {
+                    return Err(invalid(format!("camera {index} has no projection")));
+                }
+            }
+            return Ok(Some(settings));
+        }
+        Ok(None)
+    }
+
+    // The meshes of the scene in world space. Primitives without a material are diffuse and take
+    // their vertex colors, as meshes from other files do.
+    pub fn build(&self) -> Result<HittableList, RenderError> {
+        let mut images = HashMap::new();
+        let materials = self
+            .root
+            .materials
+            .iter()
+            .enumerate()
+            .map(|(i, material)| {
+                self.material(material, &mut images)
+                    .with_context(|| format!("material {i}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut world = HittableList::default();
+        for (node, transform) in self.nodes()? {
+            let Some(index) = self.root.nodes[node].mesh else {
+                continue;
+            };
+            let mesh = get(&self.root.meshes, index, "mesh")?;
+            for (i, primitive) in mesh.primitives.iter().enumerate() {
+                let context = || format!("primitive {i} of mesh {index}");
+                let (material, texcoord) = match primitive.material {
+                    Some(m) => (
+                        Some(get(&materials, m, "material")?.clone()),
+                        self.root.materials[m].texcoord(),
+                    ),
+                    None => (None, 0),
+                };
+                let triangles = self
+                    .triangles(primitive, &transform, texcoord)
+                    .with_context(context)?;
+                if !triangles.triangles.is_empty() {
+                    world.add(Mesh::new(triangles, material).with_context(context)?);
+                }
+            }
+        }
+        Ok(world)
+    }
+
+    // Every node of the default scene with its transform to world space, parents first.
+    fn nodes(&self) -> Result<Vec<(usize, Affine)>, RenderError> {
+        let roots = match self.root.scenes.get(self.root.scene.unwrap_or(0)) {
+            Some(scene) => scene.nodes.clone(),
+            // Without scenes, every node that is not a child is a root.
+            None if self.root.scenes.is_empty() => {
+                let children: Vec<usize> = self
+                    .root
+                    .nodes
+                    .iter()
+                    .flat_map(|node| node.children.iter().copied())
+                    .collect();
+                (0..self.root.nodes.len())
+                    .filter(|i| !children.contains(i))
+                    .collect()
+            }
+            None => return Err(invalid("the default scene does not exist")),
+        };
+
+        let mut visited = vec![false; self.root.nodes.len()];
+        let mut nodes = Vec::new();
+        let mut stack: Vec<(usize, Affine)> = roots
+            .into_iter()
+            .rev()
+            .map(|i| (i, Affine::identity()))
+            .collect();
+        while let Some((index, parent)) = stack.pop() {
+            let node = get(&self.root.nodes, index, "node")?;
+            if std::mem::replace(&mut visited[index], true) {
+                return Err(invalid(format!("node {index} has more than one parent")));
+            }
+            let transform = parent.then(Affine::of(node));
+            stack.extend(node.children.iter().rev().map(|&child| (child, transform)));
+            nodes.push((index, transform));
+        }
+        Ok(nodes)
+    }
+
+    fn triangles(
+        &self,
+        primitive: &Primitive,
+        transform: &Affine,
+        texcoord: usize,
+    ) -> Result<TriangleMesh, RenderError> {
+        let attribute = |name: &str| -> Result<Option<Vec<Float>>, RenderError> {
+            primitive
+                .attributes
+                .get(name)
+                .map(|&accessor| self.read_accessor(accessor).context(name.to_string()))
+                .transpose()
+        };
+        let triple = |values: &[Float]| vec3([values[0], values[1], values[2]]);
+
+        let mut mesh = TriangleMesh::default();
+        let positions = attribute("POSITION")?.ok_or_else(|| invalid("no POSITION attribute"))?;
+        mesh.positions = positions
+            .chunks_exact(3)
+            .map(|p| transform.point(triple(p)))
+            .collect();
+        if let Some(normals) = attribute("NORMAL")? {
+            mesh.normals = normals
+                .chunks_exact(3)
+                .map(|n| transform.normal(triple(n)))
+                .collect();
+        }
+        // glTF puts v = 0 at the top of the image.
+        if let Some(texcoords) = attribute(&format!("TEXCOORD_{texcoord}"))? {
+            mesh.texcoords = texcoords
+                .chunks_exact(2)
+                .map(|t| (t[0], 1. - t[1]))
+                .collect();
+        }
+        // Colors are RGB or RGBA, whose alpha is dropped.
+        if let Some(&accessor) = primitive.attributes.get("COLOR_0") {
+            let components = match get(&self.root.accessors, accessor, "accessor")?
+                .kind
+                .as_str()
+            {
+                "VEC3" => 3,
+                "VEC4" => 4,
+                kind => return Err(invalid(format!("COLOR_0 of unsupported type {kind}"))),
+            };
+            let colors = self.read_accessor(accessor).context("COLOR_0")?;
+            if colors.len() != mesh.positions.len() * components {
+                return Err(invalid("COLOR_0 does not have one color per vertex"));
+            }
+            mesh.colors = colors.chunks_exact(components).map(triple).collect();
+        }
+
+        let indices: Vec<usize> = match primitive.indices {
+            Some(accessor) => self
+                .read_accessor(accessor)
+                .context("indices")?
+                .into_iter()
+                .map(|i| i as usize)
+                .collect(),
+            None => (0..mesh.positions.len()).collect(),
+        };
+        mesh.triangles = match primitive.mode {
+            4 => indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+            // Every other triangle of a strip is wound the other way.
+            5 => (2..indices.len())
+                .map(|i| match i % 2 {
+                    0 => [indices[i - 2], indices[i - 1], indices[i]],
+                    _ => [indices[i - 1], indices[i - 2], indices[i]],
+                })
+                .collect(),
+            6 => (2..indices.len())
+                .map(|i| [indices[0], indices[i - 1], indices[i]])
+                .collect(),
+            // Points and lines have no surface to render.
+            _ => Vec::new(),
+        };
+        // A mirroring transform turns the triangles inside out.
+        if transform.determinant() < 0. {
+            for triangle in &mut mesh.triangles {
+                triangle.swap(1, 2);
+            }
+        }
+        Ok(mesh)
+    }
+
+    // The bytes of a buffer view.
+    fn view_data(&self, index: usize) -> Result<&[u8], RenderError> {
+        let view = get(&self.root.buffer_views, index, "buffer view")?;
+        let buffer = get(&self.buffers, view.buffer, "buffer")?;
+        view.byte_offset
+            .checked_add(view.byte_length)
+            .and_then(|end| buffer.get(view.byte_offset..end))
+            .ok_or_else(|| invalid(format!("buffer view {index} lies outside of its buffer")))
+    }
+
+    // The elements of an accessor, one after the other. Normalized integers are mapped to [0, 1]
+    // or [-1, 1].
+    fn read_accessor(&self, index: usize) -> Result<Vec<Float>, RenderError> {
+        let accessor = get(&self.root.accessors, index, "accessor")?;
+        let components = match accessor.kind.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            kind => return Err(invalid(format!("unsupported accessor type {kind}"))),
+        };
+        let (size, max): (usize, f64) = match accessor.component_type {
+            5120 => (1, i8::MAX.into()),
+            5121 => (1, u8::MAX.into()),
+            5122 => (2, i16::MAX.into()),
+            5123 => (2, u16::MAX.into()),
+            5125 => (4, u32::MAX.into()),
+            5126 => (4, 1.),
+            kind => return Err(invalid(format!("unsupported component type {kind}"))),
+        };
+        let too_large = || invalid(format!("accessor {index} is too large"));
+        let Some(view) = accessor.buffer_view else {
+            // Without data every element is zero, but `count` still comes from the file.
+            let count = accessor
+                .count
+                .checked_mul(components)
+                .filter(|&count| count <= MAX_EMPTY_ACCESSOR)
+                .ok_or_else(too_large)?;
+            return Ok(vec![0.; count]);
+        };
+
+        let stride = get(&self.root.buffer_views, view, "buffer view")?
+            .byte_stride
+            .unwrap_or(size * components);
+        let data = self.view_data(view)?;
+        // The last element must end inside the view, which also bounds the allocation below.
+        if accessor.count > 0 {
+            let end = (accessor.count - 1)
+                .checked_mul(stride)
+                .and_then(|last| last.checked_add(accessor.byte_offset))
+                .and_then(|last| last.checked_add(size * components))
+                .ok_or_else(too_large)?;
+            if end > data.len() {
+                return Err(invalid(format!("accessor {index} runs past its view")));
+            }
+        }
+
+        let mut values = Vec::with_capacity(accessor.count * components);
+        for element in 0..accessor.count {
+            for component in 0..components {
+                let offset = accessor.byte_offset + element * stride + component * size;
+                let bytes = data
+                    .get(offset..offset + size)
+                    .ok_or_else(|| invalid(format!("accessor {index} runs past its view")))?;
+                let value: f64 = match accessor.component_type {
+                    5120 => (bytes[0] as i8).into(),
+                    5121 => bytes[0].into(),
+                    5122 => i16::from_le_bytes([bytes[0], bytes[1]]).into(),
+                    5123 => u16::from_le_bytes([bytes[0], bytes[1]]).into(),
+                    5125 => u32::from_le_bytes(bytes.try_into().unwrap()).into(),
+                    _ => f32::from_le_bytes(bytes.try_into().unwrap()).into(),
+                };
+                let value = if accessor.normalized {
+                    (value / max).max(-1.)
+                } else {
+                    value
+                };
+                values.push(value as Float);
+            }
+        }
+        Ok(values)
+    }
+
+    fn material(
+        &self,
+        material: &GltfMaterial,
+        images: &mut HashMap<(usize, bool), Arc<ImageTexture>>,
+    ) -> Result<Arc<dyn Material>, RenderError> {
+        let base = self.base_material(material, images)?;
+        let Some(info) = &material.normal_texture else {
+            return Ok(base);
+        };
+        let detail = SurfaceDetail::NormalMap {
+            map: self.texture_image(info.index, true, images)?,
+            strength: info.scale,
+        };
+        Ok(Arc::new(DetailedMaterial::new(base, detail)))
+    }
+
+    // Lights for emissive materials, glass for transmissive ones, and metal or diffuse surfaces
+    // depending on the metalness, whose roughness becomes the fuzz of the reflections.
+    fn base_material(
+        &self,
+        material: &GltfMaterial,
+        images: &mut HashMap<(usize, bool), Arc<ImageTexture>>,
+    ) -> Result<Arc<dyn Material>, RenderError> {
+        let extensions = &material.extensions;
+        let strength = extensions
+            .emissive_strength
+            .as_ref()
+            .map_or(1., |e| e.emissive_strength);
+        let emission = vec3(material.emissive_factor) * strength;
+        if emission != Color::default() {
+            return Ok(Arc::new(DiffuseLight::new(emission)));
+        }
+        if extensions
+            .transmission
+            .as_ref()
+            .is_some_and(|t| t.transmission_factor >= 0.5)
+        {
+            let ior = extensions.ior.as_ref().map_or(1.5, |ior| ior.ior);
+            return Ok(Arc::new(Dielectric::new(ior)?));
+        }
+
+        let pbr = &material.pbr_metallic_roughness;
+        let [r, g, b, _] = pbr.base_color_factor;
+        let factor = Color::new(r, g, b);
+        let base: Arc<dyn Texture> = match &pbr.base_color_texture {
+            Some(info) => {
+                let image = self.texture_image(info.index, false, images)?;
+                if factor == Color::new(1., 1., 1.) {
+                    image
+                } else {
+                    Arc::new(image.tinted(factor))
+                }
+            }
+            None => Arc::new(SolidColor::new(factor)),
+        };
+        if pbr.metallic_factor >= 0.5 {
+            let fuzz = pbr.roughness_factor.clamp(0., 1.);
+            Ok(Arc::new(Metal::from_texture(base, fuzz)?))
+        } else {
+            Ok(Arc::new(Lambertian::from_texture(base)))
+        }
+    }
+
+    // Images are decoded once, however many materials use them. Colors are gamma encoded, while
+    // `linear` images such as normal maps hold data that is read as it is.
+    fn texture_image(
+        &self,
+        texture: usize,
+        linear: bool,
+        images: &mut HashMap<(usize, bool), Arc<ImageTexture>>,
+    ) -> Result<Arc<ImageTexture>, RenderError> {
+        let texture = get(&self.root.textures, texture, "texture")?;
+        let index = texture
+            .source
+            .ok_or_else(|| invalid("texture without an image"))?;
+        if let Some(image) = images.get(&(index, linear)) {
+            return Ok(image.clone());
+        }
+
+        let image = get(&self.root.images, index, "image")?;
+        let bytes = match (&image.uri, image.buffer_view) {
+            (Some(uri), _) => read_uri(&self.dir, uri)?,
+            (None, Some(view)) => self.view_data(view)?.to_vec(),
+            (None, None) => return Err(invalid(format!("image {index} has no data"))),
+        };
+        let decoded = if linear {
+            ImageTexture::decode_linear(&bytes)
+        } else {
+            ImageTexture::decode(&bytes)
+        };
+        let decoded = Arc::new(decoded.with_context(|| format!("failed to decode image {index}"))?);
+        images.insert((index, linear), decoded.clone());
+        Ok(decoded)
+    }
+}
+
+// An affine transform, as glTF nodes hold: the images of the three axes and of the origin.
+#[derive(Clone, Copy)]
+struct Affine {
+    axes: [Vec3; 3],
+    translation: Vec3,
+}
+
+impl Affine {
+    fn identity() -> Affine {
+        Affine {
+            axes: [
+                Vec3::new(1., 0., 0.),
+                Vec3::new(0., 1., 0.),
+                Vec3::new(0., 0., 1.),
+            ],
+            translation: Vec3::new(0., 0., 0.),
+        }
+    }
+
+    // The node's matrix, which is stored by columns, or its translation, rotation and scale.
+    fn of(node: &Node) -> Affine {
+        if let Some(m) = node.matrix {
+            let column = |i: usize| Vec3::new(m[4 * i], m[4 * i + 1], m[4 * i + 2]);
+            return Affine {
+                axes: [column(0), column(1), column(2)],
+                translation: column(3),
+            };
+        }
+
+        // Rotates by the unit quaternion (x, y, z, w).
+        let [x, y, z, w] = node.rotation;
+        let q = Vec3::new(x, y, z);
+        let rotate = |v: Vec3| {
+            let t = 2. * cross(q, v);
+            v + w * t + cross(q, t)
+        };
+        let [sx, sy, sz] = node.scale;
+        Affine {
+            axes: [
+                sx * rotate(Vec3::new(1., 0., 0.)),
+                sy * rotate(Vec3::new(0., 1., 0.)),
+                sz * rotate(Vec3::new(0., 0., 1.)),
+            ],
+            translation: vec3(node.translation),
+        }
+    }
+
+    // Applies `child` first and then this transform.
+    fn then(&self, child: Affine) -> Affine {
+        Affine {
+            axes: child.axes.map(|axis| self.vector(axis)),
+            translation: self.point(child.translation),
+        }
+    }
+
+    fn vector(&self, v: Vec3) -> Vec3 {
+        let [a, b, c] = self.axes;
+        v.x() * a + v.y() * b + v.z() * c
+    }
+
+    fn point(&self, p: Point3) -> Point3 {
+        self.vector(p) + self.translation
+    }
+
+    // Normals transform by the inverse transpose, which is the cofactor matrix up to a scale.
+    fn normal(&self, n: Vec3) -> Vec3 {
+        let [a, b, c] = self.axes;
+        let normal = n.x() * cross(b, c) + n.y() * cross(c, a) + n.z() * cross(a, b);
+        if normal.length_squared() == 0. {
+            return normal;
+        }
+        self.determinant().signum() * unit_vector(normal)
+    }
+
+    fn determinant(&self) -> Float {
+        let [a, b, c] = self.axes;
+        dot(a, cross(b, c))
+    }
+}
+
+fn vec3([x, y, z]: [Float; 3]) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+
+// The JSON and binary chunks of a `.glb` file.
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), RenderError> {
+    let word = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()) as usize)
+            .ok_or_else(|| invalid("truncated GLB file"))
+    };
+    if word(4)? != 2 {
+        return Err(invalid(format!("unsupported GLB version {}", word(4)?)));
+    }
+
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset < bytes.len().min(word(8)?) {
+        let (length, kind) = (word(offset)?, word(offset + 4)?);
+        let data = bytes
+            .get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| invalid("truncated GLB chunk"))?;
+        chunks.push((kind as u32, data));
+        offset += 8 + length;
+    }
+    match chunks.as_slice() {
+        [(JSON_CHUNK, json), rest @ ..] => {
+            let bin = rest
+                .iter()
+                .find(|(kind, _)| *kind == BIN_CHUNK)
+                .map(|(_, data)| *data);
+            Ok((json, bin))
+        }
+        _ => Err(invalid("the GLB file does not start with a JSON chunk")),
+    }
+}
+
+// Buffers and images are embedded as base64 data URIs or stored in files next to the asset.
+fn read_uri(dir: &Path, uri: &str) -> Result<Vec<u8>, RenderError> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        return match data.split_once(',') {
+            Some((header, payload)) if header.ends_with(";base64") => decode_base64(payload),
+            _ => Err(invalid("only base64 data URIs are supported")),
+        };
+    }
+    if uri.contains("://") {
+        return Err(invalid(format!("only local files can be read, not {uri}")));
+    }
+
+    let path = dir.join(percent_decode(uri));
+    fs::read(&path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, RenderError> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    };
+
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text
+        .bytes()
+        .filter(|&c| c != b'=' && !c.is_ascii_whitespace())
+    {
+        let value = value(c).ok_or_else(|| invalid("bad base64 data"))?;
+        bits = bits << 6 | u32::from(value);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+fn get<'a, T>(items: &'a [T], index: usize, what: &str) -> Result<&'a T, RenderError> {
+    items
+        .get(index)
+        .ok_or_else(|| invalid(format!("{what} {index} does not exist")))
+}
+
+fn invalid(message: impl Into<String>) -> RenderError {
+    RenderError::InvalidGltf(message.into())
+}
+
+// The parts of the glTF JSON the renderer uses; everything else is ignored.
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Root {
+    scene: Option<usize>,
+    scenes: Vec<Scene>,
+    nodes: Vec<Node>,
+    meshes: Vec<GltfMesh>,
+    accessors: Vec<Accessor>,
+    buffer_views: Vec<BufferView>,
+    buffers: Vec<Buffer>,
+    materials: Vec<GltfMaterial>,
+    textures: Vec<GltfTexture>,
+    images: Vec<Image>,
+    cameras: Vec<Camera>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Scene {
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct Node {
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    camera: Option<usize>,
+    matrix: Option<[Float; 16]>,
+    translation: [Float; 3],
+    rotation: [Float; 4],
+    scale: [Float; 3],
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Self {
+            children: Vec::new(),
+            mesh: None,
+            camera: None,
+            matrix: None,
+            translation: [0.; 3],
+            rotation: [0., 0., 0., 1.],
+            scale: [1.; 3],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GltfMesh {
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "triangles")]
+    mode: u32,
+}
+
+fn triangles() -> u32 {
+    4
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Buffer {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct GltfMaterial {
+    pbr_metallic_roughness: Pbr,
+    normal_texture: Option<NormalTextureInfo>,
+    emissive_factor: [Float; 3],
+    extensions: MaterialExtensions,
+}
+
+impl GltfMaterial {
+    // The set of texture coordinates the base color, or else the normal map, is mapped with.
+    fn texcoord(&self) -> usize {
+        let base = &self.pbr_metallic_roughness.base_color_texture;
+        match (base, &self.normal_texture) {
+            (Some(info), _) => info.tex_coord,
+            (None, Some(info)) => info.tex_coord,
+            (None, None) => 0,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Pbr {
+    base_color_factor: [Float; 4],
+    base_color_texture: Option<TextureInfo>,
+    metallic_factor: Float,
+    roughness_factor: Float,
+}
+
+impl Default for Pbr {
+    fn default() -> Self {
+        Self {
+            base_color_factor: [1.; 4],
+            base_color_texture: None,
+            metallic_factor: 1.,
+            roughness_factor: 1.,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TextureInfo {
+    index: usize,
+    #[serde(default)]
+    tex_coord: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NormalTextureInfo {
+    index: usize,
+    #[serde(default)]
+    tex_coord: usize,
+    #[serde(default = "unit_scale")]
+    scale: Float,
+}
+
+fn unit_scale() -> Float {
+    1.
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct MaterialExtensions {
+    #[serde(rename = "KHR_materials_emissive_strength")]
+    emissive_strength: Option<EmissiveStrength>,
+    #[serde(rename = "KHR_materials_transmission")]
+    transmission: Option<Transmission>,
+    #[serde(rename = "KHR_materials_ior")]
+    ior: Option<Ior>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmissiveStrength {
+    emissive_strength: Float,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Transmission {
+    #[serde(default)]
+    transmission_factor: Float,
+}
+
+#[derive(Deserialize)]
+struct Ior {
+    ior: Float,
+}
+
+#[derive(Deserialize)]
+struct GltfTexture {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Image {
+    uri: Option<String>,
+    buffer_view: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct Camera {
+    perspective: Option<Perspective>,
+    orthographic: Option<Orthographic>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Perspective {
+    yfov: Float,
+    aspect_ratio: Option<Float>,
+}
+
+#[derive(Deserialize)]
+struct Orthographic {
+    xmag: Float,
+    ymag: Float,
+}
@@ -0,0 +1,174 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+};
+
+use crate::{invalid_data, pixel_count, Color, Float};
+
+pub struct HdrImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl HdrImage {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Self::read_radiance(&mut BufReader::new(file))
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    // Reads a Radiance RGBE picture, flat or with the run-length encoded scanlines most tools
+    // write. Only the standard `-Y height +X width` orientation is supported.
+    pub fn read_radiance(reader: &mut impl BufRead) -> io::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        if !line.starts_with("#?") {
+            return Err(invalid_data("missing Radiance signature"));
+        }
+
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(invalid_data("unexpected end of header"));
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(format) = line.strip_prefix("FORMAT=") {
+                if format != "32-bit_rle_rgbe" {
+                    return Err(invalid_data("only 32-bit_rle_rgbe pictures are supported"));
+                }
+            }
+        }
+
+        line.clear();
+        reader.read_line(&mut line)?;
+        let resolution: Vec<&str> = line.split_whitespace().collect();
+        let (height, width) = match resolution.as_slice() {
+            ["-Y", height, "+X", width] => (
+                height.parse().map_err(|_| invalid_data("bad height"))?,
+                width.parse().map_err(|_| invalid_data("bad width"))?,
+            ),
+            _ => return Err(invalid_data("unsupported resolution line")),
+        };
+
+        let mut pixels = Vec::with_capacity(pixel_count(width, height)?);
+        let mut scanline = vec![[0u8; 4]; width];
+        for _ in 0..height {
+            read_scanline(reader, &mut scanline)?;
+            pixels.extend(scanline.iter().map(|&rgbe| rgbe_to_color(rgbe)));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    // Writes flat (not run-length encoded) scanlines, which every reader accepts.
+    pub fn write_radiance(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            self.height, self.width
+        )?;
+        for &pixel in &self.pixels {
+            out.write_all(&color_to_rgbe(pixel))?;
+        }
+        Ok(())
+    }
+}
+
+fn read_scanline(reader: &mut impl Read, scanline: &mut [[u8; 4]]) -> io::Result<()> {
+    let width = scanline.len();
+    let mut first = [0u8; 4];
+    reader.read_exact(&mut first)?;
+
+    let is_rle = (8..0x8000).contains(&width) && first[0] == 2 && first[1] == 2;
+    if !is_rle {
+        scanline[0] = first;
+        for pixel in &mut scanline[1..] {
+            reader.read_exact(pixel)?;
+        }
+        return Ok(());
+    }
+
+    if (usize::from(first[2]) << 8 | usize::from(first[3])) != width {
+        return Err(invalid_data("scanline width mismatch"));
+    }
+
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let mut count = [0u8; 1];
+            reader.read_exact(&mut count)?;
+            let (run, count) = if count[0] > 128 {
+                (true, usize::from(count[0] - 128))
+            } else {
+                (false, usize::from(count[0]))
+            };
+
+            if count == 0 || x + count > width {
+                return Err(invalid_data("bad scanline run"));
+            }
+
+            if run {
+                let mut value = [0u8; 1];
+                reader.read_exact(&mut value)?;
+                for pixel in &mut scanline[x..x + count] {
+                    pixel[channel] = value[0];
+                }
+            } else {
+                let mut values = vec![0u8; count];
+                reader.read_exact(&mut values)?;
+                for (pixel, value) in scanline[x..x + count].iter_mut().zip(values) {
+                    pixel[channel] = value;
+                }
+            }
+            x += count;
+        }
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn rgbe_to_color(rgbe: [u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::default();
+    }
+
+    let scale = Float::powi(2., i32::from(rgbe[3]) - 136);
+    Color::new(
+        (rgbe[0] as Float + 0.5) * scale,
+        (rgbe[1] as Float + 0.5) * scale,
+        (rgbe[2] as Float + 0.5) * scale,
+    )
+}
+
+#[inline]
+fn color_to_rgbe(color: Color) -> [u8; 4] {
+    let max = color.x().max(color.y()).max(color.z());
+    if max.is_nan() || max < 1e-32 {
+        return [0; 4];
+    }
+
+    // Chooses the exponent so that `max` scales into [128, 256).
+    let exponent = max.log2().floor() as i32 + 1;
+    let scale = Float::powi(2., 8 - exponent);
+    let byte = |component: Float| (component.max(0.) * scale).min(255.) as u8;
+    [
+        byte(color.x()),
+        byte(color.y()),
+        byte(color.z()),
+        (exponent + 128).clamp(0, 255) as u8,
+    ]
+}
@@ -1,12 +1,22 @@
-use std::{default, sync::Arc};
+use std::sync::Arc;
 
-use crate::{dot, Interval, Material, Placeholder, Point3, Ray, Vec3};
+use crate::{dot, Aabb, Float, Interval, Material, Placeholder, Point3, Ray, RenderError, Vec3};
+#[cfg(feature = "simd")]
+use crate::{RayPacket, LANES};
 
+#[derive(Clone)]
 pub struct HitRecord {
     pub p: Point3,
     pub normal: Vec3,
     pub mat: Arc<dyn Material>,
-    pub t: f64,
+    pub t: Float,
+    // Surface coordinates of the hit point, in [0, 1] for textures.
+    pub u: Float,
+    pub v: Float,
+    // How the hit point moves as u and v grow, which orients normal and bump maps. Left at zero by
+    // surfaces that do not provide them.
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
     pub front_face: bool,
 }
 
@@ -15,17 +25,42 @@ impl Default for HitRecord {
         Self {
             p: Point3::default(),
             normal: Vec3::default(),
-            mat: Arc::new(Placeholder::default()),
-            t: f64::default(),
+            mat: Arc::new(Placeholder),
+            t: Float::default(),
+            u: Float::default(),
+            v: Float::default(),
+            dpdu: Vec3::default(),
+            dpdv: Vec3::default(),
             front_face: bool::default(),
         }
     }
 }
 
 impl HitRecord {
+    // A point on a surface with its outward normal, as spans report it.
+    pub fn on_surface(
+        ray: &Ray,
+        t: Float,
+        outward_normal: Vec3,
+        (u, v): (Float, Float),
+        mat: &Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            p: ray.at(t),
+            normal: outward_normal,
+            mat: mat.clone(),
+            t,
+            u,
+            v,
+            dpdu: Vec3::default(),
+            dpdv: Vec3::default(),
+            front_face: true,
+        }
+    }
+
     pub fn set_face_normal(&mut self, ray: &Ray, outward_normal: &Vec3) {
-        let front_face = dot(*ray.direction(), *outward_normal) < 0.0;
-        self.normal = if front_face {
+        self.front_face = dot(*ray.direction(), *outward_normal) < 0.0;
+        self.normal = if self.front_face {
             *outward_normal
         } else {
             -*outward_normal
@@ -33,6 +68,79 @@ impl HitRecord {
     }
 }
 
-pub trait Hittable {
+// A stretch of a ray inside a closed object, from the surface where it enters to the one where it
+// leaves. Unlike after `hit`, both normals point out of the object.
+#[derive(Clone)]
+pub struct Span {
+    pub entry: HitRecord,
+    pub exit: HitRecord,
+}
+
+pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool;
+
+    fn bounding_box(&self) -> Aabb;
+
+    // Packet counterpart of `hit` for the lanes in `mask`. Each lane's interval is narrowed to its
+    // closest hit so far and the returned mask holds the lanes that hit this object. Falls back to
+    // tracing the lanes one by one.
+    #[cfg(feature = "simd")]
+    fn hit_packet(
+        &self,
+        packet: &RayPacket,
+        mask: u8,
+        ray_t: &mut [Interval; LANES],
+        records: &mut [HitRecord; LANES],
+    ) -> u8 {
+        let mut hits = 0;
+        for k in (0..LANES).filter(|k| mask & 1 << k != 0) {
+            if self.hit(&packet.rays[k], ray_t[k], &mut records[k]) {
+                ray_t[k].max = records[k].t;
+                hits |= 1 << k;
+            }
+        }
+        hits
+    }
+
+    // The spans of the whole line through `ray` that lie inside the object, in order along the
+    // ray. Only closed objects have an inside, the others return `None`.
+    fn spans(&self, _ray: &Ray) -> Option<Vec<Span>> {
+        None
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        Ok(())
+    }
+}
+
+// The first surface among `spans` within `ray_t`, as `hit` reports it.
+pub fn closest_boundary(
+    spans: &[Span],
+    ray: &Ray,
+    ray_t: Interval,
+    record: &mut HitRecord,
+) -> bool {
+    let boundary = spans
+        .iter()
+        .flat_map(|span| [&span.entry, &span.exit])
+        .find(|boundary| ray_t.surrounds(boundary.t));
+    let Some(boundary) = boundary else {
+        return false;
+    };
+
+    *record = boundary.clone();
+    record.set_face_normal(ray, &boundary.normal);
+    true
+}
+
+// Pairs up the surfaces a ray crosses on its way through a closed object into the spans inside it.
+// An odd one out, from a ray grazing an edge, is dropped.
+pub fn spans_between(mut boundaries: Vec<HitRecord>) -> Vec<Span> {
+    boundaries.sort_by(|a, b| a.t.total_cmp(&b.t));
+    let mut boundaries = boundaries.into_iter();
+    let mut spans = Vec::new();
+    while let (Some(entry), Some(exit)) = (boundaries.next(), boundaries.next()) {
+        spans.push(Span { entry, exit });
+    }
+    spans
 }
@@ -1,19 +1,34 @@
 use std::sync::Arc;
 
-use crate::{HitRecord, Hittable, Interval};
+use crate::{Aabb, Context, HitRecord, Hittable, Interval, RenderError};
+#[cfg(feature = "simd")]
+use crate::{RayPacket, LANES};
 
 #[derive(Default)]
 pub struct HittableList {
     objects: Vec<Arc<dyn Hittable>>,
+    bbox: Aabb,
 }
 
 impl HittableList {
     pub fn add(&mut self, hittable: impl Hittable + 'static) {
+        self.bbox = Aabb::surrounding(&self.bbox, &hittable.bounding_box());
         self.objects.push(Arc::new(hittable));
     }
 
+    // For objects that are also referenced elsewhere, like the boundary of a medium.
+    pub fn add_shared(&mut self, hittable: Arc<dyn Hittable>) {
+        self.bbox = Aabb::surrounding(&self.bbox, &hittable.bounding_box());
+        self.objects.push(hittable);
+    }
+
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = Aabb::EMPTY;
+    }
+
+    pub fn into_objects(self) -> Vec<Arc<dyn Hittable>> {
+        self.objects
     }
 }
 
@@ -37,10 +52,36 @@ impl Hittable for HittableList {
                 record.normal = temp_record.normal;
                 record.mat = Arc::clone(&temp_record.mat);
                 record.t = temp_record.t;
+                (record.u, record.v) = (temp_record.u, temp_record.v);
+                (record.dpdu, record.dpdv) = (temp_record.dpdu, temp_record.dpdv);
                 record.front_face = temp_record.front_face;
             }
         }
 
         hit_anything
     }
+
+    #[cfg(feature = "simd")]
+    fn hit_packet(
+        &self,
+        packet: &RayPacket,
+        mask: u8,
+        ray_t: &mut [Interval; LANES],
+        records: &mut [HitRecord; LANES],
+    ) -> u8 {
+        self.objects.iter().fold(0, |hits, object| {
+            hits | object.hit_packet(packet, mask, ray_t, records)
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        for (i, object) in self.objects.iter().enumerate() {
+            object.validate().with_context(|| format!("object #{i}"))?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,216 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{invalid_data, Color, Context, FileFormat, Float, Framebuffer, HdrImage, RenderError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    // 8-bit and gamma encoded, as the renderer has always written.
+    Ppm,
+    // Portable float map, linear 32-bit floats.
+    Pfm,
+    // Radiance RGBE, linear.
+    Hdr,
+}
+
+impl FileFormat for ImageFormat {
+    const EXTENSIONS: &'static str = "'.ppm', '.pfm' or '.hdr'";
+
+    fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension {
+            "ppm" => Some(ImageFormat::Ppm),
+            "pfm" => Some(ImageFormat::Pfm),
+            "hdr" => Some(ImageFormat::Hdr),
+            _ => None,
+        }
+    }
+}
+
+impl ImageFormat {
+    pub fn write(self, image: &Framebuffer, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            ImageFormat::Ppm => image.write_ppm(out)?,
+            ImageFormat::Pfm => write_pfm(image, out)?,
+            ImageFormat::Hdr => HdrImage::from(image).write_radiance(out)?,
+        }
+        out.flush()
+    }
+
+    pub fn read(self, input: &mut impl BufRead) -> io::Result<Framebuffer> {
+        match self {
+            ImageFormat::Ppm => read_ppm(input),
+            ImageFormat::Pfm => read_pfm(input),
+            ImageFormat::Hdr => Ok(HdrImage::read_radiance(input)?.into()),
+        }
+    }
+}
+
+// Both pick the format from the extension of `path`.
+pub fn read_image(path: &Path) -> Result<Framebuffer, RenderError> {
+    let format = ImageFormat::of(path)?;
+    File::open(path)
+        .and_then(|file| format.read(&mut BufReader::new(file)))
+        .with_context(|| format!("failed to read {}", path.display()))
+}
+
+pub fn write_image(image: &Framebuffer, path: &Path) -> Result<(), RenderError> {
+    let format = ImageFormat::of(path)?;
+    File::create(path)
+        .and_then(|file| format.write(image, &mut BufWriter::new(file)))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+impl From<&Framebuffer> for HdrImage {
+    fn from(image: &Framebuffer) -> Self {
+        HdrImage {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            pixels: image.pixels().to_vec(),
+        }
+    }
+}
+
+impl From<HdrImage> for Framebuffer {
+    fn from(image: HdrImage) -> Self {
+        Framebuffer::from_pixels(image.width as u32, image.height as u32, image.pixels)
+            .expect("the image has width x height pixels")
+    }
+}
+
+// Header tokens are separated by any whitespace; a single whitespace byte follows the last one.
+fn read_header<const N: usize>(input: &mut impl BufRead) -> io::Result<[String; N]> {
+    let mut tokens: [String; N] = std::array::from_fn(|_| String::new());
+    for token in &mut tokens {
+        let mut byte = [0u8; 1];
+        loop {
+            input.read_exact(&mut byte)?;
+            match byte[0] {
+                b'#' if token.is_empty() => {
+                    input.read_line(&mut String::new())?;
+                }
+                b if b.is_ascii_whitespace() => {
+                    if !token.is_empty() {
+                        break;
+                    }
+                }
+                b => token.push(b as char),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// Images larger than this, 16384x8192 pixels, are refused before anything is allocated for them,
+// since their size comes straight from the header.
+const MAX_PIXELS: usize = 1 << 27;
+
+pub(crate) fn pixel_count(width: usize, height: usize) -> io::Result<usize> {
+    width
+        .checked_mul(height)
+        .filter(|&count| count <= MAX_PIXELS)
+        .ok_or_else(|| invalid_data(&format!("an image of {width}x{height} pixels is too large")))
+}
+
+fn parse<T: std::str::FromStr>(token: &str, what: &str) -> io::Result<T> {
+    token
+        .parse()
+        .map_err(|_| invalid_data(&format!("bad {what} '{token}'")))
+}
+
+// Reads plain (P3) and binary (P6) pixmaps and undoes the gamma encoding of `write_color`.
+fn read_ppm(input: &mut impl BufRead) -> io::Result<Framebuffer> {
+    let [magic, width, height, max] = read_header(input)?;
+    let width: u32 = parse(&width, "width")?;
+    let height: u32 = parse(&height, "height")?;
+    let max: u16 = parse(&max, "maximum value")?;
+    if max == 0 || max > 255 {
+        return Err(invalid_data("only 8-bit pixmaps are supported"));
+    }
+
+    let count = pixel_count(width as usize, height as usize)? * 3;
+    let values: Vec<u8> = match magic.as_str() {
+        "P3" => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            text.split_whitespace()
+                .take(count)
+                .map(|value| parse(value, "value"))
+                .collect::<io::Result<_>>()?
+        }
+        "P6" => {
+            let mut bytes = vec![0; count];
+            input.read_exact(&mut bytes)?;
+            bytes
+        }
+        _ => return Err(invalid_data("not a P3 or P6 pixmap")),
+    };
+    if values.len() != count {
+        return Err(invalid_data("truncated pixmap"));
+    }
+
+    // The centre of the range `to_rgb8` maps to the value, so that writing it back gives the same
+    // value.
+    let decode = |value: u8| {
+        let gamma = (value as Float + 0.5) / (max as Float + 1.);
+        gamma * gamma
+    };
+    let pixels = values
+        .chunks_exact(3)
+        .map(|rgb| Color::new(decode(rgb[0]), decode(rgb[1]), decode(rgb[2])))
+        .collect();
+    Ok(Framebuffer::from_pixels(width, height, pixels).expect("one pixel per value triple"))
+}
+
+// Colour PFM: "PF", the size, then a scale whose sign gives the byte order, followed by the rows
+// from bottom to top.
+fn read_pfm(input: &mut impl BufRead) -> io::Result<Framebuffer> {
+    let [magic, width, height, scale] = read_header(input)?;
+    if magic != "PF" {
+        return Err(invalid_data("not a colour PFM image"));
+    }
+    let width: u32 = parse(&width, "width")?;
+    let height: u32 = parse(&height, "height")?;
+    let little_endian = parse::<f32>(&scale, "scale")? < 0.;
+    pixel_count(width as usize, height as usize)?;
+
+    let mut image = Framebuffer::new(width, height);
+    let mut row = vec![0u8; width as usize * 12];
+    for y in (0..height).rev() {
+        input.read_exact(&mut row)?;
+        for (x, pixel) in row.chunks_exact(12).enumerate() {
+            let component = |i: usize| {
+                let bytes = pixel[i * 4..i * 4 + 4].try_into().unwrap();
+                let value = if little_endian {
+                    f32::from_le_bytes(bytes)
+                } else {
+                    f32::from_be_bytes(bytes)
+                };
+                value as Float
+            };
+            image.set_pixel(
+                x as u32,
+                y,
+                Color::new(component(0), component(1), component(2)),
+            );
+        }
+    }
+    Ok(image)
+}
+
+// PFM always stores 32-bit floats, which is already `Float` with the `f32` feature.
+#[cfg_attr(feature = "f32", allow(clippy::unnecessary_cast))]
+fn write_pfm(image: &Framebuffer, out: &mut impl Write) -> io::Result<()> {
+    write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+    for y in (0..image.height()).rev() {
+        for x in 0..image.width() {
+            let pixel = image.pixel(x, y);
+            for component in [pixel.x(), pixel.y(), pixel.z()] {
+                out.write_all(&(component as f32).to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use crate::{
+    degrees_to_radians, Aabb, Context, Float, HitRecord, Hittable, Interval, Point3, Ray,
+    RenderError, Span, Vec3, INFINITY,
+};
+
+// Moves an object by `offset`. The ray is moved the other way instead, so the object itself is
+// shared and never copied.
+pub struct Translate {
+    object: Arc<dyn Hittable>,
+    offset: Vec3,
+    bbox: Aabb,
+}
+
+impl Translate {
+    pub fn new(object: Arc<dyn Hittable>, offset: Vec3) -> Self {
+        let bbox = object.bounding_box().translated(offset);
+        Self {
+            object,
+            offset,
+            bbox,
+        }
+    }
+}
+
+impl Hittable for Translate {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        let moved = Ray::with_wavelength(
+            *ray.origin() - self.offset,
+            *ray.direction(),
+            ray.wavelength(),
+        );
+        if !self.object.hit(&moved, ray_t, record) {
+            return false;
+        }
+
+        record.p += self.offset;
+        true
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        let moved = Ray::with_wavelength(
+            *ray.origin() - self.offset,
+            *ray.direction(),
+            ray.wavelength(),
+        );
+        let mut spans = self.object.spans(&moved)?;
+        for span in &mut spans {
+            span.entry.p += self.offset;
+            span.exit.p += self.offset;
+        }
+        Some(spans)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.object.validate().context("translated object")
+    }
+}
+
+// Rotates an object by `angle` degrees around the y axis.
+pub struct RotateY {
+    object: Arc<dyn Hittable>,
+    sin_theta: Float,
+    cos_theta: Float,
+    bbox: Aabb,
+}
+
+impl RotateY {
+    pub fn new(object: Arc<dyn Hittable>, angle: Float) -> Self {
+        let (sin_theta, cos_theta) = degrees_to_radians(angle).sin_cos();
+        let mut rotate = Self {
+            object,
+            sin_theta,
+            cos_theta,
+            bbox: Aabb::EMPTY,
+        };
+
+        // The box around the eight rotated corners of the original box.
+        let bbox = rotate.object.bounding_box();
+        let mut min = Point3::new(INFINITY, INFINITY, INFINITY);
+        let mut max = Point3::new(-INFINITY, -INFINITY, -INFINITY);
+        for x in [bbox.x.min, bbox.x.max] {
+            for y in [bbox.y.min, bbox.y.max] {
+                for z in [bbox.z.min, bbox.z.max] {
+                    let corner = rotate.to_world(Vec3::new(x, y, z));
+                    for axis in 0..3 {
+                        min[axis] = min[axis].min(corner[axis]);
+                        max[axis] = max[axis].max(corner[axis]);
+                    }
+                }
+            }
+        }
+        rotate.bbox = Aabb::from_points(min, max);
+        rotate
+    }
+
+    fn to_world(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x() + self.sin_theta * v.z(),
+            v.y(),
+            -self.sin_theta * v.x() + self.cos_theta * v.z(),
+        )
+    }
+
+    fn to_object(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x() - self.sin_theta * v.z(),
+            v.y(),
+            self.sin_theta * v.x() + self.cos_theta * v.z(),
+        )
+    }
+}
+
+impl Hittable for RotateY {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        let rotated = Ray::with_wavelength(
+            self.to_object(*ray.origin()),
+            self.to_object(*ray.direction()),
+            ray.wavelength(),
+        );
+        if !self.object.hit(&rotated, ray_t, record) {
+            return false;
+        }
+
+        record.p = self.to_world(record.p);
+        record.normal = self.to_world(record.normal);
+        record.dpdu = self.to_world(record.dpdu);
+        record.dpdv = self.to_world(record.dpdv);
+        true
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        let rotated = Ray::with_wavelength(
+            self.to_object(*ray.origin()),
+            self.to_object(*ray.direction()),
+            ray.wavelength(),
+        );
+        let mut spans = self.object.spans(&rotated)?;
+        for record in spans.iter_mut().flat_map(|s| [&mut s.entry, &mut s.exit]) {
+            record.p = self.to_world(record.p);
+            record.normal = self.to_world(record.normal);
+            record.dpdu = self.to_world(record.dpdu);
+            record.dpdv = self.to_world(record.dpdv);
+        }
+        Some(spans)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.object.validate().context("rotated object")
+    }
+}
@@ -1,45 +1,53 @@
-use crate::INFINITY;
+use crate::{Float, INFINITY};
 
+#[derive(Clone, Copy, Debug)]
 pub struct Interval {
-    pub min: f64,
-    pub max: f64,
+    pub min: Float,
+    pub max: Float,
 }
 
 impl Default for Interval {
     fn default() -> Self {
         Self {
             min: INFINITY,
-            max: f64::NEG_INFINITY,
+            max: Float::NEG_INFINITY,
         }
     }
 }
 impl Interval {
     pub const EMPTY: Interval = Interval {
-        min: f64::INFINITY,
-        max: f64::NEG_INFINITY,
+        min: Float::INFINITY,
+        max: Float::NEG_INFINITY,
     };
     pub const UNIVERSE: Interval = Interval {
-        min: f64::NEG_INFINITY,
-        max: f64::INFINITY,
+        min: Float::NEG_INFINITY,
+        max: Float::INFINITY,
     };
 
-    pub fn new(min: f64, max: f64) -> Self {
+    pub fn new(min: Float, max: Float) -> Self {
         Self { min, max }
     }
 
-    pub fn size(&self) -> f64 {
+    pub fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
+    pub fn size(&self) -> Float {
         self.max - self.min
     }
 
-    pub fn contains(&self, x: f64) -> bool {
+    pub fn contains(&self, x: Float) -> bool {
         self.min <= x && x <= self.max
     }
 
-    pub fn surrounds(&self, x: f64) -> bool {
+    pub fn surrounds(&self, x: Float) -> bool {
         self.min < x && x < self.max
     }
 
-    pub fn clamp(&self, x: f64) -> f64 {
+    pub fn clamp(&self, x: Float) -> Float {
         if x < self.min {
             return self.min;
         }
@@ -50,4 +58,9 @@ impl Interval {
 
         x
     }
+
+    pub fn expand(&self, delta: Float) -> Interval {
+        let padding = delta / 2.;
+        Interval::new(self.min - padding, self.max + padding)
+    }
 }
@@ -1,23 +1,103 @@
+mod aabb;
+mod animation;
 mod args;
+mod bvh;
 mod camera;
+mod camera_builder;
 mod color;
+mod cone;
+mod config;
+mod constant_medium;
+mod csg;
+mod cuboid;
+mod cylinder;
+mod disk;
+mod distributed;
+mod environment;
+mod error;
+mod file_format;
+mod framebuffer;
+mod gltf;
+mod hdr;
 mod hittable;
 mod hittable_list;
+mod image_file;
+mod instance;
 mod interval;
 mod material;
+mod mesh;
+mod mesh_file;
+mod onb;
+mod perlin;
+mod ply;
+mod projection;
+mod quad;
 mod ray;
 mod rtweekend;
+mod scene_file;
+mod scenes;
+mod sdf;
+mod server;
+#[cfg(feature = "simd")]
+mod simd;
+mod spectrum;
 mod sphere;
+mod stats;
+mod stl;
+mod surface_detail;
+mod terminal;
+mod texture;
+mod torus;
 mod vec3;
 
+pub use aabb::*;
+pub use animation::*;
 pub use args::*;
+pub use bvh::*;
 pub use camera::*;
+pub use camera_builder::*;
 pub use color::*;
+pub use cone::*;
+pub use config::*;
+pub use constant_medium::*;
+pub use csg::*;
+pub use cuboid::*;
+pub use cylinder::*;
+pub use disk::*;
+pub use distributed::*;
+pub use environment::*;
+pub use error::*;
+pub use file_format::*;
+pub use framebuffer::*;
+pub use gltf::*;
+pub use hdr::*;
 pub use hittable::*;
 pub use hittable_list::*;
+pub use image_file::*;
+pub use instance::*;
 pub use interval::*;
 pub use material::*;
+pub use mesh::*;
+pub use mesh_file::*;
+pub use onb::*;
+pub use perlin::*;
+pub use ply::*;
+pub use projection::*;
+pub use quad::*;
 pub use ray::*;
 pub use rtweekend::*;
+pub use scene_file::*;
+pub use scenes::*;
+pub use sdf::*;
+pub use server::*;
+#[cfg(feature = "simd")]
+pub use simd::*;
+pub use spectrum::*;
 pub use sphere::*;
+pub use stats::*;
+pub use stl::*;
+pub use surface_detail::*;
+pub use terminal::*;
+pub use texture::*;
+pub use torus::*;
 pub use vec3::*;
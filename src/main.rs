@@ -1,79 +1,236 @@
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::ControlFlow,
+    path::Path,
+    process::ExitCode,
+};
 
-use clap::Parser;
 use raytracing::{
-    random_f64, random_f64_range, Args, Camera, Color, Dielectric, HittableList, Lambertian, Metal,
-    Point3, Sphere, PI,
+    frame_path, parse_layered, read_image, run_coordinator, run_worker, serve, write_image, Args,
+    BvhNode, Camera, CameraBuilder, CameraSettings, CameraTrack, Command, Context, ConvertArgs,
+    FileFormat, Float, Framebuffer, Hittable, HittableList, ImageFormat, InfoArgs, Keyframe,
+    RenderArgs, RenderError, RenderStats, SceneFile, SceneFormat, TerminalPreview, ValidateArgs,
 };
 
-fn main() {
-    let args = Args::parse();
-    let aspect_ratio = args.ratio_width / args.ratio_height;
-    let image_width = args.image_width;
-    let samples_per_pixel = args.samples_per_pixel;
-    let max_depth = args.max_depth;
-    let vfov = args.vfov;
-    let lookfrom = args.lookfrom;
-    let lookat = args.lookat;
-    let vup = args.vup;
-    let defocus_angle = args.defocus_angle;
-    let focus_dist = args.focus_dist;
-    let mut cam = Camera::setup(
-        aspect_ratio,
-        image_width,
-        samples_per_pixel,
-        max_depth,
-        vfov,
-        lookfrom,
-        lookat,
-        vup,
-        focus_dist,
-        defocus_angle,
-    );
+fn main() -> ExitCode {
+    let mut layered = match parse_layered::<Args>("render") {
+        Ok(layered) => layered,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if layered.print_config {
+        print!("{}", layered.resolved);
+        return ExitCode::SUCCESS;
+    }
+    let args = &mut layered.args;
+    if let Command::Render(render_args) = &mut args.command {
+        render_args.resolved = layered.resolved;
+    }
+
+    let result = match &args.command {
+        Command::Render(render_args) => render(render_args),
+        Command::Validate(validate_args) => validate(validate_args),
+        Command::Info(info_args) => info(info_args),
+        Command::Convert(convert_args) => convert(convert_args),
+        Command::Serve(serve_args) => serve(serve_args),
+        Command::Worker(worker_args) => run_worker(worker_args),
+        Command::Coordinator(coordinator_args) => run_coordinator(coordinator_args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn render(args: &RenderArgs) -> Result<(), RenderError> {
+    let (mut cam, world) = args.load_scene()?;
+    world.validate().context("invalid scene")?;
+    let world = BvhNode::new(world);
+
+    let Some(frames) = args.frames else {
+        // Checked before rendering so that a bad extension does not waste the render.
+        let output = args.output.as_deref().map(Path::new);
+        output.map(ImageFormat::of).transpose()?;
+
+        let (image, stats) = render_image(args, &mut cam, &world).context("failed to render")?;
+        match output {
+            Some(path) => write_image(&image, path)?,
+            None => ImageFormat::Ppm
+                .write(&image, &mut io::stdout().lock())
+                .context("failed to write the image")?,
+        }
+        return report_stats(args, &stats, None);
+    };
+
+    let track = match &args.keyframes {
+        Some(path) => CameraTrack::load(path, args.interpolation)?,
+        None => CameraTrack::turntable(Keyframe::from_camera(0., &cam), cam.vup, frames),
+    };
+
+    let pattern = args.output.as_deref().unwrap_or("frame_####.ppm");
+    ImageFormat::of(&frame_path(pattern, 0))?;
+    for frame in 0..frames {
+        track.at(frame as Float).apply(&mut cam);
+        cam.validate()
+            .with_context(|| format!("invalid camera on frame {frame}"))?;
+
+        let path = frame_path(pattern, frame);
+        if !args.quiet {
+            eprintln!("- frame {} -> {}", frame, path.display());
+        }
+        let (image, stats) = render_image(args, &mut cam, &world)
+            .with_context(|| format!("failed to render frame {frame}"))?;
+        write_image(&image, &path)?;
+        report_stats(args, &stats, Some(frame))?;
+    }
+
+    Ok(())
+}
+
+fn render_image(
+    args: &RenderArgs,
+    cam: &mut Camera,
+    world: &impl Hittable,
+) -> io::Result<(Framebuffer, RenderStats)> {
+    if !args.preview_terminal {
+        return Ok(cam.render_image(world));
+    }
+
+    // The image may be going to stdout, so the preview is drawn on stderr.
+    let mut preview = TerminalPreview::for_terminal();
+    let mut stderr = io::stderr().lock();
+    let mut error = None;
+    let rendered = cam.render_progressive(world, |passes, image| {
+        let caption = format!("{passes}/{} samples per pixel", args.samples_per_pixel);
+        match preview.draw(&mut stderr, image, &caption) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => {
+                error = Some(e);
+                ControlFlow::Break(())
+            }
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(rendered),
+    }
+}
+
+// Animations get one statistics file per frame, named like the frames themselves.
+fn report_stats(
+    args: &RenderArgs,
+    stats: &RenderStats,
+    frame: Option<u32>,
+) -> Result<(), RenderError> {
+    match &args.stats {
+        None => Ok(()),
+        Some(None) => stats
+            .write_table(&mut io::stderr().lock())
+            .context("failed to print the render statistics"),
+        Some(Some(path)) => {
+            let path = match frame {
+                Some(frame) => frame_path(&path.to_string_lossy(), frame),
+                None => path.clone(),
+            };
+            File::create(&path)
+                .and_then(|file| {
+                    let mut out = BufWriter::new(file);
+                    stats.write_json(&mut out)?;
+                    out.flush()
+                })
+                .with_context(|| format!("failed to write {}", path.display()))
+        }
+    }
+}
 
-    let mut world = HittableList::default();
-    let material_ground = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
-
-    world.add(Sphere::new(
-        &Point3::new(0., -1000., 0.),
-        1000.,
-        material_ground,
-    ));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let choose_material = random_f64();
-            let center = Point3::new(
-                f64::from(a) + 0.9 * random_f64(),
-                0.2,
-                f64::from(b) + 0.9 * random_f64(),
-            );
-
-            if (center - Point3::new(4., 0.2, 0.)).length() > 0.9 {
-                if choose_material < 0.8 {
-                    let albedo = Color::random() * Color::random();
-                    let sphere_material = Arc::new(Lambertian::new(albedo));
-                    world.add(Sphere::new(&center, 0.2, sphere_material));
-                } else if choose_material < 0.95 {
-                    let albedo = Color::random_with_range(0.5, 1.);
-                    let fuzz = random_f64_range(0., 0.5);
-                    let sphere_material = Arc::new(Metal::new(albedo, fuzz));
-                    world.add(Sphere::new(&center, 0.2, sphere_material));
-                } else {
-                    let sphere_material = Arc::new(Dielectric::new(1.5));
-                    world.add(Sphere::new(&center, 0.2, sphere_material));
-                }
+// Reports every file, then fails if any of them was invalid.
+fn validate(args: &ValidateArgs) -> Result<(), RenderError> {
+    let mut invalid = 0;
+    for path in &args.scenes {
+        match load_checked(path) {
+            Ok((scene, ..)) => println!("{}: ok, {} objects", path.display(), scene.objects.len()),
+            Err(e) => {
+                println!("{}: {e}", path.display());
+                invalid += 1;
             }
         }
     }
 
-    let material_1 = Arc::new(Dielectric::new(1.5));
-    let material_2 = Arc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
-    let material_3 = Arc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.));
+    if invalid > 0 {
+        return Err(RenderError::InvalidScene(format!(
+            "{invalid} of {} files failed to validate",
+            args.scenes.len()
+        )));
+    }
+    Ok(())
+}
 
-    world.add(Sphere::new(&Point3::new(0., 1., 0.), 1.0, material_1));
-    world.add(Sphere::new(&Point3::new(-4., 1., 0.), 1.0, material_2));
-    world.add(Sphere::new(&Point3::new(4., 1., 0.), 1.0, material_3));
+// Loads the scene and builds its camera and world, which runs every check a render would.
+fn load_checked(path: &Path) -> Result<(SceneFile, Camera, HittableList), RenderError> {
+    let scene = SceneFile::load(path)?;
+    let builder = CameraBuilder::default().quiet(true);
+    let cam = scene.camera(builder, &CameraSettings::default())?;
+    let world = scene
+        .build()
+        .and_then(|world| world.validate().map(|()| world))
+        .context("invalid scene")?;
+    Ok((scene, cam, world))
+}
 
-    cam.render(world);
+fn info(args: &InfoArgs) -> Result<(), RenderError> {
+    let (scene, cam, world) = load_checked(&args.scene)?;
+
+    let mut objects = BTreeMap::new();
+    let mut materials = BTreeMap::new();
+    for object in &scene.objects {
+        *objects.entry(object.name()).or_insert(0) += 1;
+        for material in object.materials() {
+            *materials.entry(material.name()).or_insert(0) += 1;
+        }
+    }
+    let material_count = materials.values().sum::<usize>();
+    let counts = |counts: BTreeMap<&str, usize>| {
+        let counts: Vec<_> = counts
+            .iter()
+            .map(|(name, count)| format!("{count} {name}"))
+            .collect();
+        counts.join(", ")
+    };
+
+    println!("objects:     {} ({})", scene.objects.len(), counts(objects));
+    println!("materials:   {material_count} ({})", counts(materials));
+
+    let bbox = world.bounding_box();
+    if scene.objects.is_empty() {
+        println!("bounds:      empty");
+    } else {
+        println!(
+            "bounds:      x {} to {}, y {} to {}, z {} to {}",
+            bbox.x.min, bbox.x.max, bbox.y.min, bbox.y.max, bbox.z.min, bbox.z.max
+        );
+    }
+
+    let (width, height) = cam.image_size();
+    println!(
+        "camera:      {width}x{height}, {} samples per pixel, max depth {}",
+        cam.samples_per_pixel, cam.max_depth
+    );
+    println!("environment: {}", scene.environment.map);
+    Ok(())
+}
+
+fn convert(args: &ConvertArgs) -> Result<(), RenderError> {
+    if SceneFormat::from_path(&args.input).is_some() {
+        SceneFile::load(&args.input)?.save(&args.output)
+    } else {
+        write_image(&read_image(&args.input)?, &args.output)
+    }
 }
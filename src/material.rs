@@ -1,17 +1,42 @@
+use std::sync::Arc;
+
 use crate::{
-    dot, random_f64, random_unit_vector, reflect, refract, unit_vector, Color, HitRecord, Ray, Vec3,
+    dot, random_f64, random_unit_vector, reflect, refract, unit_vector, Color, Float, HitRecord,
+    Point3, Ray, RefractiveIndex, RenderError, SolidColor, Texture, Vec3, LAMBDA_MAX, LAMBDA_MIN,
+    LAMBDA_REFERENCE, PI,
 };
 
-pub trait Material {
+pub trait Material: Send + Sync {
     fn scatter(
         &self,
-        r_in: &mut Ray,
-        record: &HitRecord,
-        attenuation: &mut Color,
-        scattered: &mut Ray,
+        _r_in: &mut Ray,
+        _record: &HitRecord,
+        _attenuation: &mut Color,
+        _scattered: &mut Ray,
     ) -> bool {
         false
     }
+
+    // Solid angle density with which `scatter` picks `scattered`. Zero marks a specular lobe that
+    // cannot be combined with light sampling.
+    fn scattering_pdf(&self, _r_in: &Ray, _record: &HitRecord, _scattered: &Ray) -> Float {
+        0.
+    }
+
+    // The normal the hit point is shaded with, which may add detail the geometry lacks. It is
+    // applied before `scatter`.
+    fn shading_normal(&self, record: &HitRecord) -> Vec3 {
+        record.normal
+    }
+
+    // Radiance given off at the hit point, whether or not the material also scatters.
+    fn emitted(&self, _u: Float, _v: Float, _p: &Point3) -> Color {
+        Color::new(0., 0., 0.)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -20,12 +45,16 @@ pub struct Placeholder;
 impl Material for Placeholder {}
 
 pub struct Lambertian {
-    albedo: Color,
+    texture: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
-        Self { albedo }
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(texture: Arc<dyn Texture>) -> Self {
+        Self { texture }
     }
 }
 
@@ -43,23 +72,38 @@ impl Material for Lambertian {
             scatter_direction = record.normal;
         }
 
-        *scattered = Ray::new(record.p, scatter_direction);
-        *attenuation = self.albedo;
+        *scattered = Ray::with_wavelength(record.p, scatter_direction, r_in.wavelength());
+        *attenuation = self.texture.value(record.u, record.v, &record.p);
         true
     }
+
+    fn scattering_pdf(&self, _r_in: &Ray, record: &HitRecord, scattered: &Ray) -> Float {
+        let cos_theta = dot(record.normal, unit_vector(*scattered.direction()));
+        Float::max(cos_theta, 0.) / PI
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.texture.validate()
+    }
 }
 
 pub struct Metal {
-    albedo: Color,
-    fuzz: f64,
+    albedo: Arc<dyn Texture>,
+    fuzz: Float,
 }
 
 impl Metal {
-    pub fn new(albedo: Color, fuzz: f64) -> Self {
-        Self {
-            albedo,
-            fuzz: if fuzz < 1.0 { fuzz } else { 1.0 },
+    pub fn new(albedo: Color, fuzz: Float) -> Result<Self, RenderError> {
+        validate_albedo(albedo)?;
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, fuzz: Float) -> Result<Self, RenderError> {
+        if !(0. ..=1.).contains(&fuzz) {
+            return Err(RenderError::InvalidFuzz(fuzz));
         }
+
+        Ok(Self { albedo, fuzz })
     }
 }
 impl Material for Metal {
@@ -72,26 +116,51 @@ impl Material for Metal {
     ) -> bool {
         let mut reflected = reflect(*r_in.direction(), record.normal);
         reflected = unit_vector(reflected) + (self.fuzz * random_unit_vector());
-        *scattered = Ray::new(record.p, reflected);
-        *attenuation = self.albedo;
+        *scattered = Ray::with_wavelength(record.p, reflected, r_in.wavelength());
+        *attenuation = self.albedo.value(record.u, record.v, &record.p);
 
         dot(*scattered.direction(), record.normal) > 0.
     }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.albedo.validate()
+    }
 }
 
 pub struct Dielectric {
-    pub refractive_index: f64,
+    pub refractive_index: RefractiveIndex,
 }
 
 impl Dielectric {
-    pub fn new(refractive_index: f64) -> Self {
-        Self { refractive_index }
+    pub fn new(refractive_index: Float) -> Result<Self, RenderError> {
+        Self::with_dispersion(RefractiveIndex::Constant(refractive_index))
     }
 
-    pub fn reflectance(&self, cosine: f64, refractive_index: f64) -> f64 {
+    // Dispersive indices are only checked at the ends of the visible range, which is enough for
+    // the monotonic Cauchy and Sellmeier curves of real glasses.
+    pub fn with_dispersion(refractive_index: RefractiveIndex) -> Result<Self, RenderError> {
+        for wavelength in [LAMBDA_MIN, LAMBDA_REFERENCE, LAMBDA_MAX] {
+            let n = refractive_index.at(wavelength);
+            if !(n.is_finite() && n > 0.) {
+                return Err(RenderError::InvalidRefractiveIndex(n));
+            }
+        }
+
+        Ok(Self { refractive_index })
+    }
+
+    pub fn cauchy(a: Float, b: Float) -> Result<Self, RenderError> {
+        Self::with_dispersion(RefractiveIndex::Cauchy { a, b })
+    }
+
+    pub fn sellmeier(b: [Float; 3], c: [Float; 3]) -> Result<Self, RenderError> {
+        Self::with_dispersion(RefractiveIndex::Sellmeier { b, c })
+    }
+
+    pub fn reflectance(&self, cosine: Float, refractive_index: Float) -> Float {
         let mut r0 = (1. - refractive_index) / (1. + refractive_index);
         r0 = r0 * r0;
-        r0 + (1. - r0) * f64::powi(1. - cosine, 5)
+        r0 + (1. - r0) * Float::powi(1. - cosine, 5)
     }
 }
 impl Material for Dielectric {
@@ -103,15 +172,18 @@ impl Material for Dielectric {
         scattered: &mut Ray,
     ) -> bool {
         *attenuation = Color::new(1., 1., 1.);
+        let refractive_index = self
+            .refractive_index
+            .at(r_in.wavelength().unwrap_or(LAMBDA_REFERENCE));
         let ri = if record.front_face {
-            1. / self.refractive_index
+            1. / refractive_index
         } else {
-            self.refractive_index
+            refractive_index
         };
 
         let unit_direction = unit_vector(*r_in.direction());
 
-        let cos_theta = f64::min(dot(-unit_direction, record.normal), 1.0);
+        let cos_theta = Float::min(dot(-unit_direction, record.normal), 1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = ri * sin_theta > 1.0;
@@ -122,7 +194,78 @@ impl Material for Dielectric {
             refract(unit_direction, record.normal, ri)
         };
 
-        *scattered = Ray::new(record.p, direction);
+        *scattered = Ray::with_wavelength(record.p, direction, r_in.wavelength());
+        true
+    }
+}
+
+// An area light. It emits from both sides and absorbs every ray that hits it.
+pub struct DiffuseLight {
+    texture: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(emit)))
+    }
+
+    pub fn from_texture(texture: Arc<dyn Texture>) -> Self {
+        Self { texture }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn emitted(&self, u: Float, v: Float, p: &Point3) -> Color {
+        self.texture.value(u, v, p)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.texture.validate()
+    }
+}
+
+// Phase function of participating media: scatters uniformly in every direction.
+pub struct Isotropic {
+    texture: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(texture: Arc<dyn Texture>) -> Self {
+        Self { texture }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        r_in: &mut Ray,
+        record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        *scattered = Ray::with_wavelength(record.p, random_unit_vector(), r_in.wavelength());
+        *attenuation = self.texture.value(record.u, record.v, &record.p);
         true
     }
+
+    fn scattering_pdf(&self, _r_in: &Ray, _record: &HitRecord, _scattered: &Ray) -> Float {
+        1. / (4. * PI)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.texture.validate()
+    }
+}
+
+pub(crate) fn validate_albedo(albedo: Color) -> Result<(), RenderError> {
+    let valid = |c: Float| c.is_finite() && c >= 0.;
+    if valid(albedo.x()) && valid(albedo.y()) && valid(albedo.z()) {
+        Ok(())
+    } else {
+        Err(RenderError::InvalidAlbedo(albedo))
+    }
 }
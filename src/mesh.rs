@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use crate::{
+    cross, dot, ensure_finite, stats, unit_vector, validate_albedo, Aabb, BvhNode, Color, Context,
+    Float, HitRecord, Hittable, HittableList, Interval, Lambertian, Material, Point3, Ray,
+    RenderError, Vec3, VertexColors,
+};
+#[cfg(feature = "simd")]
+use crate::{RayPacket, LANES};
+
+// Triangles sharing a list of vertices, as read from a mesh file. Normals, colors and texture
+// coordinates are either given for every vertex or left empty.
+#[derive(Clone, Debug, Default)]
+pub struct TriangleMesh {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<Color>,
+    pub texcoords: Vec<(Float, Float)>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl TriangleMesh {
+    // Each vertex gets the average of the normals of the triangles around it, weighted by their
+    // area, which shades the mesh smoothly. Vertices that are not shared keep flat facets.
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![Vec3::default(); self.positions.len()];
+        for &[a, b, c] in &self.triangles {
+            let p = &self.positions;
+            let normal = cross(p[b] - p[a], p[c] - p[a]);
+            for i in [a, b, c] {
+                normals[i] += normal;
+            }
+        }
+        self.normals = normals
+            .into_iter()
+            .map(|n| {
+                if n.length_squared() > 0. {
+                    unit_vector(n)
+                } else {
+                    n
+                }
+            })
+            .collect();
+    }
+
+    pub fn validate(&self) -> Result<(), RenderError> {
+        let vertices = self.positions.len();
+        for (name, len) in [
+            ("normals", self.normals.len()),
+            ("colors", self.colors.len()),
+            ("texture coordinates", self.texcoords.len()),
+        ] {
+            if len != 0 && len != vertices {
+                return Err(RenderError::InvalidMesh(format!(
+                    "{len} {name} for {vertices} vertices"
+                )));
+            }
+        }
+        for &position in &self.positions {
+            ensure_finite(position, "mesh vertex")?;
+        }
+        for &color in &self.colors {
+            validate_albedo(color)?;
+        }
+        match self.triangles.iter().flatten().find(|&&i| i >= vertices) {
+            Some(i) => Err(RenderError::InvalidMesh(format!(
+                "vertex index {i} out of range for {vertices} vertices"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+// A mesh ready to render, with its triangles in a BVH of their own.
+pub struct Mesh {
+    triangles: BvhNode,
+}
+
+impl Mesh {
+    // Without a material, the mesh is diffuse and takes the colors of its vertices, or grey if it
+    // has none.
+    pub fn new(
+        mut mesh: TriangleMesh,
+        mat: Option<Arc<dyn Material>>,
+    ) -> Result<Self, RenderError> {
+        mesh.validate()?;
+        if mesh.normals.is_empty() {
+            mesh.compute_normals();
+        }
+        // Vertex colors are blended by the barycentric coordinates of the hit point, which take
+        // the place of the texture coordinates.
+        if mat.is_none() && !mesh.colors.is_empty() {
+            mesh.texcoords.clear();
+        }
+
+        let grey: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let mesh = Arc::new(mesh);
+        let mut triangles = HittableList::default();
+        for &vertices in &mesh.triangles {
+            let mat = match &mat {
+                Some(mat) => mat.clone(),
+                None if mesh.colors.is_empty() => grey.clone(),
+                None => Arc::new(Lambertian::from_texture(Arc::new(VertexColors::new(
+                    vertices.map(|i| mesh.colors[i]),
+                )))),
+            };
+            // Triangles without area can never be hit.
+            if let Some(triangle) = Triangle::new(mesh.clone(), vertices, mat) {
+                triangles.add(triangle);
+            }
+        }
+        Ok(Self {
+            triangles: BvhNode::new(triangles),
+        })
+    }
+}
+
+impl Hittable for Mesh {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        self.triangles.hit(ray, ray_t, record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.triangles.bounding_box()
+    }
+
+    #[cfg(feature = "simd")]
+    fn hit_packet(
+        &self,
+        packet: &RayPacket,
+        mask: u8,
+        ray_t: &mut [Interval; LANES],
+        records: &mut [HitRecord; LANES],
+    ) -> u8 {
+        self.triangles.hit_packet(packet, mask, ray_t, records)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.triangles.validate().context("mesh material")
+    }
+}
+
+// One triangle of a mesh. Its (u, v) are the texture coordinates of the mesh, or without them the
+// barycentric coordinates of the hit point: the weights of the second and third vertices.
+pub struct Triangle {
+    mesh: Arc<TriangleMesh>,
+    vertices: [usize; 3],
+    normal: Vec3,
+    dpdu: Vec3,
+    dpdv: Vec3,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn new(
+        mesh: Arc<TriangleMesh>,
+        vertices: [usize; 3],
+        mat: Arc<dyn Material>,
+    ) -> Option<Self> {
+        let [a, b, c] = vertices.map(|i| mesh.positions[i]);
+        let normal = cross(b - a, c - a);
+        if normal.length_squared() == 0. {
+            return None;
+        }
+
+        // The edges are the derivatives along the barycentric coordinates. Texture coordinates
+        // give other ones, unless they collapse the triangle.
+        let (mut dpdu, mut dpdv) = (b - a, c - a);
+        if !mesh.texcoords.is_empty() {
+            let [ta, tb, tc] = vertices.map(|i| mesh.texcoords[i]);
+            let (du1, dv1) = (tb.0 - ta.0, tb.1 - ta.1);
+            let (du2, dv2) = (tc.0 - ta.0, tc.1 - ta.1);
+            let det = du1 * dv2 - dv1 * du2;
+            if det != 0. {
+                (dpdu, dpdv) = (
+                    (dv2 * (b - a) - dv1 * (c - a)) / det,
+                    (du1 * (c - a) - du2 * (b - a)) / det,
+                );
+            }
+        }
+
+        let bbox = Aabb::surrounding(&Aabb::from_points(a, b), &Aabb::from_points(a, c));
+        Some(Self {
+            mesh,
+            vertices,
+            normal: unit_vector(normal),
+            dpdu,
+            dpdv,
+            mat,
+            bbox,
+        })
+    }
+}
+
+impl Hittable for Triangle {
+    // Möller-Trumbore: solves for t and the barycentric coordinates at once.
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        let [a, b, c] = self.vertices.map(|i| self.mesh.positions[i]);
+        let (edge1, edge2) = (b - a, c - a);
+        let direction = *ray.direction();
+
+        let pvec = cross(direction, edge2);
+        let det = dot(edge1, pvec);
+        if det == 0. {
+            return false;
+        }
+        let inv_det = 1. / det;
+
+        let tvec = *ray.origin() - a;
+        let u = dot(tvec, pvec) * inv_det;
+        if !(0. ..=1.).contains(&u) {
+            return false;
+        }
+        let qvec = cross(tvec, edge1);
+        let v = dot(direction, qvec) * inv_det;
+        if v < 0. || u + v > 1. {
+            return false;
+        }
+        let t = dot(edge2, qvec) * inv_det;
+        if !ray_t.contains(t) {
+            return false;
+        }
+
+        record.t = t;
+        record.p = ray.at(t);
+        (record.u, record.v) = if self.mesh.texcoords.is_empty() {
+            (u, v)
+        } else {
+            let [ta, tb, tc] = self.vertices.map(|i| self.mesh.texcoords[i]);
+            (
+                (1. - u - v) * ta.0 + u * tb.0 + v * tc.0,
+                (1. - u - v) * ta.1 + u * tb.1 + v * tc.1,
+            )
+        };
+        (record.dpdu, record.dpdv) = (self.dpdu, self.dpdv);
+        record.mat = self.mat.clone();
+        record.set_face_normal(ray, &self.normal);
+
+        // The interpolated normal shades the surface, turned to the side the facet faces.
+        if !self.mesh.normals.is_empty() {
+            let [na, nb, nc] = self.vertices.map(|i| self.mesh.normals[i]);
+            let shading = (1. - u - v) * na + u * nb + v * nc;
+            if !shading.near_zero() {
+                let shading = unit_vector(shading);
+                let shading = if dot(shading, self.normal) < 0. {
+                    -shading
+                } else {
+                    shading
+                };
+                record.normal = if record.front_face { shading } else { -shading };
+            }
+        }
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate()
+    }
+}
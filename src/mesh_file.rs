@@ -0,0 +1,44 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use crate::{read_ply, read_stl, Context, FileFormat, RenderError, TriangleMesh};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeshFormat {
+    // Stanford polygon files, as scanners write them.
+    Ply,
+    // Triangle soups, as CAD tools export them.
+    Stl,
+}
+
+impl FileFormat for MeshFormat {
+    const EXTENSIONS: &'static str = "'.ply' or '.stl'";
+
+    fn from_extension(extension: &str) -> Option<MeshFormat> {
+        match extension {
+            "ply" => Some(MeshFormat::Ply),
+            "stl" => Some(MeshFormat::Stl),
+            _ => None,
+        }
+    }
+}
+
+impl MeshFormat {
+    pub fn read(self, input: &mut impl BufRead) -> io::Result<TriangleMesh> {
+        match self {
+            MeshFormat::Ply => read_ply(input),
+            MeshFormat::Stl => read_stl(input),
+        }
+    }
+}
+
+// Picks the format from the extension of `path`.
+pub fn read_mesh(path: &Path) -> Result<TriangleMesh, RenderError> {
+    let format = MeshFormat::of(path)?;
+    File::open(path)
+        .and_then(|file| format.read(&mut BufReader::new(file)))
+        .with_context(|| format!("failed to read {}", path.display()))
+}
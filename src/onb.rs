@@ -0,0 +1,42 @@
+use crate::{cross, dot, unit_vector, Float, Vec3};
+
+// An orthonormal basis around `w`, used to place primitives that are defined around a local z axis
+// in any orientation.
+#[derive(Clone, Copy, Debug)]
+pub struct Onb {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Onb {
+    pub fn new(w: Vec3) -> Self {
+        let w = unit_vector(w);
+        let a = if w.x().abs() > 0.9 {
+            Vec3::new(0., 1., 0.)
+        } else {
+            Vec3::new(1., 0., 0.)
+        };
+        let v = unit_vector(cross(w, a));
+        let u = cross(v, w);
+        Self { u, v, w }
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.w
+    }
+
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(dot(v, self.u), dot(v, self.v), dot(v, self.w))
+    }
+
+    pub fn to_world(&self, v: Vec3) -> Vec3 {
+        v.x() * self.u + v.y() * self.v + v.z() * self.w
+    }
+
+    // Half the extent along each world axis of a circle of `radius` perpendicular to `w`.
+    pub fn circle_extent(&self, radius: Float) -> Vec3 {
+        let extent = |a: Float| radius * (1. - a * a).max(0.).sqrt();
+        Vec3::new(extent(self.w.x()), extent(self.w.y()), extent(self.w.z()))
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{dot, random_f64, unit_vector, Float, Point3, Vec3};
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise on a lattice of random unit vectors, smoothed with Hermite interpolation.
+pub struct Perlin {
+    gradients: [Vec3; POINT_COUNT],
+    perm_x: [usize; POINT_COUNT],
+    perm_y: [usize; POINT_COUNT],
+    perm_z: [usize; POINT_COUNT],
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        Self {
+            gradients: std::array::from_fn(|_| unit_vector(Vec3::random_with_range(-1., 1.))),
+            perm_x: generate_perm(),
+            perm_y: generate_perm(),
+            perm_z: generate_perm(),
+        }
+    }
+
+    // In [-1, 1].
+    pub fn noise(&self, p: &Point3) -> Float {
+        let floor = |x: Float| x.floor();
+        let (u, v, w) = (
+            p.x() - floor(p.x()),
+            p.y() - floor(p.y()),
+            p.z() - floor(p.z()),
+        );
+        let (i, j, k) = (
+            floor(p.x()) as i64,
+            floor(p.y()) as i64,
+            floor(p.z()) as i64,
+        );
+
+        let mut corners = [[[Vec3::default(); 2]; 2]; 2];
+        for (di, plane) in corners.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    let index = |n: i64, d: usize| ((n + d as i64) & 255) as usize;
+                    *corner = self.gradients[self.perm_x[index(i, di)]
+                        ^ self.perm_y[index(j, dj)]
+                        ^ self.perm_z[index(k, dk)]];
+                }
+            }
+        }
+
+        interpolate(&corners, u, v, w)
+    }
+
+    // Sum of `depth` octaves of the absolute noise, each at twice the frequency and half the
+    // weight of the previous one.
+    pub fn turbulence(&self, p: &Point3, depth: u32) -> Float {
+        let mut accumulated = 0.;
+        let mut p = *p;
+        let mut weight = 1.;
+
+        for _ in 0..depth {
+            accumulated += weight * self.noise(&p);
+            weight *= 0.5;
+            p *= 2.;
+        }
+
+        accumulated.abs()
+    }
+}
+
+fn generate_perm() -> [usize; POINT_COUNT] {
+    let mut perm: [usize; POINT_COUNT] = std::array::from_fn(|i| i);
+    for i in (1..POINT_COUNT).rev() {
+        let target = (random_f64() * (i + 1) as Float) as usize;
+        perm.swap(i, target.min(i));
+    }
+    perm
+}
+
+fn interpolate(corners: &[[[Vec3; 2]; 2]; 2], u: Float, v: Float, w: Float) -> Float {
+    let smooth = |t: Float| t * t * (3. - 2. * t);
+    let (uu, vv, ww) = (smooth(u), smooth(v), smooth(w));
+
+    let mut accumulated = 0.;
+    for (i, plane) in corners.iter().enumerate() {
+        for (j, row) in plane.iter().enumerate() {
+            for (k, corner) in row.iter().enumerate() {
+                let (i, j, k) = (i as Float, j as Float, k as Float);
+                let weight = Vec3::new(u - i, v - j, w - k);
+                accumulated += (i * uu + (1. - i) * (1. - uu))
+                    * (j * vv + (1. - j) * (1. - vv))
+                    * (k * ww + (1. - k) * (1. - ww))
+                    * dot(*corner, weight);
+            }
+        }
+    }
+    accumulated
+}
@@ -0,0 +1,266 @@
+use std::io::{self, BufRead};
+
+use crate::{gamma_to_linear, invalid_data, Color, Float, Point3, TriangleMesh, Vec3};
+
+// Reads a Stanford PLY mesh, ASCII or binary little-endian. Faces with more than three vertices
+// are split into fans; elements other than vertices and faces are skipped.
+pub fn read_ply(input: &mut impl BufRead) -> io::Result<TriangleMesh> {
+    let (ascii, elements) = read_header(input)?;
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    let mut body = Body {
+        bytes: &bytes,
+        ascii,
+    };
+
+    let mut mesh = TriangleMesh::default();
+    let mut indices = Vec::new();
+    for element in &elements {
+        let has = |role: fn(&Role) -> bool| element.properties.iter().any(|p| role(&p.role));
+        let has_normals = has(|role| matches!(role, Role::Normal(_)));
+        let has_colors = has(|role| matches!(role, Role::Color(_)));
+
+        for _ in 0..element.count {
+            let (mut position, mut normal, mut color) =
+                (Point3::default(), Vec3::default(), Color::default());
+            for property in &element.properties {
+                let Some(count) = property.count else {
+                    let value = body.read(property.kind)? as Float;
+                    match property.role {
+                        Role::Position(axis) => position[axis] = value,
+                        Role::Normal(axis) => normal[axis] = value,
+                        Role::Color(channel) => {
+                            color[channel] = gamma_to_linear(value / property.kind.max() as Float)
+                        }
+                        _ => {}
+                    }
+                    continue;
+                };
+
+                let len = body.read(count)?;
+                if !(len >= 0. && len.fract() == 0.) {
+                    return Err(invalid_data("bad list length"));
+                }
+                indices.clear();
+                for _ in 0..len as usize {
+                    let value = body.read(property.kind)?;
+                    if property.role != Role::Indices {
+                        continue;
+                    }
+                    if !(value >= 0. && value.fract() == 0.) {
+                        return Err(invalid_data("bad vertex index"));
+                    }
+                    indices.push(value as usize);
+                }
+                if property.role == Role::Indices {
+                    for i in 2..indices.len() {
+                        mesh.triangles
+                            .push([indices[0], indices[i - 1], indices[i]]);
+                    }
+                }
+            }
+
+            if element.name == "vertex" {
+                mesh.positions.push(position);
+                if has_normals {
+                    mesh.normals.push(normal);
+                }
+                if has_colors {
+                    mesh.colors.push(color);
+                }
+            }
+        }
+    }
+    Ok(mesh)
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> io::Result<Scalar> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(invalid_data(&format!("unknown property type '{name}'"))),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    // Integer colors span the whole range of their type, floating point ones go up to 1.
+    fn max(self) -> f64 {
+        match self {
+            Scalar::I8 => i8::MAX.into(),
+            Scalar::U8 => u8::MAX.into(),
+            Scalar::I16 => i16::MAX.into(),
+            Scalar::U16 => u16::MAX.into(),
+            Scalar::I32 => i32::MAX.into(),
+            Scalar::U32 => u32::MAX.into(),
+            Scalar::F32 | Scalar::F64 => 1.,
+        }
+    }
+}
+
+// What a vertex or face property means to the mesh.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Position(usize),
+    Normal(usize),
+    Color(usize),
+    Indices,
+    Ignored,
+}
+
+impl Role {
+    fn of(element: &str, property: &str) -> Role {
+        match (element, property) {
+            ("vertex", "x") => Role::Position(0),
+            ("vertex", "y") => Role::Position(1),
+            ("vertex", "z") => Role::Position(2),
+            ("vertex", "nx") => Role::Normal(0),
+            ("vertex", "ny") => Role::Normal(1),
+            ("vertex", "nz") => Role::Normal(2),
+            ("vertex", "red" | "r" | "diffuse_red") => Role::Color(0),
+            ("vertex", "green" | "g" | "diffuse_green") => Role::Color(1),
+            ("vertex", "blue" | "b" | "diffuse_blue") => Role::Color(2),
+            ("face", "vertex_indices" | "vertex_index") => Role::Indices,
+            _ => Role::Ignored,
+        }
+    }
+}
+
+struct Property {
+    role: Role,
+    kind: Scalar,
+    // The type of the length of list properties.
+    count: Option<Scalar>,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+// Returns whether the body is ASCII, and the elements it holds in order.
+fn read_header(input: &mut impl BufRead) -> io::Result<(bool, Vec<Element>)> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    if line.trim_end() != "ply" {
+        return Err(invalid_data("missing PLY signature"));
+    }
+
+    let mut ascii = None;
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Err(invalid_data("unexpected end of header"));
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["end_header"] => break,
+            ["format", "ascii", _] => ascii = Some(true),
+            ["format", "binary_little_endian", _] => ascii = Some(false),
+            ["format", format, _] => {
+                return Err(invalid_data(&format!("unsupported PLY format '{format}'")))
+            }
+            ["comment" | "obj_info", ..] | [] => {}
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| invalid_data("bad element count"))?,
+                properties: Vec::new(),
+            }),
+            ["property", rest @ ..] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid_data("property outside of an element"))?;
+                let (kind, count, name) = match rest {
+                    ["list", count, kind, name] => (kind, Some(Scalar::parse(count)?), name),
+                    [kind, name] => (kind, None, name),
+                    _ => return Err(invalid_data("bad property line")),
+                };
+                element.properties.push(Property {
+                    role: Role::of(&element.name, name),
+                    kind: Scalar::parse(kind)?,
+                    count,
+                });
+            }
+            _ => return Err(invalid_data(&format!("bad header line '{}'", line.trim()))),
+        }
+    }
+
+    let ascii = ascii.ok_or_else(|| invalid_data("missing PLY format"))?;
+    Ok((ascii, elements))
+}
+
+// The data after the header, read one value at a time.
+struct Body<'a> {
+    bytes: &'a [u8],
+    ascii: bool,
+}
+
+impl Body<'_> {
+    // Every PLY type fits in an `f64` without loss, vertex indices included.
+    fn read(&mut self, kind: Scalar) -> io::Result<f64> {
+        if self.ascii {
+            let start = self
+                .bytes
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+                .ok_or_else(|| invalid_data("truncated PLY data"))?;
+            let bytes = &self.bytes[start..];
+            let end = bytes
+                .iter()
+                .position(|b| b.is_ascii_whitespace())
+                .unwrap_or(bytes.len());
+            let (token, rest) = bytes.split_at(end);
+            self.bytes = rest;
+            return std::str::from_utf8(token)
+                .ok()
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(|| invalid_data("bad PLY value"));
+        }
+
+        if self.bytes.len() < kind.size() {
+            return Err(invalid_data("truncated PLY data"));
+        }
+        let (bytes, rest) = self.bytes.split_at(kind.size());
+        self.bytes = rest;
+        Ok(match kind {
+            Scalar::I8 => (bytes[0] as i8).into(),
+            Scalar::U8 => bytes[0].into(),
+            Scalar::I16 => i16::from_le_bytes(bytes.try_into().unwrap()).into(),
+            Scalar::U16 => u16::from_le_bytes(bytes.try_into().unwrap()).into(),
+            Scalar::I32 => i32::from_le_bytes(bytes.try_into().unwrap()).into(),
+            Scalar::U32 => u32::from_le_bytes(bytes.try_into().unwrap()).into(),
+            Scalar::F32 => f32::from_le_bytes(bytes.try_into().unwrap()).into(),
+            Scalar::F64 => f64::from_le_bytes(bytes.try_into().unwrap()),
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Projection {
+    #[default]
+    Perspective,
+    Orthographic,
+    // r = f·θ, the usual choice for measurement and all-sky images.
+    FisheyeEquidistant,
+    // r = 2f·sin(θ/2), preserves solid angle.
+    FisheyeEquisolid,
+    // Full 360°x180° latitude/longitude panorama.
+    Equirectangular,
+    // Left and right perspective views side by side, each `image_width` pixels wide.
+    Stereo,
+}
+
+impl Projection {
+    pub fn views(&self) -> u32 {
+        match self {
+            Projection::Stereo => 2,
+            _ => 1,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use crate::{
+    cross, dot, ensure_finite, stats, unit_vector, Aabb, Context, Float, HitRecord, Hittable,
+    HittableList, Interval, Material, Point3, Ray, RenderError, Vec3,
+};
+
+// The parallelogram spanned by `u` and `v` from the corner `q`.
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    // Maps a point on the plane to its (alpha, beta) coordinates along `u` and `v`.
+    w: Vec3,
+    normal: Vec3,
+    d: Float,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, mat: Arc<dyn Material>) -> Result<Self, RenderError> {
+        ensure_finite(q, "quad corner")?;
+        ensure_finite(u, "quad edge")?;
+        ensure_finite(v, "quad edge")?;
+        let n = cross(u, v);
+        if n.near_zero() {
+            return Err(RenderError::DegenerateQuad(u, v));
+        }
+
+        let normal = unit_vector(n);
+        let bbox = Aabb::surrounding(
+            &Aabb::from_points(q, q + u + v),
+            &Aabb::from_points(q + u, q + v),
+        );
+        Ok(Self {
+            q,
+            u,
+            v,
+            w: n / dot(n, n),
+            normal,
+            d: dot(normal, q),
+            mat,
+            bbox,
+        })
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        let denom = dot(self.normal, *ray.direction());
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (self.d - dot(self.normal, *ray.origin())) / denom;
+        if !ray_t.contains(t) {
+            return false;
+        }
+
+        let intersection = ray.at(t);
+        let planar = intersection - self.q;
+        let alpha = dot(self.w, cross(planar, self.v));
+        let beta = dot(self.w, cross(self.u, planar));
+        let unit = Interval::new(0., 1.);
+        if !unit.contains(alpha) || !unit.contains(beta) {
+            return false;
+        }
+
+        record.t = t;
+        record.p = intersection;
+        record.u = alpha;
+        record.v = beta;
+        (record.dpdu, record.dpdv) = (self.u, self.v);
+        record.mat = self.mat.clone();
+        record.set_face_normal(ray, &self.normal);
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("quad material")
+    }
+}
+
+// The six sides of the axis-aligned box with opposite corners `a` and `b`.
+pub fn make_box(a: Point3, b: Point3, mat: Arc<dyn Material>) -> Result<HittableList, RenderError> {
+    let mut sides = HittableList::default();
+    let min = Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z()));
+    let max = Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z()));
+
+    let dx = Vec3::new(max.x() - min.x(), 0., 0.);
+    let dy = Vec3::new(0., max.y() - min.y(), 0.);
+    let dz = Vec3::new(0., 0., max.z() - min.z());
+
+    let faces = [
+        (Point3::new(min.x(), min.y(), max.z()), dx, dy),
+        (Point3::new(max.x(), min.y(), max.z()), -dz, dy),
+        (Point3::new(max.x(), min.y(), min.z()), -dx, dy),
+        (Point3::new(min.x(), min.y(), min.z()), dz, dy),
+        (Point3::new(min.x(), max.y(), max.z()), dx, -dz),
+        (Point3::new(min.x(), min.y(), min.z()), dx, dz),
+    ];
+    for (q, u, v) in faces {
+        sides.add(Quad::new(q, u, v, mat.clone())?);
+    }
+
+    Ok(sides)
+}
@@ -1,15 +1,29 @@
-use crate::{Point3, Vec3};
+use crate::{Float, Point3, Vec3};
 
-#[derive(Default)]
+#[derive(Clone, Copy, Default)]
 pub struct Ray {
     origin: Point3,
     dir: Vec3,
+    wavelength: Option<Float>,
 }
 
 impl Ray {
     #[must_use]
     pub fn new(origin: Point3, dir: Vec3) -> Self {
-        Self { origin, dir }
+        Self {
+            origin,
+            dir,
+            wavelength: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_wavelength(origin: Point3, dir: Vec3, wavelength: Option<Float>) -> Self {
+        Self {
+            origin,
+            dir,
+            wavelength,
+        }
     }
 
     #[must_use]
@@ -23,7 +37,12 @@ impl Ray {
     }
 
     #[must_use]
-    pub fn at(&self, t: f64) -> Point3 {
+    pub fn wavelength(&self) -> Option<Float> {
+        self.wavelength
+    }
+
+    #[must_use]
+    pub fn at(&self, t: Float) -> Point3 {
         self.origin + t * self.dir
     }
 }
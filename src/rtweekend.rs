@@ -1,21 +1,73 @@
-use rand::Rng;
+use std::cell::RefCell;
 
-pub const INFINITY: f64 = f64::INFINITY;
-pub const PI: f64 = std::f64::consts::PI;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::Point3;
+
+#[cfg(not(feature = "f32"))]
+pub type Float = f64;
+#[cfg(not(feature = "f32"))]
+pub use std::f64::consts;
+
+#[cfg(feature = "f32")]
+pub type Float = f32;
+#[cfg(feature = "f32")]
+pub use std::f32::consts;
+
+pub const INFINITY: Float = Float::INFINITY;
+pub const PI: Float = consts::PI;
+
+// Smallest hit distance accepted for rays leaving a surface. The fixed 0.001 works for f64, but
+// with f32 the rounding error of a hit point grows past it once coordinates reach the hundreds
+// (e.g. on the ground sphere), so it also scales with the magnitude of the origin.
+pub const RAY_EPSILON: Float = 0.001;
+
+#[inline]
+pub fn self_intersection_epsilon(origin: &Point3) -> Float {
+    let magnitude = origin.x().abs().max(origin.y().abs()).max(origin.z().abs());
+    RAY_EPSILON.max(64. * Float::EPSILON * magnitude)
+}
 
 #[inline]
-pub fn degrees_to_radians(degrees: f64) -> f64 {
+pub fn degrees_to_radians(degrees: Float) -> Float {
     degrees * PI / 180.0
 }
 
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+// Reseeds the generator of the calling thread, making everything it draws afterwards
+// reproducible.
+pub fn seed_rng(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
 #[inline]
-pub fn random_f64() -> f64 {
-    let mut rng = rand::thread_rng();
-    rng.gen::<f64>()
+pub fn random_f64() -> Float {
+    RNG.with(|rng| rng.borrow_mut().gen::<Float>())
 }
 
 #[inline]
-pub fn random_f64_range(min: f64, max: f64) -> f64 {
-    let mut rng = rand::thread_rng();
-    rng.gen_range(min..max)
+pub fn random_f64_range(min: Float, max: Float) -> Float {
+    RNG.with(|rng| rng.borrow_mut().gen_range(min..max))
+}
+
+// The real roots of a t² + b t + c in increasing order, or `None` if there are none.
+pub fn solve_quadratic(a: Float, b: Float, c: Float) -> Option<(Float, Float)> {
+    if a == 0. {
+        if b == 0. {
+            return None;
+        }
+        return Some((-c / b, -c / b));
+    }
+
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return None;
+    }
+    // Avoids the cancellation of the textbook formula when b² is much larger than 4ac.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let (t0, t1) = if q == 0. { (0., 0.) } else { (q / a, c / q) };
+    Some((t0.min(t1), t0.max(t1)))
 }
@@ -0,0 +1,700 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    read_mesh, Blend, BvhNode, Camera, CameraBuilder, Color, Cone, Context, Cuboid, Cylinder,
+    DetailedMaterial, Dielectric, Difference, Disk, EnvironmentSpec, FileFormat, Float, GltfAsset,
+    Hittable, HittableList, ImageTexture, Intersection, Lambertian, Material, Mesh, Metal,
+    NoiseTexture, Point3, Projection, RefractiveIndex, RenderError, Sdf, SdfCapsule, SdfHittable,
+    SdfRoundedBox, SdfTorus, SmoothUnion, Sphere, SurfaceDetail, Texture, Torus, Union, Vec3,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneFormat {
+    Json,
+    Toml,
+    Gltf,
+}
+
+impl FileFormat for SceneFormat {
+    const EXTENSIONS: &'static str = "'.json', '.toml', '.gltf' or '.glb'";
+
+    fn from_extension(extension: &str) -> Option<SceneFormat> {
+        match extension {
+            "json" => Some(SceneFormat::Json),
+            "toml" => Some(SceneFormat::Toml),
+            "gltf" | "glb" => Some(SceneFormat::Gltf),
+            _ => None,
+        }
+    }
+}
+
+// A scene described as data, stored as JSON or TOML, e.g. the body of a job sent to the render
+// server:
+//
+// {
+//   "camera": { "image_width": 200, "look_from": [13, 2, 3], "look_at": [0, 0, 0] },
+//   "environment": { "map": "constant:0.7,0.8,1" },
+//   "objects": [
+//     { "type": "sphere", "center": [0, 1, 0], "radius": 1,
+//       "material": { "type": "dielectric", "refractive_index": "bk7" } }
+//   ]
+// }
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SceneFile {
+    pub camera: CameraSettings,
+    pub environment: EnvironmentDescription,
+    pub objects: Vec<ObjectDescription>,
+    // Relative paths in the scene are relative to this directory, the one of the file it was
+    // loaded from, and to the working directory otherwise.
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+impl SceneFile {
+    pub fn from_json(json: &str) -> Result<SceneFile, RenderError> {
+        serde_json::from_str(json).map_err(|e| RenderError::InvalidScene(e.to_string()))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<SceneFile, RenderError> {
+        toml::from_str(toml).map_err(|e| RenderError::InvalidScene(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> Result<String, RenderError> {
+        serde_json::to_string_pretty(self).map_err(|e| RenderError::InvalidScene(e.to_string()))
+    }
+
+    pub fn to_toml(&self) -> Result<String, RenderError> {
+        toml::to_string(self).map_err(|e| RenderError::InvalidScene(e.to_string()))
+    }
+
+    // Both pick the format from the extension of `path`. A glTF file loads as a scene holding
+    // just that file, seen from its first camera; scenes can't be saved as glTF.
+    pub fn load(path: &Path) -> Result<SceneFile, RenderError> {
+        let read = || {
+            fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+        };
+        let mut scene = match SceneFormat::of(path)? {
+            SceneFormat::Json => SceneFile::from_json(&read()?),
+            SceneFormat::Toml => SceneFile::from_toml(&read()?),
+            SceneFormat::Gltf => SceneFile::from_gltf(path),
+        }
+        .with_context(|| format!("failed to load {}", path.display()))?;
+        scene.dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(scene)
+    }
+
+    // Relative paths are written as they are when the file stays in the directory of the scene,
+    // and made absolute otherwise so that they still name the same files.
+    pub fn save(&self, path: &Path) -> Result<(), RenderError> {
+        let format = SceneFormat::of(path)?;
+        let cwd = env::current_dir().context("failed to find the working directory")?;
+        let target = path.parent().unwrap_or(Path::new(""));
+        let mut scene = self.clone();
+        if cwd.join(target) != cwd.join(&self.dir) {
+            scene.make_paths_absolute()?;
+        }
+
+        let text = match format {
+            SceneFormat::Json => scene.to_json()?,
+            SceneFormat::Toml => scene.to_toml()?,
+            SceneFormat::Gltf => {
+                return Err(RenderError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                    expected: "'.json' or '.toml'",
+                })
+            }
+        };
+        fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    // Every file the scene reads: meshes, glTF files, detail images and the environment image.
+    pub fn paths_mut(&mut self) -> Vec<&mut PathBuf> {
+        let mut paths: Vec<_> = self
+            .objects
+            .iter_mut()
+            .flat_map(|o| o.paths_mut())
+            .collect();
+        if let EnvironmentSpec::Image(path) = &mut self.environment.map {
+            paths.push(path);
+        }
+        paths
+    }
+
+    // Joins every relative path to `dir` and the working directory, which makes the scene the
+    // same wherever it is loaded from.
+    pub fn make_paths_absolute(&mut self) -> Result<(), RenderError> {
+        let dir = env::current_dir()
+            .context("failed to find the working directory")?
+            .join(&self.dir);
+        for path in self.paths_mut() {
+            *path = dir.join(&*path);
+        }
+        self.dir = dir;
+        Ok(())
+    }
+
+    // The file is named relative to its own directory, which `load` makes the one of the scene.
+    fn from_gltf(path: &Path) -> Result<SceneFile, RenderError> {
+        let camera = GltfAsset::open(path)?.camera()?;
+        let name = path.file_name().map(PathBuf::from).unwrap_or_default();
+        Ok(SceneFile {
+            camera: camera.unwrap_or_default(),
+            objects: vec![ObjectDescription::Gltf { path: name }],
+            ..SceneFile::default()
+        })
+    }
+
+    // The camera of the scene on top of `builder`, with `overrides` taking precedence over both.
+    pub fn camera(
+        &self,
+        builder: CameraBuilder,
+        overrides: &CameraSettings,
+    ) -> Result<Camera, RenderError> {
+        let map = match &self.environment.map {
+            EnvironmentSpec::Image(path) => EnvironmentSpec::Image(self.dir.join(path)),
+            map => map.clone(),
+        };
+        let environment = map
+            .load(self.environment.rotation, self.environment.intensity)
+            .context("failed to set up the environment")?;
+
+        overrides
+            .apply(self.camera.apply(builder))
+            .environment(environment)
+            .build()
+            .context("invalid camera configuration")
+    }
+
+    pub fn build(&self) -> Result<HittableList, RenderError> {
+        let mut world = HittableList::default();
+        for (i, object) in self.objects.iter().enumerate() {
+            let object = object
+                .build(&self.dir)
+                .with_context(|| format!("object #{i}"))?;
+            world.add_shared(object);
+        }
+        Ok(world)
+    }
+}
+
+// Every setting is optional; the ones left out keep the value of the builder they are applied to.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<Float>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samples_per_pixel: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rr_min_depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vfov: Option<Float>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub look_from: Option<Point3>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub look_at: Option<Point3>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub v_up: Option<Vec3>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defocus_angle: Option<Float>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus_dist: Option<Float>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projection: Option<Projection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eye_separation: Option<Float>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spectral: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl CameraSettings {
+    pub fn apply(&self, mut builder: CameraBuilder) -> CameraBuilder {
+        macro_rules! apply {
+            ($($field:ident => $setter:ident),* $(,)?) => {
+                $(if let Some(value) = self.$field {
+                    builder = builder.$setter(value);
+                })*
+            };
+        }
+
+        apply!(
+            aspect_ratio => aspect_ratio,
+            image_width => image_width,
+            samples_per_pixel => samples_per_pixel,
+            max_depth => max_depth,
+            rr_min_depth => russian_roulette_depth,
+            vfov => vfov,
+            look_from => lookfrom,
+            look_at => lookat,
+            v_up => vup,
+            defocus_angle => defocus_angle,
+            focus_dist => focus_dist,
+            projection => projection,
+            eye_separation => eye_separation,
+            spectral => spectral,
+        );
+        if self.seed.is_some() {
+            builder = builder.seed(self.seed);
+        }
+        builder
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvironmentDescription {
+    pub map: EnvironmentSpec,
+    pub rotation: Float,
+    pub intensity: Float,
+}
+
+impl Default for EnvironmentDescription {
+    fn default() -> Self {
+        Self {
+            map: EnvironmentSpec::Gradient,
+            rotation: 0.,
+            intensity: 1.,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ObjectDescription {
+    Sphere {
+        center: Point3,
+        radius: Float,
+        material: MaterialDescription,
+    },
+    // An axis-aligned box between two opposite corners.
+    #[serde(rename = "box")]
+    Cuboid {
+        min: Point3,
+        max: Point3,
+        material: MaterialDescription,
+    },
+    // A cylinder closed by caps at both ends.
+    Cylinder {
+        base: Point3,
+        top: Point3,
+        radius: Float,
+        material: MaterialDescription,
+    },
+    Cone {
+        base: Point3,
+        apex: Point3,
+        radius: Float,
+        material: MaterialDescription,
+    },
+    // A flat disk, or a ring when `inner_radius` is set.
+    Disk {
+        center: Point3,
+        normal: Vec3,
+        radius: Float,
+        #[serde(default)]
+        inner_radius: Float,
+        material: MaterialDescription,
+    },
+    Torus {
+        center: Point3,
+        axis: Vec3,
+        major_radius: Float,
+        minor_radius: Float,
+        material: MaterialDescription,
+    },
+    // A triangle mesh read from a PLY or STL file. Without a material it is diffuse and takes the
+    // colors of its vertices.
+    Mesh {
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        material: Option<MaterialDescription>,
+    },
+    // The meshes of a glTF 2.0 file with their transforms and materials.
+    Gltf {
+        path: PathBuf,
+    },
+    // A surface rendered by sphere tracing its signed distance function.
+    Sdf {
+        shape: SdfDescription,
+        material: MaterialDescription,
+    },
+    Union {
+        left: Box<ObjectDescription>,
+        right: Box<ObjectDescription>,
+    },
+    Intersection {
+        left: Box<ObjectDescription>,
+        right: Box<ObjectDescription>,
+    },
+    Difference {
+        left: Box<ObjectDescription>,
+        right: Box<ObjectDescription>,
+    },
+}
+
+impl ObjectDescription {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectDescription::Sphere { .. } => "sphere",
+            ObjectDescription::Cuboid { .. } => "box",
+            ObjectDescription::Cylinder { .. } => "cylinder",
+            ObjectDescription::Cone { .. } => "cone",
+            ObjectDescription::Disk { .. } => "disk",
+            ObjectDescription::Torus { .. } => "torus",
+            ObjectDescription::Mesh { .. } => "mesh",
+            ObjectDescription::Gltf { .. } => "gltf",
+            ObjectDescription::Sdf { .. } => "sdf",
+            ObjectDescription::Union { .. } => "union",
+            ObjectDescription::Intersection { .. } => "intersection",
+            ObjectDescription::Difference { .. } => "difference",
+        }
+    }
+
+    // Every material of the object, including those of the operands of CSG operations.
+    pub fn materials(&self) -> Vec<&MaterialDescription> {
+        match self {
+            ObjectDescription::Sphere { material, .. }
+            | ObjectDescription::Cuboid { material, .. }
+            | ObjectDescription::Cylinder { material, .. }
+            | ObjectDescription::Cone { material, .. }
+            | ObjectDescription::Disk { material, .. }
+            | ObjectDescription::Torus { material, .. }
+            | ObjectDescription::Sdf { material, .. } => vec![material],
+            ObjectDescription::Mesh { material, .. } => material.iter().collect(),
+            ObjectDescription::Gltf { .. } => Vec::new(),
+            ObjectDescription::Union { left, right }
+            | ObjectDescription::Intersection { left, right }
+            | ObjectDescription::Difference { left, right } => {
+                let mut materials = left.materials();
+                materials.extend(right.materials());
+                materials
+            }
+        }
+    }
+
+    pub fn paths_mut(&mut self) -> Vec<&mut PathBuf> {
+        match self {
+            ObjectDescription::Sphere { material, .. }
+            | ObjectDescription::Cuboid { material, .. }
+            | ObjectDescription::Cylinder { material, .. }
+            | ObjectDescription::Cone { material, .. }
+            | ObjectDescription::Disk { material, .. }
+            | ObjectDescription::Torus { material, .. }
+            | ObjectDescription::Sdf { material, .. } => material.path_mut().into_iter().collect(),
+            ObjectDescription::Mesh { path, material } => {
+                let mut paths = vec![path];
+                paths.extend(material.as_mut().and_then(|m| m.path_mut()));
+                paths
+            }
+            ObjectDescription::Gltf { path } => vec![path],
+            ObjectDescription::Union { left, right }
+            | ObjectDescription::Intersection { left, right }
+            | ObjectDescription::Difference { left, right } => {
+                let mut paths = left.paths_mut();
+                paths.extend(right.paths_mut());
+                paths
+            }
+        }
+    }
+
+    // Files are looked up relative to `dir`.
+    pub fn build(&self, dir: &Path) -> Result<Arc<dyn Hittable>, RenderError> {
+        let operands = |left: &ObjectDescription, right: &ObjectDescription| {
+            Ok::<_, RenderError>((
+                left.build(dir).context("left operand")?,
+                right.build(dir).context("right operand")?,
+            ))
+        };
+
+        Ok(match self {
+            ObjectDescription::Sphere {
+                center,
+                radius,
+                material,
+            } => Arc::new(Sphere::new(center, *radius, material.build(dir)?)?),
+            ObjectDescription::Cuboid { min, max, material } => {
+                Arc::new(Cuboid::new(*min, *max, material.build(dir)?)?)
+            }
+            ObjectDescription::Cylinder {
+                base,
+                top,
+                radius,
+                material,
+            } => Arc::new(Cylinder::new(*base, *top, *radius, material.build(dir)?)?),
+            ObjectDescription::Cone {
+                base,
+                apex,
+                radius,
+                material,
+            } => Arc::new(Cone::new(*base, *apex, *radius, material.build(dir)?)?),
+            ObjectDescription::Disk {
+                center,
+                normal,
+                radius,
+                inner_radius,
+                material,
+            } => Arc::new(Disk::new(
+                *center,
+                *normal,
+                *radius,
+                *inner_radius,
+                material.build(dir)?,
+            )?),
+            ObjectDescription::Torus {
+                center,
+                axis,
+                major_radius,
+                minor_radius,
+                material,
+            } => Arc::new(Torus::new(
+                *center,
+                *axis,
+                *major_radius,
+                *minor_radius,
+                material.build(dir)?,
+            )?),
+            ObjectDescription::Mesh { path, material } => {
+                let material = material.as_ref().map(|m| m.build(dir)).transpose()?;
+                Arc::new(Mesh::new(read_mesh(&dir.join(path))?, material)?)
+            }
+            ObjectDescription::Gltf { path } => {
+                Arc::new(BvhNode::new(GltfAsset::open(&dir.join(path))?.build()?))
+            }
+            ObjectDescription::Sdf { shape, material } => {
+                Arc::new(SdfHittable::new(shape.build(), material.build(dir)?))
+            }
+            ObjectDescription::Union { left, right } => {
+                let (left, right) = operands(left, right)?;
+                Arc::new(Union::new(left, right)?)
+            }
+            ObjectDescription::Intersection { left, right } => {
+                let (left, right) = operands(left, right)?;
+                Arc::new(Intersection::new(left, right)?)
+            }
+            ObjectDescription::Difference { left, right } => {
+                let (left, right) = operands(left, right)?;
+                Arc::new(Difference::new(left, right)?)
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum SdfDescription {
+    Torus {
+        center: Point3,
+        major_radius: Float,
+        minor_radius: Float,
+    },
+    RoundedBox {
+        center: Point3,
+        half_size: Vec3,
+        radius: Float,
+    },
+    Capsule {
+        a: Point3,
+        b: Point3,
+        radius: Float,
+    },
+    SmoothUnion {
+        left: Box<SdfDescription>,
+        right: Box<SdfDescription>,
+        k: Float,
+    },
+    Blend {
+        left: Box<SdfDescription>,
+        right: Box<SdfDescription>,
+        t: Float,
+    },
+}
+
+impl SdfDescription {
+    pub fn build(&self) -> Arc<dyn Sdf> {
+        match self {
+            SdfDescription::Torus {
+                center,
+                major_radius,
+                minor_radius,
+            } => Arc::new(SdfTorus::new(*center, *major_radius, *minor_radius)),
+            SdfDescription::RoundedBox {
+                center,
+                half_size,
+                radius,
+            } => Arc::new(SdfRoundedBox::new(*center, *half_size, *radius)),
+            SdfDescription::Capsule { a, b, radius } => Arc::new(SdfCapsule::new(*a, *b, *radius)),
+            SdfDescription::SmoothUnion { left, right, k } => {
+                Arc::new(SmoothUnion::new(left.build(), right.build(), *k))
+            }
+            SdfDescription::Blend { left, right, t } => {
+                Arc::new(Blend::new(left.build(), right.build(), *t))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum MaterialDescription {
+    Lambertian {
+        albedo: Color,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<DetailDescription>,
+    },
+    Metal {
+        albedo: Color,
+        #[serde(default)]
+        fuzz: Float,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<DetailDescription>,
+    },
+    Dielectric {
+        refractive_index: IndexDescription,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<DetailDescription>,
+    },
+}
+
+impl MaterialDescription {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaterialDescription::Lambertian { .. } => "lambertian",
+            MaterialDescription::Metal { .. } => "metal",
+            MaterialDescription::Dielectric { .. } => "dielectric",
+        }
+    }
+
+    // The image of its detail, if it has one.
+    pub fn path_mut(&mut self) -> Option<&mut PathBuf> {
+        let (MaterialDescription::Lambertian { detail, .. }
+        | MaterialDescription::Metal { detail, .. }
+        | MaterialDescription::Dielectric { detail, .. }) = self;
+        match detail.as_mut()? {
+            DetailDescription::NormalMap { path, .. } | DetailDescription::Bump { path, .. } => {
+                Some(path)
+            }
+            DetailDescription::NoiseBump { .. } => None,
+        }
+    }
+
+    pub fn build(&self, dir: &Path) -> Result<Arc<dyn Material>, RenderError> {
+        let (base, detail): (Arc<dyn Material>, _) = match self {
+            MaterialDescription::Lambertian { albedo, detail } => {
+                (Arc::new(Lambertian::new(*albedo)), detail)
+            }
+            MaterialDescription::Metal {
+                albedo,
+                fuzz,
+                detail,
+            } => (Arc::new(Metal::new(*albedo, *fuzz)?), detail),
+            MaterialDescription::Dielectric {
+                refractive_index,
+                detail,
+            } => (
+                Arc::new(Dielectric::with_dispersion(refractive_index.index())?),
+                detail,
+            ),
+        };
+        Ok(match detail {
+            Some(detail) => Arc::new(DetailedMaterial::new(base, detail.build(dir)?)),
+            None => base,
+        })
+    }
+}
+
+// Detail a material adds by bending its normal. The images are PNG or JPEG files whose values are
+// read as they are, without gamma decoding.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum DetailDescription {
+    NormalMap {
+        path: PathBuf,
+        #[serde(default = "full_strength")]
+        strength: Float,
+    },
+    // Heights from the brightness of an image, `strength` units high at white.
+    Bump {
+        path: PathBuf,
+        strength: Float,
+    },
+    // Bumps that follow the veins of the marble noise texture.
+    NoiseBump {
+        scale: Float,
+        strength: Float,
+    },
+}
+
+fn full_strength() -> Float {
+    1.
+}
+
+impl DetailDescription {
+    pub fn build(&self, dir: &Path) -> Result<SurfaceDetail, RenderError> {
+        let read = |path: &Path| -> Result<Arc<dyn Texture>, RenderError> {
+            let path = dir.join(path);
+            let bytes =
+                fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+            let image = ImageTexture::decode_linear(&bytes)
+                .with_context(|| format!("failed to decode {}", path.display()))?;
+            Ok(Arc::new(image))
+        };
+        Ok(match self {
+            DetailDescription::NormalMap { path, strength } => SurfaceDetail::NormalMap {
+                map: read(path)?,
+                strength: *strength,
+            },
+            DetailDescription::Bump { path, strength } => SurfaceDetail::Bump {
+                height: read(path)?,
+                strength: *strength,
+            },
+            DetailDescription::NoiseBump { scale, strength } => SurfaceDetail::Bump {
+                height: Arc::new(NoiseTexture::new(*scale)),
+                strength: *strength,
+            },
+        })
+    }
+}
+
+// A plain number, the name of a built-in glass or the coefficients of a dispersion formula.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IndexDescription {
+    Constant(Float),
+    Named(Glass),
+    Cauchy { a: Float, b: Float },
+    Sellmeier { b: [Float; 3], c: [Float; 3] },
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Glass {
+    Bk7,
+    Sf11,
+    Diamond,
+}
+
+impl IndexDescription {
+    pub fn index(&self) -> RefractiveIndex {
+        match *self {
+            IndexDescription::Constant(n) => RefractiveIndex::Constant(n),
+            IndexDescription::Named(Glass::Bk7) => RefractiveIndex::BK7,
+            IndexDescription::Named(Glass::Sf11) => RefractiveIndex::SF11,
+            IndexDescription::Named(Glass::Diamond) => RefractiveIndex::DIAMOND,
+            IndexDescription::Cauchy { a, b } => RefractiveIndex::Cauchy { a, b },
+            IndexDescription::Sellmeier { b, c } => RefractiveIndex::Sellmeier { b, c },
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use clap::ValueEnum;
+
+use crate::{
+    make_box, random_f64, random_f64_range, BvhNode, CameraSettings, CheckerTexture, Color,
+    ConstantMedium, Dielectric, DiffuseLight, EnvironmentSpec, Float, Hittable, HittableList,
+    Lambertian, Metal, NoiseTexture, Point3, Quad, RenderError, RotateY, Sphere, Translate, Vec3,
+};
+
+// The demo scenes that can be rendered without a scene file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ScenePreset {
+    #[default]
+    RandomSpheres,
+    ThreeSpheres,
+    CornellBox,
+    CheckeredSpheres,
+    PerlinSpheres,
+    NextWeekFinal,
+}
+
+impl ScenePreset {
+    pub fn build(self) -> Result<HittableList, RenderError> {
+        match self {
+            ScenePreset::RandomSpheres => random_spheres(),
+            ScenePreset::ThreeSpheres => three_spheres(),
+            ScenePreset::CornellBox => cornell_box(),
+            ScenePreset::CheckeredSpheres => checkered_spheres(),
+            ScenePreset::PerlinSpheres => perlin_spheres(),
+            ScenePreset::NextWeekFinal => next_week_final(),
+        }
+    }
+
+    // The view the scene was composed for. Quality settings such as the image width and the
+    // samples per pixel are left to the caller.
+    pub fn camera(self) -> CameraSettings {
+        let view = |look_from: Point3, look_at: Point3, vfov: Float| CameraSettings {
+            look_from: Some(look_from),
+            look_at: Some(look_at),
+            v_up: Some(Vec3::new(0., 1., 0.)),
+            vfov: Some(vfov),
+            defocus_angle: Some(0.),
+            ..CameraSettings::default()
+        };
+
+        match self {
+            ScenePreset::RandomSpheres => CameraSettings {
+                defocus_angle: Some(0.6),
+                focus_dist: Some(10.),
+                ..view(Point3::new(13., 2., 3.), Point3::new(0., 0., 0.), 20.)
+            },
+            ScenePreset::ThreeSpheres => {
+                view(Point3::new(0., 0., 0.), Point3::new(0., 0., -1.), 90.)
+            }
+            ScenePreset::CheckeredSpheres | ScenePreset::PerlinSpheres => {
+                view(Point3::new(13., 2., 3.), Point3::new(0., 0., 0.), 20.)
+            }
+            ScenePreset::CornellBox => CameraSettings {
+                aspect_ratio: Some(1.),
+                ..view(
+                    Point3::new(278., 278., -800.),
+                    Point3::new(278., 278., 0.),
+                    40.,
+                )
+            },
+            ScenePreset::NextWeekFinal => CameraSettings {
+                aspect_ratio: Some(1.),
+                ..view(
+                    Point3::new(478., 278., -600.),
+                    Point3::new(278., 278., 0.),
+                    40.,
+                )
+            },
+        }
+    }
+
+    // Scenes lit by their own lights are rendered against black.
+    pub fn environment(self) -> EnvironmentSpec {
+        match self {
+            ScenePreset::CornellBox | ScenePreset::NextWeekFinal => {
+                EnvironmentSpec::Constant(Color::new(0., 0., 0.))
+            }
+            _ => EnvironmentSpec::Gradient,
+        }
+    }
+}
+
+// The final scene of the first book.
+pub fn random_spheres() -> Result<HittableList, RenderError> {
+    let mut world = HittableList::default();
+    let material_ground = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+
+    world.add(Sphere::new(
+        &Point3::new(0., -1000., 0.),
+        1000.,
+        material_ground,
+    )?);
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let choose_material = random_f64();
+            let center = Point3::new(
+                a as Float + 0.9 * random_f64(),
+                0.2,
+                b as Float + 0.9 * random_f64(),
+            );
+
+            if (center - Point3::new(4., 0.2, 0.)).length() > 0.9 {
+                if choose_material < 0.8 {
+                    let albedo = Color::random() * Color::random();
+                    let sphere_material = Arc::new(Lambertian::new(albedo));
+                    world.add(Sphere::new(&center, 0.2, sphere_material)?);
+                } else if choose_material < 0.95 {
+                    let albedo = Color::random_with_range(0.5, 1.);
+                    let fuzz = random_f64_range(0., 0.5);
+                    let sphere_material = Arc::new(Metal::new(albedo, fuzz)?);
+                    world.add(Sphere::new(&center, 0.2, sphere_material)?);
+                } else {
+                    let sphere_material = Arc::new(Dielectric::new(1.5)?);
+                    world.add(Sphere::new(&center, 0.2, sphere_material)?);
+                }
+            }
+        }
+    }
+
+    let material_1 = Arc::new(Dielectric::new(1.5)?);
+    let material_2 = Arc::new(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
+    let material_3 = Arc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.)?);
+
+    world.add(Sphere::new(&Point3::new(0., 1., 0.), 1.0, material_1)?);
+    world.add(Sphere::new(&Point3::new(-4., 1., 0.), 1.0, material_2)?);
+    world.add(Sphere::new(&Point3::new(4., 1., 0.), 1.0, material_3)?);
+
+    Ok(world)
+}
+
+// The material test scene of the first book.
+fn three_spheres() -> Result<HittableList, RenderError> {
+    let mut world = HittableList::default();
+    let ground = Arc::new(Lambertian::new(Color::new(0.8, 0.8, 0.0)));
+    world.add(Sphere::new(&Point3::new(0., -100.5, -1.), 100., ground)?);
+    let center = Arc::new(Lambertian::new(Color::new(0.1, 0.2, 0.5)));
+    world.add(Sphere::new(&Point3::new(0., 0., -1.2), 0.5, center)?);
+    let glass = Arc::new(Dielectric::new(1.5)?);
+    world.add(Sphere::new(&Point3::new(-1., 0., -1.), 0.5, glass)?);
+    let metal = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.3)?);
+    world.add(Sphere::new(&Point3::new(1., 0., -1.), 0.5, metal)?);
+    Ok(world)
+}
+
+fn cornell_box() -> Result<HittableList, RenderError> {
+    let mut world = HittableList::default();
+    let red = Arc::new(Lambertian::new(Color::new(0.65, 0.05, 0.05)));
+    let white = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let green = Arc::new(Lambertian::new(Color::new(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::new(Color::new(15., 15., 15.)));
+
+    let (x, y, z) = (
+        Vec3::new(555., 0., 0.),
+        Vec3::new(0., 555., 0.),
+        Vec3::new(0., 0., 555.),
+    );
+    world.add(Quad::new(Point3::new(555., 0., 0.), y, z, green)?);
+    world.add(Quad::new(Point3::new(0., 0., 0.), y, z, red)?);
+    world.add(Quad::new(
+        Point3::new(343., 554., 332.),
+        Vec3::new(-130., 0., 0.),
+        Vec3::new(0., 0., -105.),
+        light,
+    )?);
+    world.add(Quad::new(Point3::new(0., 0., 0.), x, z, white.clone())?);
+    world.add(Quad::new(
+        Point3::new(555., 555., 555.),
+        -x,
+        -z,
+        white.clone(),
+    )?);
+    world.add(Quad::new(Point3::new(0., 0., 555.), x, y, white.clone())?);
+
+    world.add(rotated_box(
+        Point3::new(165., 330., 165.),
+        15.,
+        Vec3::new(265., 0., 295.),
+        white.clone(),
+    )?);
+    world.add(rotated_box(
+        Point3::new(165., 165., 165.),
+        -18.,
+        Vec3::new(130., 0., 65.),
+        white,
+    )?);
+    Ok(world)
+}
+
+fn checkered_spheres() -> Result<HittableList, RenderError> {
+    let mut world = HittableList::default();
+    let checker = Arc::new(Lambertian::from_texture(Arc::new(
+        CheckerTexture::from_colors(0.32, Color::new(0.2, 0.3, 0.1), Color::new(0.9, 0.9, 0.9)),
+    )));
+    world.add(Sphere::new(
+        &Point3::new(0., -10., 0.),
+        10.,
+        checker.clone(),
+    )?);
+    world.add(Sphere::new(&Point3::new(0., 10., 0.), 10., checker)?);
+    Ok(world)
+}
+
+fn perlin_spheres() -> Result<HittableList, RenderError> {
+    let mut world = HittableList::default();
+    let marble = Arc::new(Lambertian::from_texture(Arc::new(NoiseTexture::new(4.))));
+    world.add(Sphere::new(
+        &Point3::new(0., -1000., 0.),
+        1000.,
+        marble.clone(),
+    )?);
+    world.add(Sphere::new(&Point3::new(0., 2., 0.), 2., marble)?);
+    Ok(world)
+}
+
+// The final scene of the second book. The moving sphere is static here, since the camera has no
+// shutter interval, and the earth image is replaced by a checker texture.
+fn next_week_final() -> Result<HittableList, RenderError> {
+    let mut world = HittableList::default();
+
+    let ground = Arc::new(Lambertian::new(Color::new(0.48, 0.83, 0.53)));
+    let mut floor = HittableList::default();
+    let boxes_per_side = 20;
+    for i in 0..boxes_per_side {
+        for j in 0..boxes_per_side {
+            let w = 100.;
+            let x0 = -1000. + i as Float * w;
+            let z0 = -1000. + j as Float * w;
+            let y1 = random_f64_range(1., 101.);
+            floor.add(make_box(
+                Point3::new(x0, 0., z0),
+                Point3::new(x0 + w, y1, z0 + w),
+                ground.clone(),
+            )?);
+        }
+    }
+    world.add(BvhNode::new(floor));
+
+    let light = Arc::new(DiffuseLight::new(Color::new(7., 7., 7.)));
+    world.add(Quad::new(
+        Point3::new(123., 554., 147.),
+        Vec3::new(300., 0., 0.),
+        Vec3::new(0., 0., 265.),
+        light,
+    )?);
+
+    let orange = Arc::new(Lambertian::new(Color::new(0.7, 0.3, 0.1)));
+    world.add(Sphere::new(&Point3::new(415., 400., 200.), 50., orange)?);
+
+    let glass = Arc::new(Dielectric::new(1.5)?);
+    world.add(Sphere::new(
+        &Point3::new(260., 150., 45.),
+        50.,
+        glass.clone(),
+    )?);
+    let metal = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.9), 1.)?);
+    world.add(Sphere::new(&Point3::new(0., 150., 145.), 50., metal)?);
+
+    // A glass ball filled with blue smoke, and a faint mist over the whole scene.
+    let boundary: Arc<dyn Hittable> = Arc::new(Sphere::new(
+        &Point3::new(360., 150., 145.),
+        70.,
+        glass.clone(),
+    )?);
+    world.add_shared(Arc::clone(&boundary));
+    world.add(ConstantMedium::new(
+        boundary,
+        0.2,
+        Color::new(0.2, 0.4, 0.9),
+    ));
+    let mist = Arc::new(Sphere::new(&Point3::new(0., 0., 0.), 5000., glass)?);
+    world.add(ConstantMedium::new(mist, 0.0001, Color::new(1., 1., 1.)));
+
+    let checker = Arc::new(Lambertian::from_texture(Arc::new(
+        CheckerTexture::from_colors(20., Color::new(0.1, 0.2, 0.5), Color::new(0.9, 0.9, 0.9)),
+    )));
+    world.add(Sphere::new(&Point3::new(400., 200., 400.), 100., checker)?);
+    let marble = Arc::new(Lambertian::from_texture(Arc::new(NoiseTexture::new(0.2))));
+    world.add(Sphere::new(&Point3::new(220., 280., 300.), 80., marble)?);
+
+    let white = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let mut cluster = HittableList::default();
+    for _ in 0..1000 {
+        let center = Point3::random_with_range(0., 165.);
+        cluster.add(Sphere::new(&center, 10., white.clone())?);
+    }
+    world.add(Translate::new(
+        Arc::new(RotateY::new(Arc::new(BvhNode::new(cluster)), 15.)),
+        Vec3::new(-100., 270., 395.),
+    ));
+
+    Ok(world)
+}
+
+// A box with one corner at the origin, turned around y and then moved into place.
+fn rotated_box(
+    size: Point3,
+    angle: Float,
+    offset: Vec3,
+    mat: Arc<Lambertian>,
+) -> Result<Translate, RenderError> {
+    let sides = make_box(Point3::new(0., 0., 0.), size, mat)?;
+    Ok(Translate::new(
+        Arc::new(RotateY::new(Arc::new(sides), angle)),
+        offset,
+    ))
+}
@@ -0,0 +1,331 @@
+use std::sync::Arc;
+
+use crate::{
+    dot, ensure_finite, stats, unit_vector, validate_radius, Aabb, Context, Float, HitRecord,
+    Hittable, Interval, Material, Point3, Ray, RenderError, Vec3,
+};
+
+const MAX_STEPS: usize = 512;
+// Surfaces are found to within this fraction of the size of their bounding box.
+const SURFACE_EPSILON: Float = 1e-5;
+
+// A signed distance function: negative inside the surface, positive outside. It may underestimate
+// the distance to the surface but never overestimate it, or the marching steps through it.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, p: Point3) -> Float;
+
+    // The surface lies within this box, which also limits how far a ray is marched.
+    fn bounding_box(&self) -> Aabb;
+
+    fn validate(&self) -> Result<(), RenderError> {
+        Ok(())
+    }
+}
+
+// Any closure can be an `Sdf` once its bounding box is known.
+pub struct SdfFn<F> {
+    distance: F,
+    bbox: Aabb,
+}
+
+impl<F: Fn(Point3) -> Float + Send + Sync> SdfFn<F> {
+    pub fn new(bbox: Aabb, distance: F) -> Self {
+        Self { distance, bbox }
+    }
+}
+
+impl<F: Fn(Point3) -> Float + Send + Sync> Sdf for SdfFn<F> {
+    fn distance(&self, p: Point3) -> Float {
+        (self.distance)(p)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+// A ring around the y axis.
+pub struct SdfTorus {
+    center: Point3,
+    major_radius: Float,
+    minor_radius: Float,
+}
+
+impl SdfTorus {
+    pub fn new(center: Point3, major_radius: Float, minor_radius: Float) -> Self {
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for SdfTorus {
+    fn distance(&self, p: Point3) -> Float {
+        let p = p - self.center;
+        let ring = (p.x() * p.x() + p.z() * p.z()).sqrt() - self.major_radius;
+        (ring * ring + p.y() * p.y()).sqrt() - self.minor_radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3::new(outer, self.minor_radius, outer);
+        Aabb::from_points(self.center - extent, self.center + extent)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        ensure_finite(self.center, "torus center")?;
+        validate_radius(self.major_radius, "torus radius")?;
+        validate_radius(self.minor_radius, "torus tube radius")
+    }
+}
+
+// An axis-aligned box reaching `half_size` from its center, with edges rounded by `radius`.
+pub struct SdfRoundedBox {
+    center: Point3,
+    half_size: Vec3,
+    radius: Float,
+}
+
+impl SdfRoundedBox {
+    pub fn new(center: Point3, half_size: Vec3, radius: Float) -> Self {
+        Self {
+            center,
+            half_size,
+            radius,
+        }
+    }
+}
+
+impl Sdf for SdfRoundedBox {
+    fn distance(&self, p: Point3) -> Float {
+        let p = p - self.center;
+        let q = |i: usize| p[i].abs() - self.half_size[i] + self.radius;
+        let (x, y, z) = (q(0), q(1), q(2));
+        let outside = Vec3::new(x.max(0.), y.max(0.), z.max(0.)).length();
+        let inside = x.max(y).max(z).min(0.);
+        outside + inside - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(self.center - self.half_size, self.center + self.half_size)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        ensure_finite(self.center, "rounded box center")?;
+        ensure_finite(self.half_size, "rounded box size")?;
+        validate_radius(self.radius, "rounded box radius")
+    }
+}
+
+// The points within `radius` of the segment from `a` to `b`.
+pub struct SdfCapsule {
+    a: Point3,
+    b: Point3,
+    radius: Float,
+}
+
+impl SdfCapsule {
+    pub fn new(a: Point3, b: Point3, radius: Float) -> Self {
+        Self { a, b, radius }
+    }
+}
+
+impl Sdf for SdfCapsule {
+    fn distance(&self, p: Point3) -> Float {
+        let pa = p - self.a;
+        let ba = self.b - self.a;
+        let length_squared = dot(ba, ba);
+        let h = if length_squared > 0. {
+            (dot(pa, ba) / length_squared).clamp(0., 1.)
+        } else {
+            0.
+        };
+        (pa - h * ba).length() - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::surrounding(
+            &Aabb::from_points(self.a - r, self.a + r),
+            &Aabb::from_points(self.b - r, self.b + r),
+        )
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        ensure_finite(self.a, "capsule end")?;
+        ensure_finite(self.b, "capsule end")?;
+        validate_radius(self.radius, "capsule radius")
+    }
+}
+
+// The union of two surfaces with the seam filleted over a distance of about `k`, which makes them
+// flow into each other like drops of liquid.
+pub struct SmoothUnion {
+    left: Arc<dyn Sdf>,
+    right: Arc<dyn Sdf>,
+    k: Float,
+}
+
+impl SmoothUnion {
+    pub fn new(left: Arc<dyn Sdf>, right: Arc<dyn Sdf>, k: Float) -> Self {
+        Self { left, right, k }
+    }
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: Point3) -> Float {
+        let (a, b) = (self.left.distance(p), self.right.distance(p));
+        if self.k <= 0. {
+            return a.min(b);
+        }
+        let h = (self.k - (a - b).abs()).max(0.) / self.k;
+        a.min(b) - h * h * self.k / 4.
+    }
+
+    // The fillet adds at most `k / 4` to either surface.
+    fn bounding_box(&self) -> Aabb {
+        let bbox = Aabb::surrounding(&self.left.bounding_box(), &self.right.bounding_box());
+        let k = self.k.max(0.) / 4.;
+        let k = Vec3::new(k, k, k);
+        let min = Point3::new(bbox.x.min, bbox.y.min, bbox.z.min);
+        let max = Point3::new(bbox.x.max, bbox.y.max, bbox.z.max);
+        Aabb::from_points(min - k, max + k)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        if !self.k.is_finite() {
+            return Err(RenderError::NonFinite("smooth union radius"));
+        }
+        self.left.validate()?;
+        self.right.validate()
+    }
+}
+
+// Morphs from the left surface at `t = 0` to the right one at `t = 1`.
+pub struct Blend {
+    left: Arc<dyn Sdf>,
+    right: Arc<dyn Sdf>,
+    t: Float,
+}
+
+impl Blend {
+    pub fn new(left: Arc<dyn Sdf>, right: Arc<dyn Sdf>, t: Float) -> Self {
+        Self { left, right, t }
+    }
+}
+
+impl Sdf for Blend {
+    fn distance(&self, p: Point3) -> Float {
+        (1. - self.t) * self.left.distance(p) + self.t * self.right.distance(p)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::surrounding(&self.left.bounding_box(), &self.right.bounding_box())
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        if !(0. ..=1.).contains(&self.t) {
+            return Err(RenderError::InvalidBlend(self.t));
+        }
+        self.left.validate()?;
+        self.right.validate()
+    }
+}
+
+// Renders the surface of an `Sdf` by sphere tracing: the ray advances by the distance to the
+// surface, which can never step through it, until it is close enough to count as a hit.
+pub struct SdfHittable {
+    sdf: Arc<dyn Sdf>,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+    epsilon: Float,
+}
+
+impl SdfHittable {
+    pub fn new(sdf: Arc<dyn Sdf>, mat: Arc<dyn Material>) -> Self {
+        let bbox = sdf.bounding_box();
+        let diagonal = Vec3::new(bbox.x.size(), bbox.y.size(), bbox.z.size()).length();
+        Self {
+            sdf,
+            mat,
+            bbox,
+            epsilon: SURFACE_EPSILON * diagonal,
+        }
+    }
+
+    // Central differences of the distance, which point out of the surface.
+    fn normal(&self, p: Point3) -> Vec3 {
+        let h = self.epsilon;
+        let axis = |i: usize| {
+            let mut offset = Vec3::default();
+            offset[i] = h;
+            self.sdf.distance(p + offset) - self.sdf.distance(p - offset)
+        };
+        let gradient = Vec3::new(axis(0), axis(1), axis(2));
+        if gradient.near_zero() {
+            return Vec3::new(0., 1., 0.);
+        }
+        unit_vector(gradient)
+    }
+}
+
+impl Hittable for SdfHittable {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        let Some(range) = self.bbox.clip(ray, ray_t) else {
+            return false;
+        };
+        let speed = ray.direction().length();
+        let mut t = range.min.max(ray_t.min);
+
+        // Rays entering the bounding box come from outside the surface, even where the box touches
+        // it. Rays leaving the surface they start on would find it again right away, so the side
+        // they march on is decided by their direction, and they first step clear of it.
+        let start = self.sdf.distance(ray.at(t));
+        let side = if range.min > ray_t.min {
+            1.
+        } else if start.abs() > self.epsilon {
+            start.signum()
+        } else {
+            let leaving = dot(*ray.direction(), self.normal(ray.at(t))) > 0.;
+            t += 4. * self.epsilon / speed;
+            if leaving {
+                1.
+            } else {
+                -1.
+            }
+        };
+
+        for _ in 0..MAX_STEPS {
+            if t > range.max {
+                return false;
+            }
+            let distance = side * self.sdf.distance(ray.at(t));
+            if distance < self.epsilon {
+                record.t = t;
+                record.p = ray.at(t);
+                record.set_face_normal(ray, &self.normal(record.p));
+                // There are no surface coordinates to differentiate.
+                (record.u, record.v) = (0., 0.);
+                (record.dpdu, record.dpdv) = (Vec3::default(), Vec3::default());
+                record.mat = self.mat.clone();
+                return true;
+            }
+            t += distance.max(self.epsilon) / speed;
+        }
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.sdf.validate().context("signed distance function")?;
+        self.mat
+            .validate()
+            .context("signed distance function material")
+    }
+}
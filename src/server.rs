@@ -0,0 +1,398 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::{self, Read},
+    panic::{self, AssertUnwindSafe},
+    path::Component,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+    thread,
+};
+
+use indicatif::ProgressBar;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    BvhNode, CameraBuilder, CameraSettings, Context, Hittable, RenderError, RenderStats, SceneFile,
+    ServeArgs,
+};
+
+// Serves a small HTTP API for rendering scene files:
+//
+// - `POST /jobs` queues `{"scene": <scene file>, "settings": <camera settings>}`, where the
+//   settings override the camera of the scene, and answers with the id of the job.
+// - `GET /jobs` lists every job and `GET /jobs/{id}` reports the state and progress of one.
+// - `GET /jobs/{id}/image` returns the finished image as a PPM.
+// - `DELETE /jobs/{id}` cancels a queued or running job, or forgets a finished one.
+//
+// Up to `concurrency` jobs render at the same time, each on `threads` threads, and the last
+// `keep_finished` finished jobs are kept.
+pub fn serve(args: &ServeArgs) -> Result<(), RenderError> {
+    let server = Server::http(&args.address)
+        .map_err(io::Error::other)
+        .with_context(|| format!("failed to listen on {}", args.address))?;
+    let jobs = Arc::new(Jobs {
+        keep_finished: args.keep_finished,
+        ..Jobs::default()
+    });
+
+    for _ in 0..args.concurrency.max(1) {
+        let jobs = Arc::clone(&jobs);
+        let threads = args.threads;
+        thread::spawn(move || jobs.work(threads));
+    }
+
+    eprintln!("listening on http://{}", args.address);
+    for mut request in server.incoming_requests() {
+        let response = jobs.handle(&mut request);
+        // The client may have gone away already, which is no reason to stop serving.
+        let _ = request.respond(response.into_response());
+    }
+
+    Ok(())
+}
+
+// Limits that keep a single job from exhausting the memory or the time of the server.
+const MAX_IMAGE_SIDE: u32 = 8192;
+const MAX_SAMPLES_PER_PIXEL: u32 = 100_000;
+const MAX_DEPTH: u32 = 1000;
+const MAX_BODY: u64 = 1 << 20;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobRequest {
+    scene: SceneFile,
+    #[serde(default)]
+    settings: CameraSettings,
+}
+
+impl JobRequest {
+    // Checks the camera the job will render with before it is queued.
+    fn check(&self) -> Result<(), String> {
+        let cam = self
+            .settings
+            .apply(self.scene.camera.apply(CameraBuilder::default()))
+            .build()
+            .map_err(|e| format!("invalid camera configuration: {e}"))?;
+        if cam.image_width > MAX_IMAGE_SIDE {
+            return Err(format!(
+                "the image width is at most {MAX_IMAGE_SIDE}, got {}",
+                cam.image_width
+            ));
+        }
+        let (width, height) = cam.image_size();
+        if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+            return Err(format!(
+                "the image is at most {MAX_IMAGE_SIDE} pixels wide and high, got {width}x{height}"
+            ));
+        }
+        if cam.samples_per_pixel > MAX_SAMPLES_PER_PIXEL {
+            return Err(format!(
+                "the samples per pixel are at most {MAX_SAMPLES_PER_PIXEL}, got {}",
+                cam.samples_per_pixel
+            ));
+        }
+        if cam.max_depth > MAX_DEPTH {
+            return Err(format!(
+                "the max depth is at most {MAX_DEPTH}, got {}",
+                cam.max_depth
+            ));
+        }
+
+        // Clients only get to read files below the working directory of the server, since the
+        // errors of the loaders can quote what they read.
+        for path in self.scene.clone().paths_mut() {
+            if !path
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(format!(
+                    "files are named relative to the server without '..', got {}",
+                    path.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+enum JobState {
+    Queued,
+    Running,
+    Done {
+        image: Arc<Vec<u8>>,
+        stats: RenderStats,
+    },
+    Failed(String),
+    Cancelled,
+}
+
+impl JobState {
+    fn name(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Done { .. } => "done",
+            JobState::Failed(_) => "failed",
+            JobState::Cancelled => "cancelled",
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        !matches!(self, JobState::Queued | JobState::Running)
+    }
+}
+
+struct Job {
+    id: u64,
+    request: Mutex<Option<JobRequest>>,
+    state: Mutex<JobState>,
+    // Counts the finished rows, just like the progress bar of the CLI.
+    progress: ProgressBar,
+    cancel: AtomicBool,
+}
+
+impl Job {
+    fn state(&self) -> MutexGuard<'_, JobState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn status(&self) -> Value {
+        let state = self.state();
+        let mut status = json!({
+            "id": self.id,
+            "state": state.name(),
+            "rows_done": self.progress.position(),
+            "rows_total": self.progress.length().unwrap_or(0),
+        });
+        match &*state {
+            JobState::Done { stats, .. } => {
+                status["elapsed_seconds"] = json!(stats.elapsed.as_secs_f64());
+            }
+            JobState::Failed(message) => status["error"] = json!(message),
+            _ => {}
+        }
+        status
+    }
+
+    fn render(&self, request: JobRequest, threads: usize) -> Result<JobState, RenderError> {
+        let builder = CameraBuilder::default().threads(threads).quiet(true);
+        let mut cam = request.scene.camera(builder, &request.settings)?;
+
+        let world = request.scene.build().context("failed to build the scene")?;
+        world.validate().context("invalid scene")?;
+        let world = BvhNode::new(world);
+
+        let Some((image, stats)) =
+            cam.render_image_cancellable(&world, &self.progress, &self.cancel)
+        else {
+            return Ok(JobState::Cancelled);
+        };
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm)?;
+        Ok(JobState::Done {
+            image: Arc::new(ppm),
+            stats,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Jobs {
+    all: Mutex<BTreeMap<u64, Arc<Job>>>,
+    queue: Mutex<VecDeque<Arc<Job>>>,
+    queued: Condvar,
+    last_id: AtomicU64,
+    keep_finished: usize,
+}
+
+impl Jobs {
+    fn all(&self) -> MutexGuard<'_, BTreeMap<u64, Arc<Job>>> {
+        self.all.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<Job>> {
+        let id = id.parse().ok()?;
+        self.all().get(&id).cloned()
+    }
+
+    fn submit(&self, request: JobRequest) -> Arc<Job> {
+        let id = self.last_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let job = Arc::new(Job {
+            id,
+            request: Mutex::new(Some(request)),
+            state: Mutex::new(JobState::Queued),
+            progress: ProgressBar::hidden(),
+            cancel: AtomicBool::new(false),
+        });
+        self.all().insert(id, Arc::clone(&job));
+
+        self.queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(Arc::clone(&job));
+        self.queued.notify_one();
+        job
+    }
+
+    // Forgets the oldest finished jobs beyond the `keep_finished` most recent ones.
+    fn forget_old(&self, all: &mut BTreeMap<u64, Arc<Job>>) {
+        let finished: Vec<u64> = all
+            .values()
+            .filter(|job| job.state().is_finished())
+            .map(|job| job.id)
+            .collect();
+        for id in &finished[..finished.len().saturating_sub(self.keep_finished)] {
+            all.remove(id);
+        }
+    }
+
+    fn next(&self) -> Arc<Job> {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(job) = queue.pop_front() {
+                return job;
+            }
+            queue = self.queued.wait(queue).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    fn work(&self, threads: usize) {
+        loop {
+            let job = self.next();
+            let request = job.request.lock().unwrap_or_else(|e| e.into_inner()).take();
+            let Some(request) = request else {
+                continue;
+            };
+
+            {
+                let mut state = job.state();
+                if state.is_finished() {
+                    continue;
+                }
+                *state = JobState::Running;
+            }
+
+            // A panicking render fails its job but leaves this worker running.
+            let state = match panic::catch_unwind(AssertUnwindSafe(|| job.render(request, threads)))
+            {
+                Ok(Ok(state)) => state,
+                Ok(Err(e)) => JobState::Failed(e.to_string()),
+                Err(_) => JobState::Failed("the render panicked".to_string()),
+            };
+            // Under the lock of the list, so that nobody sees the job finished before the old ones
+            // are gone.
+            let mut all = self.all();
+            *job.state() = state;
+            self.forget_old(&mut all);
+        }
+    }
+
+    fn handle(&self, request: &mut Request) -> Reply {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let segments: Vec<&str> = url
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        match (method, segments.as_slice()) {
+            (Method::Get, ["jobs"]) => {
+                let jobs: Vec<Value> = self.all().values().map(|job| job.status()).collect();
+                Reply::Json(200, json!(jobs))
+            }
+            (Method::Post, ["jobs"]) => {
+                let too_large =
+                    || Reply::error(413, &format!("the request is at most {MAX_BODY} bytes"));
+                if request
+                    .body_length()
+                    .is_some_and(|length| length as u64 > MAX_BODY)
+                {
+                    return too_large();
+                }
+                let mut body = String::new();
+                let mut reader = request.as_reader().take(MAX_BODY + 1);
+                if let Err(e) = reader.read_to_string(&mut body) {
+                    return Reply::error(400, &format!("failed to read the request: {e}"));
+                }
+                if body.len() as u64 > MAX_BODY {
+                    return too_large();
+                }
+                let job: JobRequest = match serde_json::from_str(&body) {
+                    Ok(job) => job,
+                    Err(e) => return Reply::error(400, &format!("invalid job: {e}")),
+                };
+                match job.check() {
+                    Ok(()) => Reply::Json(201, self.submit(job).status()),
+                    Err(message) => Reply::error(400, &message),
+                }
+            }
+            (Method::Get, ["jobs", id]) => match self.get(id) {
+                Some(job) => Reply::Json(200, job.status()),
+                None => Reply::error(404, "no such job"),
+            },
+            (Method::Get, ["jobs", id, "image"]) => {
+                let Some(job) = self.get(id) else {
+                    return Reply::error(404, "no such job");
+                };
+                let state = job.state();
+                match &*state {
+                    JobState::Done { image, .. } => Reply::Image(Arc::clone(image)),
+                    state => Reply::error(409, &format!("the job is {}", state.name())),
+                }
+            }
+            (Method::Delete, ["jobs", id]) => {
+                let Some(job) = self.get(id) else {
+                    return Reply::error(404, "no such job");
+                };
+                let mut state = job.state();
+                match *state {
+                    JobState::Queued => *state = JobState::Cancelled,
+                    // The render notices the flag before its next row and the worker records the
+                    // cancellation.
+                    JobState::Running => job.cancel.store(true, Ordering::Relaxed),
+                    _ => {
+                        drop(state);
+                        self.all().remove(&job.id);
+                        return Reply::Empty;
+                    }
+                }
+                drop(state);
+                self.forget_old(&mut self.all());
+                Reply::Json(200, job.status())
+            }
+            _ => Reply::error(404, "not found"),
+        }
+    }
+}
+
+enum Reply {
+    Json(u16, Value),
+    Image(Arc<Vec<u8>>),
+    Empty,
+}
+
+impl Reply {
+    fn error(status: u16, message: &str) -> Reply {
+        Reply::Json(status, json!({ "error": message }))
+    }
+
+    fn into_response(self) -> Response<io::Cursor<Vec<u8>>> {
+        let (status, content_type, body) = match self {
+            Reply::Json(status, body) => (status, "application/json", body.to_string().into()),
+            Reply::Image(image) => (200, "image/x-portable-pixmap", image.to_vec()),
+            Reply::Empty => (204, "text/plain", Vec::new()),
+        };
+        let header = Header::from_bytes("Content-Type", content_type).expect("valid header");
+        Response::from_data(body)
+            .with_status_code(status)
+            .with_header(header)
+    }
+}
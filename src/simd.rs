@@ -0,0 +1,100 @@
+use std::array;
+
+#[cfg(feature = "f32")]
+pub use wide::f32x4 as Lane;
+#[cfg(not(feature = "f32"))]
+pub use wide::f64x4 as Lane;
+
+use crate::{Aabb, Float, Interval, Point3, Ray, Vec3};
+
+pub const LANES: usize = 4;
+
+// Four rays stored as structure-of-arrays so the slab and sphere tests can run on all of them at
+// once. Lanes without a ray are left out of `active` and hold a dummy ray that is never reported.
+pub struct RayPacket {
+    pub rays: [Ray; LANES],
+    pub active: u8,
+    origin: [Lane; 3],
+    dir: [Lane; 3],
+    inv_dir: [Lane; 3],
+}
+
+impl RayPacket {
+    pub fn new(rays: &[Option<Ray>; LANES]) -> Self {
+        let active = rays
+            .iter()
+            .enumerate()
+            .filter(|(_, ray)| ray.is_some())
+            .fold(0, |mask, (k, _)| mask | 1 << k);
+        let rays = rays
+            .map(|ray| ray.unwrap_or_else(|| Ray::new(Point3::default(), Vec3::new(1., 1., 1.))));
+
+        let gather =
+            |f: fn(&Ray) -> &Vec3, axis: usize| Lane::new(array::from_fn(|k| f(&rays[k])[axis]));
+        let origin = array::from_fn(|axis| gather(Ray::origin, axis));
+        let dir: [Lane; 3] = array::from_fn(|axis| gather(Ray::direction, axis));
+        let inv_dir = dir.map(|d| Lane::splat(1.) / d);
+
+        Self {
+            rays,
+            active,
+            origin,
+            dir,
+            inv_dir,
+        }
+    }
+
+    // Slab test of every lane against `bbox`, returning the mask of lanes whose ray enters it
+    // within their interval.
+    pub fn hit_aabb(&self, bbox: &Aabb, ray_t: &[Interval; LANES]) -> u8 {
+        let mut t_min = Lane::new(ray_t.map(|t| t.min));
+        let mut t_max = Lane::new(ray_t.map(|t| t.max));
+
+        for axis in 0..3 {
+            let slab = bbox[axis];
+            let t0 = (Lane::splat(slab.min) - self.origin[axis]) * self.inv_dir[axis];
+            let t1 = (Lane::splat(slab.max) - self.origin[axis]) * self.inv_dir[axis];
+
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+        }
+
+        t_max.simd_gt(t_min).to_bitmask() as u8
+    }
+
+    // Solves the sphere quadratic for every lane, returning the mask of lanes with a root inside
+    // their interval together with the nearest such root.
+    pub fn hit_sphere(
+        &self,
+        center: Point3,
+        radius: Float,
+        ray_t: &[Interval; LANES],
+    ) -> (u8, [Float; LANES]) {
+        let t_min = Lane::new(ray_t.map(|t| t.min));
+        let t_max = Lane::new(ray_t.map(|t| t.max));
+
+        let oc: [Lane; 3] = array::from_fn(|axis| Lane::splat(center[axis]) - self.origin[axis]);
+        let a = dot(&self.dir, &self.dir);
+        let h = dot(&self.dir, &oc);
+        let c = dot(&oc, &oc) - Lane::splat(radius * radius);
+
+        let discriminant = h * h - a * c;
+        let has_roots = discriminant.simd_ge(Lane::splat(0.));
+        let sqrtd = discriminant.max(Lane::splat(0.)).sqrt();
+
+        let near = (h - sqrtd) / a;
+        let far = (h + sqrtd) / a;
+        let surrounds = |t: Lane| t_min.simd_lt(t) & t.simd_lt(t_max);
+
+        let near_ok = surrounds(near);
+        let root = near_ok.select(near, far);
+        let hit = has_roots & (near_ok | surrounds(far));
+
+        (hit.to_bitmask() as u8, root.to_array())
+    }
+}
+
+#[inline]
+fn dot(a: &[Lane; 3], b: &[Lane; 3]) -> Lane {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
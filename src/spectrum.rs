@@ -0,0 +1,183 @@
+// The published coefficients are kept at full precision even when they get rounded to f32.
+#![cfg_attr(feature = "f32", allow(clippy::excessive_precision))]
+
+use std::sync::OnceLock;
+
+use crate::{random_f64, Color, Float, Vec3};
+
+pub const LAMBDA_MIN: Float = 380.;
+pub const LAMBDA_MAX: Float = 780.;
+
+// Wavelength of the sodium D line, used whenever a dispersive material is hit by a ray that does
+// not carry a wavelength.
+pub const LAMBDA_REFERENCE: Float = 589.3;
+
+#[derive(Debug, Clone, Copy)]
+pub enum RefractiveIndex {
+    Constant(Float),
+    // n = a + b / λ², λ in micrometres.
+    Cauchy { a: Float, b: Float },
+    // n² = 1 + Σ bᵢλ² / (λ² - cᵢ), λ in micrometres and cᵢ in micrometres squared.
+    Sellmeier { b: [Float; 3], c: [Float; 3] },
+}
+
+impl RefractiveIndex {
+    pub const BK7: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+        c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+    };
+
+    pub const SF11: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [1.737_596_95, 0.313_747_346, 1.898_781_01],
+        c: [0.013_188_707, 0.062_306_814_2, 155.236_29],
+    };
+
+    pub const DIAMOND: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [0.3306, 4.3356, 0.],
+        c: [0.030_625, 0.011_236, 0.],
+    };
+
+    pub fn at(&self, wavelength: Float) -> Float {
+        let micrometres = wavelength / 1000.;
+        let l2 = micrometres * micrometres;
+
+        match *self {
+            RefractiveIndex::Constant(n) => n,
+            RefractiveIndex::Cauchy { a, b } => a + b / l2,
+            RefractiveIndex::Sellmeier { b, c } => {
+                let n2 = 1. + (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<Float>();
+                n2.sqrt()
+            }
+        }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, RefractiveIndex::Constant(_))
+    }
+}
+
+impl From<Float> for RefractiveIndex {
+    fn from(n: Float) -> Self {
+        RefractiveIndex::Constant(n)
+    }
+}
+
+#[inline]
+pub fn sample_wavelength() -> (Float, Float) {
+    let wavelength = LAMBDA_MIN + random_f64() * (LAMBDA_MAX - LAMBDA_MIN);
+    (wavelength, 1. / (LAMBDA_MAX - LAMBDA_MIN))
+}
+
+// Multi-lobe fit of the CIE 1931 2° observer from Wyman, Sloan and Shirley (2013).
+pub fn cie_xyz(wavelength: Float) -> Vec3 {
+    let lobe = |x: Float, mu: Float, sigma_lo: Float, sigma_hi: Float| {
+        let t = (x - mu) * if x < mu { sigma_lo } else { sigma_hi };
+        (-0.5 * t * t).exp()
+    };
+
+    let x = 1.056 * lobe(wavelength, 599.8, 0.0264, 0.0323)
+        + 0.362 * lobe(wavelength, 442.0, 0.0624, 0.0374)
+        - 0.065 * lobe(wavelength, 501.1, 0.0490, 0.0382);
+    let y = 0.821 * lobe(wavelength, 568.8, 0.0213, 0.0247)
+        + 0.286 * lobe(wavelength, 530.9, 0.0613, 0.0322);
+    let z = 1.217 * lobe(wavelength, 437.0, 0.0845, 0.0278)
+        + 0.681 * lobe(wavelength, 459.0, 0.0385, 0.0725);
+
+    Vec3::new(x, y, z)
+}
+
+#[inline]
+pub fn xyz_to_linear_srgb(xyz: Vec3) -> Color {
+    Color::new(
+        3.240_454_2 * xyz.x() - 1.537_138_5 * xyz.y() - 0.498_531_4 * xyz.z(),
+        -0.969_266_0 * xyz.x() + 1.876_010_8 * xyz.y() + 0.041_556_0 * xyz.z(),
+        0.055_643_4 * xyz.x() - 0.204_025_9 * xyz.y() + 1.057_225_2 * xyz.z(),
+    )
+}
+
+// Converts an XYZ estimate to linear sRGB, scaled so that a spectrally flat unit reflectance maps
+// back to white instead of the slight tint the equal-energy illuminant has under D65.
+pub fn spectral_to_rgb(xyz: Vec3) -> Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    let white = WHITE.get_or_init(|| {
+        let mut integral = Vec3::default();
+        let mut wavelength = LAMBDA_MIN;
+        while wavelength < LAMBDA_MAX {
+            integral += cie_xyz(wavelength + 0.5);
+            wavelength += 1.;
+        }
+        xyz_to_linear_srgb(integral)
+    });
+
+    let rgb = xyz_to_linear_srgb(xyz);
+    Color::new(
+        rgb.x() / white.x(),
+        rgb.y() / white.y(),
+        rgb.z() / white.z(),
+    )
+}
+
+// Smits (1999) basis spectra over ten bins between 380nm and 720nm.
+const SMITS_WHITE: [Float; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const SMITS_CYAN: [Float; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const SMITS_MAGENTA: [Float; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const SMITS_YELLOW: [Float; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [Float; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [Float; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const SMITS_BLUE: [Float; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+// Evaluates the Smits upsampling of an RGB triple at a single wavelength.
+pub fn rgb_to_spectrum(rgb: Color, wavelength: Float) -> Float {
+    let bin = (((wavelength - 380.) / 34.) as usize).min(9);
+    let (r, g, b) = (rgb.x(), rgb.y(), rgb.z());
+
+    if r <= g && r <= b {
+        let base = r * SMITS_WHITE[bin];
+        if g <= b {
+            base + (g - r) * SMITS_CYAN[bin] + (b - g) * SMITS_BLUE[bin]
+        } else {
+            base + (b - r) * SMITS_CYAN[bin] + (g - b) * SMITS_GREEN[bin]
+        }
+    } else if g <= r && g <= b {
+        let base = g * SMITS_WHITE[bin];
+        if r <= b {
+            base + (r - g) * SMITS_MAGENTA[bin] + (b - r) * SMITS_BLUE[bin]
+        } else {
+            base + (b - g) * SMITS_MAGENTA[bin] + (r - b) * SMITS_RED[bin]
+        }
+    } else {
+        let base = b * SMITS_WHITE[bin];
+        if r <= g {
+            base + (r - b) * SMITS_YELLOW[bin] + (g - r) * SMITS_GREEN[bin]
+        } else {
+            base + (g - b) * SMITS_YELLOW[bin] + (r - g) * SMITS_RED[bin]
+        }
+    }
+}
+
+// Collapses an RGB quantity to its spectral value when the ray carries a wavelength, so the path
+// throughput holds the same scalar in all three channels.
+#[inline]
+pub fn color_at_wavelength(color: Color, wavelength: Option<Float>) -> Color {
+    match wavelength {
+        Some(wavelength) => {
+            let value = rgb_to_spectrum(color, wavelength);
+            Color::new(value, value, value)
+        }
+        None => color,
+    }
+}
@@ -1,26 +1,78 @@
 use std::sync::Arc;
 
-use crate::{dot, Hittable, Interval, Material, Point3};
+use crate::{
+    dot, ensure_finite, stats, Aabb, Context, Float, HitRecord, Hittable, Interval, Material,
+    Point3, Ray, RenderError, Span, Vec3, PI,
+};
+#[cfg(feature = "simd")]
+use crate::{RayPacket, LANES};
 
 pub struct Sphere {
     center: Point3,
-    radius: f64,
+    radius: Float,
     mat: Arc<dyn Material>,
+    bbox: Aabb,
 }
 
 impl Sphere {
-    #[must_use]
-    pub fn new(center: &Point3, radius: f64, mat: Arc<impl Material + 'static>) -> Self {
-        Self {
+    pub fn new(
+        center: &Point3,
+        radius: Float,
+        mat: Arc<dyn Material>,
+    ) -> Result<Self, RenderError> {
+        ensure_finite(*center, "sphere center")?;
+        if !radius.is_finite() {
+            return Err(RenderError::NonFinite("sphere radius"));
+        }
+        if radius < 0. {
+            return Err(RenderError::NegativeRadius(radius));
+        }
+
+        let rvec = Vec3::new(radius, radius, radius);
+        Ok(Self {
             center: *center,
-            radius: radius.max(0.0),
+            radius,
             mat,
-        }
+            bbox: Aabb::from_points(*center - rvec, *center + rvec),
+        })
     }
 }
 
+impl Sphere {
+    fn fill_record(&self, ray: &Ray, t: Float, record: &mut HitRecord) {
+        record.t = t;
+        record.p = ray.at(t);
+        let outward_normal = (record.p - self.center) / self.radius;
+        record.set_face_normal(ray, &outward_normal);
+        (record.u, record.v) = sphere_uv(&outward_normal);
+        (record.dpdu, record.dpdv) = sphere_tangents(&outward_normal, self.radius);
+        record.mat = self.mat.clone();
+    }
+}
+
+// Longitude and latitude of a point on the unit sphere, with u = 0 at -x and v = 0 at -y.
+fn sphere_uv(p: &Point3) -> (Float, Float) {
+    let theta = (-p.y()).acos();
+    let phi = Float::atan2(-p.z(), p.x()) + PI;
+    (phi / (2. * PI), theta / PI)
+}
+
+// The derivatives of the point at `n` on a sphere of `radius` along the longitude and latitude of
+// `sphere_uv`. Both vanish at the poles.
+fn sphere_tangents(n: &Vec3, radius: Float) -> (Vec3, Vec3) {
+    let sin_theta = (n.x() * n.x() + n.z() * n.z()).sqrt();
+    if sin_theta == 0. {
+        return (Vec3::default(), Vec3::default());
+    }
+    let dpdu = 2. * PI * radius * Vec3::new(n.z(), 0., -n.x());
+    let dpdv =
+        PI * radius / sin_theta * Vec3::new(-n.x() * n.y(), sin_theta * sin_theta, -n.y() * n.z());
+    (dpdu, dpdv)
+}
+
 impl Hittable for Sphere {
-    fn hit(&self, ray: &crate::Ray, ray_t: Interval, record: &mut crate::HitRecord) -> bool {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
         let oc = self.center - *ray.origin();
         let a = ray.direction().length_squared();
         let h = dot(*ray.direction(), oc);
@@ -43,12 +95,58 @@ impl Hittable for Sphere {
             }
         }
 
-        record.t = root;
-        record.p = ray.at(record.t);
-        let outward_normal = (record.p - self.center) / self.radius;
-        record.set_face_normal(ray, &outward_normal);
-        record.mat = self.mat.clone();
-
+        self.fill_record(ray, root, record);
         true
     }
+
+    #[cfg(feature = "simd")]
+    fn hit_packet(
+        &self,
+        packet: &RayPacket,
+        mask: u8,
+        ray_t: &mut [Interval; LANES],
+        records: &mut [HitRecord; LANES],
+    ) -> u8 {
+        stats::count(|s| s.intersection_tests += u64::from(mask.count_ones()));
+        let (hits, roots) = packet.hit_sphere(self.center, self.radius, ray_t);
+        let hits = hits & mask;
+        for k in (0..LANES).filter(|k| hits & 1 << k != 0) {
+            ray_t[k].max = roots[k];
+            self.fill_record(&packet.rays[k], roots[k], &mut records[k]);
+        }
+        hits
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        stats::count(|s| s.intersection_tests += 1);
+        let oc = self.center - *ray.origin();
+        let a = ray.direction().length_squared();
+        let h = dot(*ray.direction(), oc);
+        let c = oc.length_squared() - self.radius * self.radius;
+
+        let discriminant = h * h - a * c;
+        if discriminant <= 0. || self.radius == 0. {
+            return Some(Vec::new());
+        }
+
+        let sqrtd = discriminant.sqrt();
+        let boundary = |t| {
+            let mut record = HitRecord::default();
+            self.fill_record(ray, t, &mut record);
+            record.normal = (record.p - self.center) / self.radius;
+            record
+        };
+        Some(vec![Span {
+            entry: boundary((h - sqrtd) / a),
+            exit: boundary((h + sqrtd) / a),
+        }])
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("sphere material")
+    }
 }
@@ -0,0 +1,168 @@
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+thread_local! {
+    static COUNTERS: RefCell<ThreadStats> = const { RefCell::new(ThreadStats::ZERO) };
+}
+
+// Bumps the counters of the calling thread. They are cheap enough to stay on in every build.
+#[inline]
+pub(crate) fn count(f: impl FnOnce(&mut ThreadStats)) {
+    COUNTERS.with(|counters| f(&mut counters.borrow_mut()));
+}
+
+// Returns the counters gathered on the calling thread since the last call and resets them.
+pub(crate) fn take_thread_stats() -> ThreadStats {
+    COUNTERS.with(|counters| counters.take())
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadStats {
+    pub primary_rays: u64,
+    pub secondary_rays: u64,
+    pub intersection_tests: u64,
+    pub bvh_node_visits: u64,
+    pub path_segments: u64,
+    pub paths: u64,
+    pub elapsed: Duration,
+}
+
+impl ThreadStats {
+    const ZERO: ThreadStats = ThreadStats {
+        primary_rays: 0,
+        secondary_rays: 0,
+        intersection_tests: 0,
+        bvh_node_visits: 0,
+        path_segments: 0,
+        paths: 0,
+        elapsed: Duration::ZERO,
+    };
+
+    pub fn rays(&self) -> u64 {
+        self.primary_rays + self.secondary_rays
+    }
+
+    pub fn average_path_length(&self) -> f64 {
+        if self.paths == 0 {
+            0.
+        } else {
+            self.path_segments as f64 / self.paths as f64
+        }
+    }
+
+    pub fn rays_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0. {
+            self.rays() as f64 / seconds
+        } else {
+            0.
+        }
+    }
+
+    pub(crate) fn merge(&self, other: &ThreadStats) -> ThreadStats {
+        ThreadStats {
+            primary_rays: self.primary_rays + other.primary_rays,
+            secondary_rays: self.secondary_rays + other.secondary_rays,
+            intersection_tests: self.intersection_tests + other.intersection_tests,
+            bvh_node_visits: self.bvh_node_visits + other.bvh_node_visits,
+            path_segments: self.path_segments + other.path_segments,
+            paths: self.paths + other.paths,
+            elapsed: self.elapsed + other.elapsed,
+        }
+    }
+
+    fn report(&self) -> ThreadReport {
+        ThreadReport {
+            primary_rays: self.primary_rays,
+            secondary_rays: self.secondary_rays,
+            intersection_tests: self.intersection_tests,
+            bvh_node_visits: self.bvh_node_visits,
+            average_path_length: self.average_path_length(),
+            elapsed_seconds: self.elapsed.as_secs_f64(),
+            rays_per_second: self.rays_per_second(),
+        }
+    }
+}
+
+// The counters of a thread as written to JSON, with the rates derived from them.
+#[derive(Serialize)]
+struct ThreadReport {
+    primary_rays: u64,
+    secondary_rays: u64,
+    intersection_tests: u64,
+    bvh_node_visits: u64,
+    average_path_length: f64,
+    elapsed_seconds: f64,
+    rays_per_second: f64,
+}
+
+#[derive(Serialize)]
+struct RenderReport {
+    threads: Vec<ThreadReport>,
+    total: ThreadReport,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RenderStats {
+    pub threads: Vec<ThreadStats>,
+    pub elapsed: Duration,
+}
+
+impl RenderStats {
+    // Sums the counters of every thread. Rays per second of the total use the wall-clock time of
+    // the whole render.
+    pub fn total(&self) -> ThreadStats {
+        let total = self
+            .threads
+            .iter()
+            .fold(ThreadStats::default(), |total, thread| total.merge(thread));
+        ThreadStats {
+            elapsed: self.elapsed,
+            ..total
+        }
+    }
+
+    pub fn write_table(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{:>8} {:>12} {:>12} {:>14} {:>14} {:>9} {:>14}",
+            "thread", "primary", "secondary", "intersections", "bvh visits", "avg path", "rays/s"
+        )?;
+
+        let rows = self
+            .threads
+            .iter()
+            .enumerate()
+            .map(|(i, thread)| (i.to_string(), *thread))
+            .chain(std::iter::once(("total".to_string(), self.total())));
+        for (name, stats) in rows {
+            writeln!(
+                out,
+                "{:>8} {:>12} {:>12} {:>14} {:>14} {:>9.2} {:>14.0}",
+                name,
+                stats.primary_rays,
+                stats.secondary_rays,
+                stats.intersection_tests,
+                stats.bvh_node_visits,
+                stats.average_path_length(),
+                stats.rays_per_second()
+            )?;
+        }
+
+        writeln!(out, "rendered in {:.3}s", self.elapsed.as_secs_f64())
+    }
+
+    pub fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        let report = RenderReport {
+            threads: self.threads.iter().map(ThreadStats::report).collect(),
+            total: self.total().report(),
+        };
+        serde_json::to_writer_pretty(&mut *out, &report)?;
+        writeln!(out)
+    }
+}
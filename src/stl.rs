@@ -0,0 +1,109 @@
+use std::io::{self, BufRead};
+
+use crate::{gamma_to_linear, invalid_data, Color, Float, Point3, TriangleMesh};
+
+const HEADER_SIZE: usize = 80;
+const FACET_SIZE: usize = 50;
+
+// Reads an STL mesh, ASCII or binary. Facets keep vertices of their own, so the normals computed
+// for them are flat, as the sharp edges of CAD models should be. The stored facet normals are
+// often missing or wrong and are ignored.
+pub fn read_stl(input: &mut impl BufRead) -> io::Result<TriangleMesh> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+
+    // Binary files may start with "solid" too, but their size always matches their facet count.
+    let facets = bytes
+        .get(HEADER_SIZE..HEADER_SIZE + 4)
+        .map(|count| u32::from_le_bytes(count.try_into().unwrap()) as usize);
+    match facets {
+        Some(facets) if bytes.len() == HEADER_SIZE + 4 + facets * FACET_SIZE => {
+            Ok(read_binary(&bytes))
+        }
+        _ if bytes.starts_with(b"solid") => read_ascii(&bytes),
+        _ => Err(invalid_data("truncated binary STL")),
+    }
+}
+
+fn read_ascii(bytes: &[u8]) -> io::Result<TriangleMesh> {
+    let text = std::str::from_utf8(bytes).map_err(|_| invalid_data("ASCII STL is not UTF-8"))?;
+    let mut mesh = TriangleMesh::default();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        if word != "vertex" {
+            continue;
+        }
+        let mut coordinate = || -> io::Result<Float> {
+            words
+                .next()
+                .and_then(|word| word.parse().ok())
+                .ok_or_else(|| invalid_data("bad vertex"))
+        };
+        mesh.positions
+            .push(Point3::new(coordinate()?, coordinate()?, coordinate()?));
+    }
+
+    if mesh.positions.len() % 3 != 0 {
+        return Err(invalid_data("facet without three vertices"));
+    }
+    let facets = mesh.positions.len() / 3;
+    mesh.triangles = (0..facets).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]).collect();
+    Ok(mesh)
+}
+
+// Some exporters store a 15-bit facet color in the attribute word. Materialise Magics marks its
+// files with "COLOR=" and the default color in the header, and sets the top bit on facets that use
+// that default; the others set the top bit on facets that have a color of their own.
+fn read_binary(bytes: &[u8]) -> TriangleMesh {
+    let header = &bytes[..HEADER_SIZE];
+    let materialise = header.windows(6).position(|w| w == b"COLOR=");
+    let default_color = materialise
+        .and_then(|i| header.get(i + 6..i + 9))
+        .map(|rgb| {
+            let channel = |c: u8| gamma_to_linear(Float::from(c) / 255.);
+            Color::new(channel(rgb[0]), channel(rgb[1]), channel(rgb[2]))
+        })
+        .unwrap_or(Color::new(1., 1., 1.));
+
+    let mut mesh = TriangleMesh::default();
+    let mut has_colors = false;
+    for (i, facet) in bytes[HEADER_SIZE + 4..]
+        .chunks_exact(FACET_SIZE)
+        .enumerate()
+    {
+        let float = |offset: usize| {
+            f32::from_le_bytes(facet[offset..offset + 4].try_into().unwrap()) as Float
+        };
+        // The facet normal takes the first 12 bytes.
+        for vertex in 1..4 {
+            let offset = 12 * vertex;
+            mesh.positions.push(Point3::new(
+                float(offset),
+                float(offset + 4),
+                float(offset + 8),
+            ));
+        }
+        mesh.triangles.push([3 * i, 3 * i + 1, 3 * i + 2]);
+
+        let attribute = u16::from_le_bytes([facet[48], facet[49]]);
+        let own_color = (attribute & 0x8000 != 0) != materialise.is_some();
+        let color = if own_color {
+            has_colors = true;
+            let channel =
+                |shift: u16| gamma_to_linear(Float::from((attribute >> shift) & 31) / 31.);
+            if materialise.is_some() {
+                Color::new(channel(0), channel(5), channel(10))
+            } else {
+                Color::new(channel(10), channel(5), channel(0))
+            }
+        } else {
+            default_color
+        };
+        mesh.colors.extend([color; 3]);
+    }
+
+    if !has_colors {
+        mesh.colors.clear();
+    }
+    mesh
+}
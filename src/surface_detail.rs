@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::{
+    cross, dot, luminance, unit_vector, Color, Float, HitRecord, Material, Onb, Point3, Ray,
+    RenderError, Texture, Vec3,
+};
+
+// Step in surface coordinates over which heights are differentiated.
+const BUMP_STEP: Float = 1e-4;
+
+// Detail a material adds by bending the normal rather than through geometry.
+pub enum SurfaceDetail {
+    // Normals stored as colors in the frame of the surface: red along u, green along v and blue
+    // out of the surface. `strength` scales how far they lean from the geometric normal.
+    NormalMap {
+        map: Arc<dyn Texture>,
+        strength: Float,
+    },
+    // Heights along the normal, the luminance of the texture times `strength` in world units.
+    Bump {
+        height: Arc<dyn Texture>,
+        strength: Float,
+    },
+}
+
+impl SurfaceDetail {
+    fn texture(&self) -> &Arc<dyn Texture> {
+        match self {
+            SurfaceDetail::NormalMap { map, .. } => map,
+            SurfaceDetail::Bump { height, .. } => height,
+        }
+    }
+
+    fn strength(&self) -> Float {
+        match *self {
+            SurfaceDetail::NormalMap { strength, .. } | SurfaceDetail::Bump { strength, .. } => {
+                strength
+            }
+        }
+    }
+}
+
+// A material whose normal is perturbed by `detail` before it scatters.
+pub struct DetailedMaterial {
+    base: Arc<dyn Material>,
+    detail: SurfaceDetail,
+}
+
+impl DetailedMaterial {
+    pub fn new(base: Arc<dyn Material>, detail: SurfaceDetail) -> Self {
+        Self { base, detail }
+    }
+}
+
+impl Material for DetailedMaterial {
+    fn scatter(
+        &self,
+        r_in: &mut Ray,
+        record: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        self.base.scatter(r_in, record, attenuation, scattered)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, record: &HitRecord, scattered: &Ray) -> Float {
+        self.base.scattering_pdf(r_in, record, scattered)
+    }
+
+    // Tilted normals that would face away from the surface are dropped.
+    fn shading_normal(&self, record: &HitRecord) -> Vec3 {
+        let normal = self.base.shading_normal(record);
+        let (dpdu, dpdv) = tangents(record, normal);
+        let perturbed = match &self.detail {
+            SurfaceDetail::NormalMap { map, strength } => {
+                let texel = 2. * map.value(record.u, record.v, &record.p) - Color::new(1., 1., 1.);
+                let tangent = unit_vector(dpdu - dot(dpdu, normal) * normal);
+                let mut bitangent = cross(normal, tangent);
+                if dot(bitangent, dpdv) < 0. {
+                    bitangent = -bitangent;
+                }
+                *strength * (texel.x() * tangent + texel.y() * bitangent) + texel.z() * normal
+            }
+            // The surface displaced by the heights has these derivatives, neglecting the change of
+            // the normal itself.
+            SurfaceDetail::Bump { height, strength } => {
+                let height_at = |du: Float, dv: Float| {
+                    let p: Point3 = record.p + du * dpdu + dv * dpdv;
+                    *strength * luminance(height.value(record.u + du, record.v + dv, &p))
+                };
+                let center = height_at(0., 0.);
+                let dhdu = (height_at(BUMP_STEP, 0.) - center) / BUMP_STEP;
+                let dhdv = (height_at(0., BUMP_STEP) - center) / BUMP_STEP;
+                let perturbed = cross(dpdu + dhdu * normal, dpdv + dhdv * normal);
+                if dot(cross(dpdu, dpdv), normal) < 0. {
+                    -perturbed
+                } else {
+                    perturbed
+                }
+            }
+        };
+
+        if perturbed.length_squared() > 0. && dot(perturbed, normal) > 0. {
+            unit_vector(perturbed)
+        } else {
+            normal
+        }
+    }
+
+    fn emitted(&self, u: Float, v: Float, p: &Point3) -> Color {
+        self.base.emitted(u, v, p)
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        if !self.detail.strength().is_finite() {
+            return Err(RenderError::NonFinite("surface detail strength"));
+        }
+        self.detail.texture().validate()?;
+        self.base.validate()
+    }
+}
+
+// The derivatives of the hit point. Surfaces that do not provide them, or where they are parallel,
+// get an arbitrary frame around the normal.
+fn tangents(record: &HitRecord, normal: Vec3) -> (Vec3, Vec3) {
+    if cross(record.dpdu, record.dpdv).length_squared() > 0.
+        && cross(record.dpdu, normal).length_squared() > 0.
+    {
+        return (record.dpdu, record.dpdv);
+    }
+    let frame = Onb::new(normal);
+    (
+        frame.to_world(Vec3::new(1., 0., 0.)),
+        frame.to_world(Vec3::new(0., 1., 0.)),
+    )
+}
@@ -0,0 +1,70 @@
+use std::{
+    env,
+    io::{self, Write},
+};
+
+use crate::{to_rgb8, Framebuffer};
+
+const DEFAULT_COLUMNS: u32 = 80;
+
+// Draws a framebuffer with 24-bit ANSI colors, two pixels per character: the upper half block
+// takes the top pixel as foreground and the bottom one as background. Every draw after the first
+// moves the cursor back up and overwrites the previous picture.
+pub struct TerminalPreview {
+    columns: u32,
+    lines_drawn: usize,
+}
+
+impl TerminalPreview {
+    pub fn new(columns: u32) -> Self {
+        Self {
+            columns: columns.max(1),
+            lines_drawn: 0,
+        }
+    }
+
+    // Sized to the terminal width from `COLUMNS`, falling back to 80 columns.
+    pub fn for_terminal() -> Self {
+        let columns = env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(DEFAULT_COLUMNS);
+        Self::new(columns)
+    }
+
+    pub fn draw(
+        &mut self,
+        out: &mut impl Write,
+        image: &Framebuffer,
+        caption: &str,
+    ) -> io::Result<()> {
+        let width = self.columns.min(image.width());
+        let height = (u64::from(image.height()) * u64::from(width) / u64::from(image.width()))
+            .max(2) as u32
+            & !1;
+        let image = image.downsampled(width, height);
+
+        // Stderr is unbuffered, so the whole frame is assembled first and written at once.
+        let mut frame = Vec::new();
+        if self.lines_drawn > 0 {
+            write!(frame, "\x1b[{}A\r", self.lines_drawn)?;
+        }
+
+        for y in (0..height).step_by(2) {
+            for x in 0..width {
+                let [tr, tg, tb] = to_rgb8(image.pixel(x, y));
+                let [br, bg, bb] = to_rgb8(image.pixel(x, y + 1));
+                write!(
+                    frame,
+                    "\x1b[38;2;{tr};{tg};{tb}m\x1b[48;2;{br};{bg};{bb}m\u{2580}"
+                )?;
+            }
+            writeln!(frame, "\x1b[0m")?;
+        }
+        writeln!(frame, "\x1b[2K{caption}")?;
+
+        self.lines_drawn = height as usize / 2 + 1;
+        out.write_all(&frame)?;
+        out.flush()
+    }
+}
@@ -0,0 +1,203 @@
+use std::{io, sync::Arc};
+
+use crate::{gamma_to_linear, validate_albedo, Color, Float, Perlin, Point3, RenderError};
+
+pub trait Texture: Send + Sync {
+    fn value(&self, u: Float, v: Float, p: &Point3) -> Color;
+
+    fn validate(&self) -> Result<(), RenderError> {
+        Ok(())
+    }
+}
+
+pub struct SolidColor {
+    albedo: Color,
+}
+
+impl SolidColor {
+    pub fn new(albedo: Color) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: Float, _v: Float, _p: &Point3) -> Color {
+        self.albedo
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        validate_albedo(self.albedo)
+    }
+}
+
+// Alternates between two textures on a 3D grid of cubes `scale` wide.
+pub struct CheckerTexture {
+    inv_scale: Float,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: Float, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            inv_scale: 1. / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: Float, even: Color, odd: Color) -> Self {
+        Self::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: Float, v: Float, p: &Point3) -> Color {
+        let cell = |x: Float| (self.inv_scale * x).floor() as i64;
+        if (cell(p.x()) + cell(p.y()) + cell(p.z())) % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        if !self.inv_scale.is_finite() {
+            return Err(RenderError::NonFinite("checker scale"));
+        }
+        self.even.validate()?;
+        self.odd.validate()
+    }
+}
+
+// Marble-like veins: a sine along z whose phase is disturbed by Perlin turbulence.
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: Float,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: Float) -> Self {
+        Self {
+            noise: Perlin::new(),
+            scale,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: Float, _v: Float, p: &Point3) -> Color {
+        let phase = self.scale * p.z() + 10. * self.noise.turbulence(p, 7);
+        Color::new(0.5, 0.5, 0.5) * (1. + phase.sin())
+    }
+}
+
+// The colors of the three corners of a mesh triangle, blended by the barycentric coordinates the
+// triangle reports as (u, v).
+pub struct VertexColors {
+    colors: [Color; 3],
+}
+
+impl VertexColors {
+    pub fn new(colors: [Color; 3]) -> Self {
+        Self { colors }
+    }
+}
+
+impl Texture for VertexColors {
+    fn value(&self, u: Float, v: Float, _p: &Point3) -> Color {
+        let [a, b, c] = self.colors;
+        (1. - u - v) * a + u * b + v * c
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.colors
+            .iter()
+            .try_for_each(|&color| validate_albedo(color))
+    }
+}
+
+// A picture wrapped over the surface and repeated outside [0, 1], with v = 0 at its bottom row.
+// Neighbouring pixels are blended, so that maps of normals and heights vary smoothly.
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl ImageTexture {
+    // Rows from top to bottom.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // A PNG or JPEG picture, whose 8-bit colors are gamma encoded.
+    pub fn decode(bytes: &[u8]) -> io::Result<Self> {
+        Self::decode_with(bytes, gamma_to_linear)
+    }
+
+    // A picture holding data rather than colors, such as a normal or height map.
+    pub fn decode_linear(bytes: &[u8]) -> io::Result<Self> {
+        Self::decode_with(bytes, |c| c)
+    }
+
+    fn decode_with(bytes: &[u8], decode: impl Fn(Float) -> Float) -> io::Result<Self> {
+        let image = image::load_from_memory(bytes)
+            .map_err(io::Error::other)?
+            .to_rgb8();
+        let channel = |c: u8| decode(Float::from(c) / 255.);
+        let pixels = image
+            .pixels()
+            .map(|p| Color::new(channel(p[0]), channel(p[1]), channel(p[2])))
+            .collect();
+        Ok(Self::new(
+            image.width() as usize,
+            image.height() as usize,
+            pixels,
+        ))
+    }
+
+    // The same picture with every pixel multiplied by `factor`.
+    pub fn tinted(&self, factor: Color) -> Self {
+        let pixels = self.pixels.iter().map(|&pixel| pixel * factor).collect();
+        Self::new(self.width, self.height, pixels)
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: Float, v: Float, _p: &Point3) -> Color {
+        // Pixel centers sit at half-integer positions; the blend wraps around the edges.
+        let x = u.rem_euclid(1.) * self.width as Float - 0.5;
+        let y = (1. - v.rem_euclid(1.)) * self.height as Float - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let pixel = |i: Float, j: Float| {
+            let i = (i as i64).rem_euclid(self.width as i64) as usize;
+            let j = (j as i64).rem_euclid(self.height as i64) as usize;
+            self.pixels[j * self.width + i]
+        };
+        let top = (1. - fx) * pixel(x0, y0) + fx * pixel(x0 + 1., y0);
+        let bottom = (1. - fx) * pixel(x0, y0 + 1.) + fx * pixel(x0 + 1., y0 + 1.);
+        (1. - fy) * top + fy * bottom
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        if self.width == 0 || self.height == 0 || self.pixels.len() != self.width * self.height {
+            return Err(RenderError::InvalidScene(format!(
+                "the texture image has {} pixels for a size of {}x{}",
+                self.pixels.len(),
+                self.width,
+                self.height
+            )));
+        }
+        Ok(())
+    }
+}
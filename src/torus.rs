@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use crate::{
+    around, closest_boundary, dot, ensure_finite, local_ray, solve_quadratic, spans_between, stats,
+    unit_vector, validate_radius, Aabb, Context, Float, HitRecord, Hittable, Interval, Material,
+    Onb, Point3, Ray, RenderError, Span, Vec3, PI,
+};
+
+// A ring of `major_radius` around `axis`, swept by a tube of `minor_radius`. Its texture
+// coordinates are the angles around the ring and around the tube.
+pub struct Torus {
+    center: Point3,
+    major_radius: Float,
+    minor_radius: Float,
+    frame: Onb,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: Float,
+        minor_radius: Float,
+        mat: Arc<dyn Material>,
+    ) -> Result<Self, RenderError> {
+        ensure_finite(center, "torus center")?;
+        ensure_finite(axis, "torus axis")?;
+        validate_radius(major_radius, "torus radius")?;
+        validate_radius(minor_radius, "torus tube radius")?;
+        if axis.near_zero() {
+            return Err(RenderError::DegenerateAxis("torus"));
+        }
+
+        let frame = Onb::new(axis);
+        let tube = Vec3::new(minor_radius, minor_radius, minor_radius);
+        let extent = frame.circle_extent(major_radius) + tube;
+        Ok(Self {
+            center,
+            major_radius,
+            minor_radius,
+            frame,
+            mat,
+            bbox: Aabb::from_points(center - extent, center + extent),
+        })
+    }
+
+    fn boundaries(&self, ray: &Ray) -> Vec<HitRecord> {
+        let (o, d) = local_ray(&self.frame, self.center, ray);
+        let speed = d.length();
+        if speed == 0. {
+            return Vec::new();
+        }
+
+        // Solving from the point of the line closest to the center, along a unit direction, keeps
+        // the coefficients small for rays that start far away.
+        let d = d / speed;
+        let shift = -dot(o, d);
+        let o = o + shift * d;
+
+        let (r2, rr2) = (self.minor_radius.powi(2), self.major_radius.powi(2));
+        let k = dot(o, o) + rr2 - r2;
+        let m = dot(o, d);
+        let coefficients = [
+            4. * m,
+            4. * m * m + 2. * k - 4. * rr2 * (d.x() * d.x() + d.y() * d.y()),
+            4. * m * k - 8. * rr2 * (o.x() * d.x() + o.y() * d.y()),
+            k * k - 4. * rr2 * (o.x() * o.x() + o.y() * o.y()),
+        ];
+
+        solve_quartic(coefficients)
+            .into_iter()
+            .map(|s| {
+                let p = o + s * d;
+                let ring = Vec3::new(p.x(), p.y(), 0.);
+                let ring = if ring.near_zero() {
+                    Vec3::default()
+                } else {
+                    self.major_radius * unit_vector(ring)
+                };
+                let normal = unit_vector(p - ring);
+                let rho = (p.x() * p.x() + p.y() * p.y()).sqrt();
+                let tube = (Float::atan2(p.z(), rho - self.major_radius) + PI) / (2. * PI);
+                let t = (s + shift) / speed;
+                HitRecord::on_surface(
+                    ray,
+                    t,
+                    self.frame.to_world(normal),
+                    (around(p), tube),
+                    &self.mat,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, ray_t: Interval, record: &mut HitRecord) -> bool {
+        stats::count(|s| s.intersection_tests += 1);
+        if !self.bbox.hit(ray, ray_t) {
+            return false;
+        }
+        closest_boundary(&spans_between(self.boundaries(ray)), ray, ray_t, record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn spans(&self, ray: &Ray) -> Option<Vec<Span>> {
+        Some(spans_between(self.boundaries(ray)))
+    }
+
+    fn validate(&self) -> Result<(), RenderError> {
+        self.mat.validate().context("torus material")
+    }
+}
+
+// The real roots of x⁴ + a x³ + b x² + c x + d, by Ferrari's method: once the cubic term is
+// removed, the quartic splits into two quadratics through a root of its resolvent cubic.
+fn solve_quartic([a, b, c, d]: [Float; 4]) -> Vec<Float> {
+    let a2 = a * a;
+    let p = b - 3. / 8. * a2;
+    let q = c - a * b / 2. + a2 * a / 8.;
+    let r = d - a * c / 4. + a2 * b / 16. - 3. / 256. * a2 * a2;
+
+    let mut roots = Vec::with_capacity(4);
+    let mut push = |y: Option<(Float, Float)>| {
+        if let Some((y0, y1)) = y {
+            roots.extend([y0, y1]);
+        }
+    };
+    let m = largest_cubic_root(p, p * p / 4. - r, -q * q / 8.);
+    if m <= 0. || q.abs() < 1e-12 {
+        // Biquadratic in y: solve for y² first.
+        if let Some((z0, z1)) = solve_quadratic(1., p, r) {
+            for z in [z0, z1] {
+                if z >= 0. {
+                    push(Some((-z.sqrt(), z.sqrt())));
+                }
+            }
+        }
+    } else {
+        let s = (2. * m).sqrt();
+        push(solve_quadratic(1., -s, p / 2. + m + q / (2. * s)));
+        push(solve_quadratic(1., s, p / 2. + m - q / (2. * s)));
+    }
+
+    let quartic = |x: Float| (((x + a) * x + b) * x + c) * x + d;
+    let slope = |x: Float| ((4. * x + 3. * a) * x + 2. * b) * x + c;
+    for root in &mut roots {
+        *root -= a / 4.;
+        for _ in 0..2 {
+            let slope = slope(*root);
+            if slope != 0. {
+                *root -= quartic(*root) / slope;
+            }
+        }
+    }
+    roots
+}
+
+// The largest real root of x³ + a x² + b x + c.
+fn largest_cubic_root(a: Float, b: Float, c: Float) -> Float {
+    let p = b - a * a / 3.;
+    let q = 2. * a * a * a / 27. - a * b / 3. + c;
+    let discriminant = q * q / 4. + p * p * p / 27.;
+    let x = if discriminant > 0. {
+        let root = discriminant.sqrt();
+        (-q / 2. + root).cbrt() + (-q / 2. - root).cbrt()
+    } else if p < 0. {
+        let radius = (-p / 3.).sqrt();
+        let angle = (-q / (2. * radius.powi(3))).clamp(-1., 1.).acos();
+        2. * radius * (angle / 3.).cos()
+    } else {
+        0.
+    };
+    x - a / 3.
+}
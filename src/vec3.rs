@@ -1,44 +1,162 @@
 use std::{
-    fmt::Display,
+    fmt::{Debug, Display},
     ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub},
     str::FromStr,
 };
 
-use crate::{random_f64, random_f64_range};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{random_f64, random_f64_range, Float, RenderError};
 
 pub type Point3 = Vec3;
 
-#[derive(Default, Debug, Copy, Clone)]
+// Lower bound on the squared length of a candidate in `random_unit_vector`, kept well above the
+// underflow threshold of the active precision so normalizing never divides by zero.
+#[cfg(not(feature = "f32"))]
+const MIN_RANDOM_LENGTH_SQUARED: Float = 1e-160;
+#[cfg(feature = "f32")]
+const MIN_RANDOM_LENGTH_SQUARED: Float = 1e-30;
+
+// With the `simd` feature the components live in a 4-wide SIMD register whose last lane is
+// padding, so every operator below goes through these helpers instead of touching `e` directly.
+#[cfg(not(feature = "simd"))]
+mod storage {
+    use crate::Float;
+
+    pub type Storage = [Float; 3];
+
+    #[inline]
+    pub fn new(e0: Float, e1: Float, e2: Float) -> Storage {
+        [e0, e1, e2]
+    }
+
+    #[inline]
+    pub fn as_array(e: &Storage) -> &[Float] {
+        e
+    }
+
+    #[inline]
+    pub fn as_mut_array(e: &mut Storage) -> &mut [Float] {
+        e
+    }
+
+    #[inline]
+    pub fn add(a: Storage, b: Storage) -> Storage {
+        [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+    }
+
+    #[inline]
+    pub fn sub(a: Storage, b: Storage) -> Storage {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    #[inline]
+    pub fn mul(a: Storage, b: Storage) -> Storage {
+        [a[0] * b[0], a[1] * b[1], a[2] * b[2]]
+    }
+
+    #[inline]
+    pub fn scale(a: Storage, s: Float) -> Storage {
+        [a[0] * s, a[1] * s, a[2] * s]
+    }
+
+    #[inline]
+    pub fn neg(a: Storage) -> Storage {
+        [-a[0], -a[1], -a[2]]
+    }
+
+    #[inline]
+    pub fn sum(a: Storage) -> Float {
+        a[0] + a[1] + a[2]
+    }
+}
+
+#[cfg(feature = "simd")]
+mod storage {
+    use crate::{Float, Lane};
+
+    pub type Storage = Lane;
+
+    #[inline]
+    pub fn new(e0: Float, e1: Float, e2: Float) -> Storage {
+        Lane::new([e0, e1, e2, 0.])
+    }
+
+    #[inline]
+    pub fn as_array(e: &Storage) -> &[Float] {
+        &e.as_array()[..3]
+    }
+
+    #[inline]
+    pub fn as_mut_array(e: &mut Storage) -> &mut [Float] {
+        &mut e.as_mut_array()[..3]
+    }
+
+    #[inline]
+    pub fn add(a: Storage, b: Storage) -> Storage {
+        a + b
+    }
+
+    #[inline]
+    pub fn sub(a: Storage, b: Storage) -> Storage {
+        a - b
+    }
+
+    #[inline]
+    pub fn mul(a: Storage, b: Storage) -> Storage {
+        a * b
+    }
+
+    #[inline]
+    pub fn scale(a: Storage, s: Float) -> Storage {
+        a * Lane::splat(s)
+    }
+
+    #[inline]
+    pub fn neg(a: Storage) -> Storage {
+        -a
+    }
+
+    // The padding lane can pick up a NaN (e.g. 0 * inf when dividing by zero), so it is left out.
+    #[inline]
+    pub fn sum(a: Storage) -> Float {
+        let a = a.as_array();
+        a[0] + a[1] + a[2]
+    }
+}
+
+#[derive(Default, Copy, Clone)]
 pub struct Vec3 {
-    e: [f64; 3],
+    e: storage::Storage,
 }
 
 impl Vec3 {
     #[must_use]
-    pub fn new(e0: f64, e1: f64, e2: f64) -> Self {
-        Self { e: [e0, e1, e2] }
+    pub fn new(e0: Float, e1: Float, e2: Float) -> Self {
+        Self {
+            e: storage::new(e0, e1, e2),
+        }
     }
 
-    pub fn x(&self) -> f64 {
-        self.e[0]
+    pub fn x(&self) -> Float {
+        self[0]
     }
 
-    pub fn y(&self) -> f64 {
-        self.e[1]
+    pub fn y(&self) -> Float {
+        self[1]
     }
 
-    pub fn z(&self) -> f64 {
-        self.e[2]
+    pub fn z(&self) -> Float {
+        self[2]
     }
 
-    pub fn length(&self) -> f64 {
+    pub fn length(&self) -> Float {
         self.length_squared().sqrt()
     }
 
     #[inline]
-    pub fn length_squared(&self) -> f64 {
-        let e = self.e;
-        e[0] * e[0] + e[1] * e[1] + e[2] * e[2]
+    pub fn length_squared(&self) -> Float {
+        storage::sum(storage::mul(self.e, self.e))
     }
 
     #[inline]
@@ -47,7 +165,7 @@ impl Vec3 {
     }
 
     #[inline]
-    pub fn random_with_range(min: f64, max: f64) -> Self {
+    pub fn random_with_range(min: Float, max: Float) -> Self {
         Vec3::new(
             random_f64_range(min, max),
             random_f64_range(min, max),
@@ -58,20 +176,22 @@ impl Vec3 {
     pub fn near_zero(&self) -> bool {
         let s = 1e-8;
 
-        self.e.iter().all(|&component| component.abs() < s)
+        storage::as_array(&self.e)
+            .iter()
+            .all(|&component| component.abs() < s)
     }
 }
 
 #[inline]
-pub fn dot(lhs: Vec3, rhs: Vec3) -> f64 {
-    lhs.e[0] * rhs.e[0] + lhs.e[1] * rhs.e[1] + lhs.e[2] * rhs.e[2]
+pub fn dot(lhs: Vec3, rhs: Vec3) -> Float {
+    storage::sum(storage::mul(lhs.e, rhs.e))
 }
 #[inline]
 pub fn cross(lhs: Vec3, rhs: Vec3) -> Vec3 {
     Vec3::new(
-        lhs.e[1] * rhs.e[2] - lhs.e[2] * rhs.e[1],
-        lhs.e[2] * rhs.e[0] - lhs.e[0] * rhs.e[2],
-        lhs.e[0] * rhs.e[1] - lhs.e[1] * rhs.e[0],
+        lhs[1] * rhs[2] - lhs[2] * rhs[1],
+        lhs[2] * rhs[0] - lhs[0] * rhs[2],
+        lhs[0] * rhs[1] - lhs[1] * rhs[0],
     )
 }
 
@@ -96,7 +216,7 @@ pub fn random_unit_vector() -> Vec3 {
     loop {
         let p = Vec3::random_with_range(-1., 1.);
         let lensq = p.length_squared();
-        if (1e-160..=1.).contains(&lensq) {
+        if (MIN_RANDOM_LENGTH_SQUARED..=1.).contains(&lensq) {
             return p / lensq.sqrt();
         }
     }
@@ -117,27 +237,25 @@ pub fn reflect(v: Vec3, n: Vec3) -> Vec3 {
     v - 2. * dot(v, n) * n
 }
 
-pub fn refract(uv: Vec3, n: Vec3, etai_over_etat: f64) -> Vec3 {
-    let cos_theta = f64::min(dot(-uv, n), 1.0);
+pub fn refract(uv: Vec3, n: Vec3, etai_over_etat: Float) -> Vec3 {
+    let cos_theta = Float::min(dot(-uv, n), 1.0);
     let r_out_perp = etai_over_etat * (uv + cos_theta * n);
 
-    let r_out_parallel = f64::abs(1.0 - r_out_perp.length_squared()).sqrt() * n;
+    let r_out_parallel = Float::abs(1.0 - r_out_perp.length_squared()).sqrt() * n;
 
     r_out_perp + r_out_parallel
 }
 
-impl MulAssign<f64> for Vec3 {
+impl MulAssign<Float> for Vec3 {
     #[inline]
-    fn mul_assign(&mut self, rhs: f64) {
-        self.e[0] *= rhs;
-        self.e[1] *= rhs;
-        self.e[2] *= rhs;
+    fn mul_assign(&mut self, rhs: Float) {
+        self.e = storage::scale(self.e, rhs);
     }
 }
 
-impl DivAssign<f64> for Vec3 {
+impl DivAssign<Float> for Vec3 {
     #[inline]
-    fn div_assign(&mut self, rhs: f64) {
+    fn div_assign(&mut self, rhs: Float) {
         *self *= 1.0 / rhs;
     }
 }
@@ -147,37 +265,51 @@ impl Neg for Vec3 {
 
     #[inline]
     fn neg(self) -> Self::Output {
-        Vec3::new(-self.e[0], -self.e[1], -self.e[2])
+        Self {
+            e: storage::neg(self.e),
+        }
     }
 }
 
 impl Index<usize> for Vec3 {
-    type Output = f64;
+    type Output = Float;
 
     #[inline]
     fn index(&self, index: usize) -> &Self::Output {
-        &self.e[index]
+        &storage::as_array(&self.e)[index]
     }
 }
 
 impl IndexMut<usize> for Vec3 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.e[index]
+        &mut storage::as_mut_array(&mut self.e)[index]
     }
 }
 
 impl Display for Vec3 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} {}", self.e[0], self.e[1], self.e[2])
+        write!(f, "{} {} {}", self[0], self[1], self[2])
+    }
+}
+
+impl Debug for Vec3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vec3")
+            .field("e", &storage::as_array(&self.e))
+            .finish()
+    }
+}
+
+impl PartialEq for Vec3 {
+    fn eq(&self, other: &Self) -> bool {
+        storage::as_array(&self.e) == storage::as_array(&other.e)
     }
 }
 
 impl AddAssign for Vec3 {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
-        self.e[0] += rhs.e[0];
-        self.e[1] += rhs.e[1];
-        self.e[2] += rhs.e[2];
+        self.e = storage::add(self.e, rhs.e);
     }
 }
 
@@ -186,11 +318,9 @@ impl Add<Vec3> for Vec3 {
 
     #[inline]
     fn add(self, rhs: Self) -> Self::Output {
-        Vec3::new(
-            self.e[0] + rhs.e[0],
-            self.e[1] + rhs.e[1],
-            self.e[2] + rhs.e[2],
-        )
+        Self {
+            e: storage::add(self.e, rhs.e),
+        }
     }
 }
 
@@ -199,11 +329,9 @@ impl Sub<Vec3> for Vec3 {
 
     #[inline]
     fn sub(self, rhs: Vec3) -> Self::Output {
-        Vec3::new(
-            self.e[0] - rhs.e[0],
-            self.e[1] - rhs.e[1],
-            self.e[2] - rhs.e[2],
-        )
+        Self {
+            e: storage::sub(self.e, rhs.e),
+        }
     }
 }
 
@@ -212,24 +340,24 @@ impl Mul<Vec3> for Vec3 {
 
     #[inline]
     fn mul(self, rhs: Vec3) -> Self::Output {
-        Vec3::new(
-            self.e[0] * rhs.e[0],
-            self.e[1] * rhs.e[1],
-            self.e[2] * rhs.e[2],
-        )
+        Self {
+            e: storage::mul(self.e, rhs.e),
+        }
     }
 }
 
-impl Mul<f64> for Vec3 {
+impl Mul<Float> for Vec3 {
     type Output = Self;
 
     #[inline]
-    fn mul(self, rhs: f64) -> Self::Output {
-        Vec3::new(self.e[0] * rhs, self.e[1] * rhs, self.e[2] * rhs)
+    fn mul(self, rhs: Float) -> Self::Output {
+        Self {
+            e: storage::scale(self.e, rhs),
+        }
     }
 }
 
-impl Mul<Vec3> for f64 {
+impl Mul<Vec3> for Float {
     type Output = Vec3;
 
     #[inline]
@@ -238,28 +366,44 @@ impl Mul<Vec3> for f64 {
     }
 }
 
-impl Div<f64> for Vec3 {
+impl Div<Float> for Vec3 {
     type Output = Self;
 
     #[inline]
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: Float) -> Self::Output {
         (1.0 / rhs) * self
     }
 }
 
 impl FromStr for Vec3 {
-    type Err = String;
+    type Err = RenderError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RenderError::InvalidVector;
+
         let coords: Vec<&str> = s.split(',').collect();
         if coords.len() != 3 {
-            return Err("Un vector tiene que estar en el formato 'x,y,z'".to_string());
+            return Err(invalid());
         }
 
-        let x = coords[0].parse::<f64>().map_err(|e| e.to_string())?;
-        let y = coords[1].parse::<f64>().map_err(|e| e.to_string())?;
-        let z = coords[2].parse::<f64>().map_err(|e| e.to_string())?;
+        let x = coords[0].trim().parse::<Float>().map_err(|_| invalid())?;
+        let y = coords[1].trim().parse::<Float>().map_err(|_| invalid())?;
+        let z = coords[2].trim().parse::<Float>().map_err(|_| invalid())?;
+
+        Ok(Vec3::new(x, y, z))
+    }
+}
+
+// Scene files write vectors and colors as `[x, y, z]`.
+impl Serialize for Vec3 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x(), self.y(), self.z()].serialize(serializer)
+    }
+}
 
+impl<'de> Deserialize<'de> for Vec3 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [x, y, z] = <[Float; 3]>::deserialize(deserializer)?;
         Ok(Vec3::new(x, y, z))
     }
 }
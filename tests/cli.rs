@@ -0,0 +1,148 @@
+// Runs the `convert`, `validate` and `info` commands on scene files and checks what they write.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use raytracing::SceneFile;
+
+const SCENE: &str = r#"{
+  "camera": { "image_width": 64, "aspect_ratio": 2, "samples_per_pixel": 3, "max_depth": 4 },
+  "environment": { "map": "constant:0.5,0.25,1" },
+  "objects": [
+    { "type": "sphere", "center": [0, 0, -1], "radius": 0.5,
+      "material": { "type": "dielectric", "refractive_index": "bk7" } },
+    { "type": "sphere", "center": [1, 0, -1], "radius": 0.5,
+      "material": { "type": "metal", "albedo": [0.8, 0.6, 0.2], "fuzz": 0.3 } },
+    { "type": "box", "min": [-2, -1, -3], "max": [2, -0.5, 1],
+      "material": { "type": "lambertian", "albedo": [0.8, 0.8, 0] } },
+    { "type": "mesh", "path": "models/triangle.stl" }
+  ]
+}"#;
+
+const TRIANGLE_STL: &str = "solid triangle
+facet normal 0 0 1
+  outer loop
+    vertex 0 0 -2
+    vertex 1 0 -2
+    vertex 0 1 -2
+  endloop
+endfacet
+endsolid triangle
+";
+
+// A directory with the scene and the mesh it names, removed when dropped.
+struct SceneDir(PathBuf);
+
+impl SceneDir {
+    fn new(name: &str) -> SceneDir {
+        let dir = env::temp_dir().join(format!("raytracing-cli-{name}-{}", std::process::id()));
+        fs::create_dir_all(dir.join("models")).unwrap();
+        fs::write(dir.join("models").join("triangle.stl"), TRIANGLE_STL).unwrap();
+        fs::write(dir.join("scene.json"), SCENE).unwrap();
+        SceneDir(dir)
+    }
+}
+
+impl Drop for SceneDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn run(args: &[&Path]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_raytracing"))
+        .args(args)
+        .output()
+        .expect("failed to run raytracing")
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn convert_round_trip() {
+    let dir = SceneDir::new("convert");
+    let (json, toml) = (dir.0.join("scene.json"), dir.0.join("other/scene.toml"));
+    fs::create_dir_all(dir.0.join("other")).unwrap();
+    let back = dir.0.join("back.json");
+    stdout(&run(&["convert".as_ref(), &json, &toml]));
+    stdout(&run(&["convert".as_ref(), &toml, &back]));
+
+    // The mesh is still found from the other directory.
+    let validated = stdout(&run(&["validate".as_ref(), &toml, &back]));
+    assert!(
+        validated.contains("scene.toml: ok, 4 objects"),
+        "{validated}"
+    );
+
+    let scene = |path: &Path| {
+        let mut scene = SceneFile::load(path).unwrap();
+        scene.make_paths_absolute().unwrap();
+        scene.to_json().unwrap()
+    };
+    assert_eq!(scene(&json), scene(&toml));
+    assert_eq!(scene(&json), scene(&back));
+}
+
+#[test]
+fn validate_reports_every_file() {
+    let dir = SceneDir::new("validate");
+    let good = dir.0.join("scene.json");
+    let broken = dir.0.join("broken.json");
+    fs::write(
+        &broken,
+        SCENE.replace(
+            "\"radius\": 0.5,\n      \"material\": { \"type\": \"metal\"",
+            "\"radius\": -1,\n      \"material\": { \"type\": \"metal\"",
+        ),
+    )
+    .unwrap();
+    let missing = dir.0.join("missing.json");
+
+    let output = run(&["validate".as_ref(), &good, &broken, &missing]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "{stdout}");
+    assert_eq!(lines[0], format!("{}: ok, 4 objects", good.display()));
+    assert!(
+        lines[1].starts_with(&format!("{}: invalid scene: object #1", broken.display())),
+        "{stdout}"
+    );
+    assert!(
+        lines[2].starts_with(&format!("{}: failed to read", missing.display())),
+        "{stdout}"
+    );
+    assert!(
+        stderr.contains("2 of 3 files failed to validate"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn info_summarizes_the_scene() {
+    let dir = SceneDir::new("info");
+    let printed = stdout(&run(&["info".as_ref(), &dir.0.join("scene.json")]));
+    let lines: Vec<_> = printed.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "objects:     4 (1 box, 1 mesh, 2 sphere)",
+            "materials:   3 (1 dielectric, 1 lambertian, 1 metal)",
+            "bounds:      x -2 to 2, y -1 to 1, z -3 to 1",
+            "camera:      64x32, 3 samples per pixel, max depth 4",
+            "environment: constant:0.5,0.25,1",
+        ],
+        "{printed}"
+    );
+}
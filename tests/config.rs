@@ -0,0 +1,96 @@
+// Checks the precedence of defaults, config files, environment variables and the command line
+// through `--print-config`.
+
+use std::{env, fs, process::Command};
+
+#[test]
+fn later_layers_take_precedence() {
+    let dir = env::temp_dir().join(format!("raytracing-config-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("config.toml");
+    fs::write(
+        &config,
+        "[render]\nimage-width = 64\nsamples-per-pixel = 3\nmax-depth = 4\nlook-from = [13, 2, 3]\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_raytracing"))
+        .args(["render", "--max-depth", "9", "--print-config", "--config"])
+        .arg(&config)
+        .env("RAYTRACING_SAMPLES_PER_PIXEL", "7")
+        .output()
+        .expect("failed to run raytracing");
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+
+    let printed = String::from_utf8(output.stdout).unwrap();
+    let origin = format!("config file {}", config.display());
+    for expected in [
+        "ratio-width = 16 # default".to_string(),
+        format!("image-width = 64 # {origin}"),
+        format!("look-from = \"13,2,3\" # {origin}"),
+        "samples-per-pixel = 7 # environment variable RAYTRACING_SAMPLES_PER_PIXEL".to_string(),
+        "max-depth = 9 # command line".to_string(),
+    ] {
+        assert!(
+            printed.lines().any(|line| line == expected),
+            "missing '{expected}' in:\n{printed}"
+        );
+    }
+}
+
+#[test]
+fn unknown_options_are_rejected() {
+    let dir = env::temp_dir().join(format!("raytracing-bad-config-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("config.toml");
+    fs::write(&config, "[render]\nimage-widht = 64\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_raytracing"))
+        .args(["render", "--print-config", "--config"])
+        .arg(&config)
+        .output()
+        .expect("failed to run raytracing");
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("unknown option 'image-widht' in [render]"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn command_line_files_are_relative_to_the_working_directory() {
+    let dir = env::temp_dir().join(format!("raytracing-cwd-{}", std::process::id()));
+    fs::create_dir_all(dir.join("scenes")).unwrap();
+    fs::write(dir.join("scenes/empty.json"), "{}").unwrap();
+    // A single flat RGBE texel.
+    let mut hdr = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 1\n".to_vec();
+    hdr.extend([128, 128, 128, 129]);
+    fs::write(dir.join("sky.hdr"), hdr).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_raytracing"))
+        .current_dir(&dir)
+        .args([
+            "render",
+            "--scene",
+            "scenes/empty.json",
+            "--environment",
+            "sky.hdr",
+        ])
+        .args(["--image-width", "16", "--samples-per-pixel", "1", "--quiet"])
+        .args(["--output", "out.ppm"])
+        .output()
+        .expect("failed to run raytracing");
+    let rendered = dir.join("out.ppm").exists();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(rendered);
+}
@@ -0,0 +1,62 @@
+// Checks the surfaces CSG operations report along a ray through two overlapping operands.
+
+use std::sync::Arc;
+
+use raytracing::{
+    Color, Cuboid, Difference, HitRecord, Hittable, Intersection, Interval, Lambertian, Point3,
+    Quad, Ray, Sphere, Union, Vec3, INFINITY,
+};
+
+fn operands() -> (Arc<dyn Hittable>, Arc<dyn Hittable>) {
+    let mat = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let cuboid = Cuboid::new(
+        Point3::new(-1., -1., -1.),
+        Point3::new(1., 1., 1.),
+        mat.clone(),
+    );
+    let sphere = Sphere::new(&Point3::new(0., 0., 1.), 1., mat);
+    (Arc::new(cuboid.unwrap()), Arc::new(sphere.unwrap()))
+}
+
+// The first hit of a ray coming down the z axis towards the operands.
+fn first_hit(object: &impl Hittable) -> Option<HitRecord> {
+    let ray = Ray::new(Point3::new(0., 0., 5.), Vec3::new(0., 0., -1.));
+    let mut record = HitRecord::default();
+    object
+        .hit(&ray, Interval::new(0.001, INFINITY), &mut record)
+        .then_some(record)
+}
+
+#[test]
+fn boundaries_follow_the_operation() {
+    let (cuboid, sphere) = operands();
+
+    let union = Union::new(cuboid.clone(), sphere.clone()).unwrap();
+    let hit = first_hit(&union).unwrap();
+    assert!((hit.t - 3.).abs() < 1e-9);
+
+    let intersection = Intersection::new(cuboid.clone(), sphere.clone()).unwrap();
+    let hit = first_hit(&intersection).unwrap();
+    assert!((hit.t - 4.).abs() < 1e-9);
+    assert_eq!(hit.normal, Vec3::new(0., 0., 1.));
+
+    // The sphere leaves a hole down to z = 0, whose floor faces the ray.
+    let difference = Difference::new(cuboid, sphere).unwrap();
+    let hit = first_hit(&difference).unwrap();
+    assert!((hit.t - 5.).abs() < 1e-9);
+    assert!(hit.front_face);
+    assert_eq!(hit.normal, Vec3::new(0., 0., 1.));
+}
+
+#[test]
+fn open_operands_are_rejected() {
+    let (cuboid, _) = operands();
+    let mat = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let quad = Quad::new(
+        Point3::new(0., 0., 0.),
+        Vec3::new(1., 0., 0.),
+        Vec3::new(0., 1., 0.),
+        mat,
+    );
+    assert!(Union::new(cuboid, Arc::new(quad.unwrap())).is_err());
+}
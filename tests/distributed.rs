@@ -0,0 +1,214 @@
+// Renders a scene file through `raytracing coordinator` with local worker processes, some of them
+// failing, and checks that the result matches a local render of the same seeded scene, also for
+// scenes naming files next to them.
+
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    sync::mpsc,
+    thread,
+};
+
+use raytracing::{BvhNode, CameraBuilder, CameraSettings, SceneFile};
+
+const SCENE: &str = r#"{
+  "camera": {
+    "image_width": 48, "aspect_ratio": 1.5, "samples_per_pixel": 8, "max_depth": 10, "seed": 7,
+    "look_from": [0, 1, 3], "look_at": [0, 0, -1], "vfov": 60
+  },
+  "objects": [
+    { "type": "sphere", "center": [0, -100.5, -1], "radius": 100,
+      "material": { "type": "lambertian", "albedo": [0.8, 0.8, 0] } },
+    { "type": "sphere", "center": [0, 0, -1], "radius": 0.5,
+      "material": { "type": "dielectric", "refractive_index": 1.5 } },
+    { "type": "sphere", "center": [1, 0, -1], "radius": 0.5,
+      "material": { "type": "metal", "albedo": [0.8, 0.6, 0.2], "fuzz": 0.3 } }
+  ]
+}"#;
+
+// A worker process, killed when dropped.
+struct Worker {
+    child: Child,
+    address: String,
+}
+
+impl Worker {
+    fn spawn() -> Worker {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_raytracing"))
+            .args(["worker", "--address", "127.0.0.1:0", "--threads", "1"])
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to start a worker");
+
+        // The first line names the port the worker got; the rest is drained so the worker never
+        // blocks on a full pipe.
+        let (sender, receiver) = mpsc::channel();
+        let mut stderr = BufReader::new(child.stderr.take().unwrap());
+        thread::spawn(move || {
+            let mut line = String::new();
+            stderr.read_line(&mut line).unwrap();
+            let address = line.trim().trim_start_matches("listening on ").to_string();
+            sender.send(address).unwrap();
+            let _ = stderr.read_to_end(&mut Vec::new());
+        });
+
+        let address = receiver.recv().expect("the worker did not start");
+        Worker { child, address }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// Accepts one connection, reads the scene and the first tile and hangs up, like a worker that
+// dies in the middle of a tile.
+fn dying_worker() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        lines.next();
+        lines.next();
+    });
+    address
+}
+
+// Accepts one connection and then neither reads nor answers, like a worker that hangs.
+fn silent_worker() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        let (_stream, _) = listener.accept().unwrap();
+        thread::park();
+    });
+    address
+}
+
+// An address nobody listens on.
+fn missing_worker() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
+
+// A square that the scene next to it places beside the glass sphere.
+const SQUARE_PLY: &str = "ply
+format ascii 1.0
+element vertex 4
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+-1.5 -0.5 -1.5
+-0.5 -0.5 -1.5
+-0.5 0.5 -1.5
+-1.5 0.5 -1.5
+4 0 1 2 3
+";
+
+fn distributed_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("distributed")
+}
+
+fn coordinate(name: &str, text: &str, workers: &[String], options: &[&str]) -> (Output, PathBuf) {
+    let dir = distributed_dir();
+    fs::create_dir_all(&dir).unwrap();
+    let scene = dir.join(format!("{name}.json"));
+    let output = dir.join(format!("{name}.ppm"));
+    fs::write(&scene, text).unwrap();
+    let _ = fs::remove_file(&output);
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_raytracing"));
+    command
+        .arg("coordinator")
+        .arg("--scene")
+        .arg(&scene)
+        .arg("--output")
+        .arg(&output)
+        .args(["--rows-per-tile", "2", "--quiet"])
+        .args(options);
+    for worker in workers {
+        command.args(["--worker", worker]);
+    }
+    (command.output().unwrap(), output)
+}
+
+#[test]
+fn tiles_of_failed_workers_are_reassigned() {
+    let workers = [Worker::spawn(), Worker::spawn()];
+    let mut addresses: Vec<_> = workers.iter().map(|w| w.address.clone()).collect();
+    addresses.push(dying_worker());
+    addresses.push(missing_worker());
+
+    let (output, path) = coordinate("reassigned", SCENE, &addresses, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "the coordinator failed:\n{stderr}");
+    assert_eq!(stderr.matches("failed").count(), 2, "{stderr}");
+
+    assert_renders_like_locally(&SceneFile::from_json(SCENE).unwrap(), &path);
+}
+
+fn assert_renders_like_locally(scene: &SceneFile, path: &Path) {
+    let builder = CameraBuilder::default().quiet(true);
+    let mut cam = scene.camera(builder, &CameraSettings::default()).unwrap();
+    let (expected, _) = cam.render_image(&BvhNode::new(scene.build().unwrap()));
+    let mut expected_ppm = Vec::new();
+    expected.write_ppm(&mut expected_ppm).unwrap();
+
+    assert!(
+        fs::read(path).unwrap() == expected_ppm,
+        "the distributed render differs from a local one"
+    );
+}
+
+// The workers run in another directory than the scene file, as they would on other machines.
+#[test]
+fn workers_find_files_next_to_the_scene() {
+    let dir = distributed_dir();
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("square.ply"), SQUARE_PLY).unwrap();
+    let text = SCENE.replace(
+        "\"objects\": [",
+        "\"objects\": [\n    { \"type\": \"mesh\", \"path\": \"square.ply\" },",
+    );
+
+    let worker = Worker::spawn();
+    let (output, path) = coordinate("mesh", &text, std::slice::from_ref(&worker.address), &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "the coordinator failed:\n{stderr}");
+
+    let scene = SceneFile::load(&dir.join("mesh.json")).unwrap();
+    assert_renders_like_locally(&scene, &path);
+}
+
+#[test]
+fn fails_when_every_worker_fails() {
+    let (output, _) = coordinate(
+        "no_workers",
+        SCENE,
+        &[dying_worker(), missing_worker()],
+        &[],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("every worker failed"), "{stderr}");
+}
+
+#[test]
+fn silent_workers_time_out() {
+    let worker = Worker::spawn();
+    let addresses = [silent_worker(), worker.address.clone()];
+    let (output, _) = coordinate("silent", SCENE, &addresses, &["--worker-timeout", "1"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "the coordinator failed:\n{stderr}");
+    assert!(stderr.contains("no answer within 1s"), "{stderr}");
+}
@@ -0,0 +1,63 @@
+// Importance samples an environment map of two texels and checks that the directions follow the
+// brightness of the map and that `pdf` agrees with the densities `sample` returns.
+
+use raytracing::{seed_rng, Color, Environment, Float, HdrImage, ImageEnvironment, Vec3, PI};
+
+const TOLERANCE: Float = if cfg!(feature = "f32") { 1e-3 } else { 1e-9 };
+
+// The left half of the sky is three times as bright as the right half.
+fn two_texels() -> ImageEnvironment {
+    let image = HdrImage {
+        width: 2,
+        height: 1,
+        pixels: vec![Color::new(3., 3., 3.), Color::new(1., 1., 1.)],
+    };
+    ImageEnvironment::new(image, 0., 1.)
+}
+
+#[test]
+fn samples_follow_the_brightness() {
+    let environment = two_texels();
+    seed_rng(1);
+
+    let samples = 4000;
+    let mut bright = 0;
+    for _ in 0..samples {
+        let (direction, pdf) = environment.sample().expect("no direction was sampled");
+        assert!(pdf > 0., "{pdf}");
+        let expected = environment.pdf(&direction);
+        assert!(
+            (expected - pdf).abs() <= TOLERANCE * pdf,
+            "pdf {expected} instead of {pdf} toward {direction:?}"
+        );
+        if environment.value(&direction).x() > 2. {
+            bright += 1;
+        }
+    }
+
+    let fraction = bright as Float / samples as Float;
+    assert!((fraction - 0.75).abs() < 0.03, "{fraction}");
+}
+
+#[test]
+fn pdf_integrates_to_one() {
+    let environment = two_texels();
+
+    // The midpoint rule over the polar and azimuthal angles.
+    let (thetas, phis) = (64, 128);
+    let (d_theta, d_phi) = (PI / thetas as Float, 2. * PI / phis as Float);
+    let mut integral = 0.;
+    for i in 0..thetas {
+        let theta = (i as Float + 0.5) * d_theta;
+        for j in 0..phis {
+            let phi = (j as Float + 0.5) * d_phi;
+            let direction = Vec3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            integral += environment.pdf(&direction) * theta.sin() * d_theta * d_phi;
+        }
+    }
+    assert!((integral - 1.).abs() < 1e-3, "{integral}");
+}
@@ -0,0 +1,227 @@
+// Checks that glTF scenes load the same from a `.glb` file and from a `.gltf` file with an embedded
+// buffer, with their node transforms and camera, and that malformed vertex colors are refused.
+
+use std::{env, fs, path::Path};
+
+use raytracing::{Float, HitRecord, Hittable, Interval, Point3, Ray, SceneFile, Vec3};
+
+// A triangle scaled by 2 in a child of a node moved to z = -5, and a camera at z = 1.
+const JSON: &str = r#"{
+  "asset": { "version": "2.0" },
+  "scene": 0,
+  "scenes": [{ "nodes": [0, 2] }],
+  "nodes": [
+    { "translation": [0, 0, -5], "children": [1] },
+    { "scale": [2, 2, 2], "mesh": 0 },
+    { "translation": [0, 0, 1], "camera": 0 }
+  ],
+  "cameras": [{ "type": "perspective", "perspective": { "yfov": 0.5, "znear": 0.1 } }],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+  "accessors": [
+    { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" },
+    { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+  ],
+  "bufferViews": [
+    { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+    { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+  ],
+  "buffers": [{ "byteLength": 42 BUFFER_URI }]
+}"#;
+
+fn buffer() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for value in [0f32, 0., 0., 1., 0., 0., 0., 1., 0.] {
+        bytes.extend(value.to_le_bytes());
+    }
+    for index in [0u16, 1, 2] {
+        bytes.extend(index.to_le_bytes());
+    }
+    bytes
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, &b)| bits | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            text.push(if i <= chunk.len() {
+                ALPHABET[(bits >> (18 - 6 * i) & 63) as usize] as char
+            } else {
+                '='
+            });
+        }
+    }
+    text
+}
+
+fn glb() -> Vec<u8> {
+    let pad = |mut data: Vec<u8>, with: u8| {
+        data.resize(data.len().next_multiple_of(4), with);
+        data
+    };
+    let json = pad(JSON.replace("BUFFER_URI", "").into_bytes(), b' ');
+    let bin = pad(buffer(), 0);
+
+    let mut bytes = b"glTF".to_vec();
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+    for (kind, chunk) in [(0x4E4F_534Au32, json), (0x004E_4942, bin)] {
+        bytes.extend((chunk.len() as u32).to_le_bytes());
+        bytes.extend(kind.to_le_bytes());
+        bytes.extend(chunk);
+    }
+    bytes
+}
+
+fn check(path: &Path) {
+    let scene = SceneFile::load(path).unwrap();
+    assert_eq!(scene.camera.look_from, Some(Point3::new(0., 0., 1.)));
+    assert_eq!(scene.camera.look_at, Some(Point3::new(0., 0., 0.)));
+    let vfov = scene.camera.vfov.unwrap();
+    assert!((vfov - Float::to_degrees(0.5)).abs() < 1e-4);
+
+    let world = scene.build().unwrap();
+    let cast = |x, y| {
+        let mut record = HitRecord::default();
+        let ray = Ray::new(Point3::new(x, y, 10.), Vec3::new(0., 0., -1.));
+        world
+            .hit(&ray, Interval::new(0.001, 100.), &mut record)
+            .then_some(record)
+    };
+    // The scale doubles the triangle, which then sits at z = -5 facing the camera.
+    let record = cast(1.5, 0.25).expect("missed the scaled triangle");
+    assert!((record.t - 15.).abs() < 1e-6);
+    assert!(record.front_face);
+    assert!(cast(1.5, 1.).is_none());
+}
+
+// The `.gltf` file with its buffer embedded and `colors` as the accessor of its COLOR_0 attribute.
+fn gltf_with_colors(colors: Option<&str>) -> String {
+    let uri = format!(
+        r#", "uri": "data:application/octet-stream;base64,{}""#,
+        base64(&buffer())
+    );
+    let mut json = JSON.replace("BUFFER_URI", &uri);
+    if let Some(colors) = colors {
+        json = json
+            .replace(r#"{ "POSITION": 0 }"#, r#"{ "POSITION": 0, "COLOR_0": 2 }"#)
+            .replace(
+                r#""type": "SCALAR" }"#,
+                &format!(r#""type": "SCALAR" }}, {colors}"#),
+            );
+    }
+    json
+}
+
+#[test]
+fn glb_and_gltf_files_agree() {
+    let dir = env::temp_dir().join(format!("raytracing-gltf-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let glb_path = dir.join("triangle.glb");
+    fs::write(&glb_path, glb()).unwrap();
+    let gltf_path = dir.join("triangle.gltf");
+    fs::write(&gltf_path, gltf_with_colors(None)).unwrap();
+
+    check(&glb_path);
+    check(&gltf_path);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scene_files_find_models_next_to_them() {
+    let dir = env::temp_dir().join(format!("raytracing-gltf-scene-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("triangle.glb"), glb()).unwrap();
+    let scene = dir.join("scene.json");
+    fs::write(
+        &scene,
+        r#"{"objects": [{"type": "gltf", "path": "triangle.glb"}]}"#,
+    )
+    .unwrap();
+
+    let world = SceneFile::load(&scene).and_then(|scene| scene.build());
+    fs::remove_dir_all(&dir).unwrap();
+    let world = world.unwrap();
+    let ray = Ray::new(Point3::new(0.5, 0.25, 10.), Vec3::new(0., 0., -1.));
+    let mut record = HitRecord::default();
+    assert!(world.hit(&ray, Interval::new(0.001, 100.), &mut record));
+}
+
+#[test]
+fn vertex_colors_need_one_color_per_vertex() {
+    let dir = env::temp_dir().join(format!("raytracing-gltf-colors-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let load = |name: &str, colors: &str| {
+        let path = dir.join(name);
+        fs::write(&path, gltf_with_colors(Some(colors))).unwrap();
+        SceneFile::load(&path).and_then(|scene| scene.build())
+    };
+    let accessor = |count: usize, kind: &str| {
+        format!(r#"{{ "componentType": 5126, "count": {count}, "type": "{kind}" }}"#)
+    };
+
+    let rgba = load("rgba.gltf", &accessor(3, "VEC4"));
+    let scalar = load("scalar.gltf", &accessor(3, "SCALAR"));
+    let short = load("short.gltf", &accessor(2, "VEC3"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(rgba.is_ok());
+    for (result, expected) in [
+        (scalar, "COLOR_0 of unsupported type SCALAR"),
+        (short, "COLOR_0 does not have one color per vertex"),
+    ] {
+        let error = result.err().unwrap().to_string();
+        assert!(error.contains(expected), "{error}");
+    }
+}
+
+#[test]
+fn huge_counts_are_refused() {
+    let dir = env::temp_dir().join(format!("raytracing-gltf-huge-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let load = |name: &str, json: String| {
+        let path = dir.join(name);
+        fs::write(&path, json).unwrap();
+        SceneFile::load(&path).and_then(|scene| scene.build())
+    };
+
+    let positions = r#""componentType": 5126, "count": 3, "type": "VEC3""#;
+    let huge = "3000000000000";
+    let results = [
+        load(
+            "positions.gltf",
+            gltf_with_colors(None).replace(
+                positions,
+                &positions.replace("\"count\": 3", &format!("\"count\": {huge}")),
+            ),
+        ),
+        load(
+            "colors.gltf",
+            gltf_with_colors(Some(&format!(
+                r#"{{ "componentType": 5126, "count": {huge}, "type": "VEC3" }}"#
+            ))),
+        ),
+        load(
+            "view.gltf",
+            gltf_with_colors(None).replace(
+                r#""byteOffset": 36, "byteLength": 6"#,
+                r#""byteOffset": 36, "byteLength": 18446744073709551615"#,
+            ),
+        ),
+    ];
+    fs::remove_dir_all(&dir).unwrap();
+
+    for (result, expected) in results.into_iter().zip([
+        "accessor 0 runs past its view",
+        "accessor 2 is too large",
+        "buffer view 1 lies outside of its buffer",
+    ]) {
+        let error = result.err().unwrap().to_string();
+        assert!(error.contains(expected), "{error}");
+    }
+}
@@ -0,0 +1,263 @@
+// Renders small seeded scenes and compares them with the references in `tests/golden`. On a
+// mismatch the render and a difference image are written next to the test binaries under
+// `target/tmp/golden`. After an intended change in the output, re-bless the references with
+// `cargo bless`.
+//
+// The references are rendered in double precision. With `f32` the random generator yields
+// different numbers, so even the layout of the final scene changes and the tests are skipped.
+#![cfg(not(feature = "f32"))]
+
+use std::{fs, path::PathBuf, sync::Arc};
+
+use raytracing::{
+    random_spheres, seed_rng, BvhNode, Camera, CameraBuilder, Color, Dielectric, EnvironmentSpec,
+    Framebuffer, HittableList, Lambertian, Point3, Projection, RefractiveIndex, ScenePreset,
+    Sphere,
+};
+
+const SEED: u64 = 1;
+
+// Images are compared by the root mean square error (in 0..1) of their BLOCK x BLOCK pixel
+// averages. Sampling noise mostly cancels out within a block while missing objects, shifted
+// geometry or wrong colors do not, so platform differences in the math functions don't break the
+// tests. Packet tracing draws the random numbers in another order, which leaves the noise
+// uncorrelated with the references and needs a looser bound.
+const BLOCK: usize = 4;
+const TOLERANCE: f64 = if cfg!(feature = "simd") { 0.05 } else { 0.01 };
+
+struct Golden {
+    name: &'static str,
+    scene: fn() -> (HittableList, Camera),
+}
+
+const GOLDENS: [Golden; 4] = [
+    Golden {
+        name: "final_scene",
+        scene: final_scene,
+    },
+    Golden {
+        name: "three_spheres",
+        scene: three_spheres,
+    },
+    Golden {
+        name: "spectral_prism",
+        scene: spectral_prism,
+    },
+    Golden {
+        name: "fisheye",
+        scene: fisheye,
+    },
+];
+
+fn camera() -> CameraBuilder {
+    CameraBuilder::default()
+        .image_width(64)
+        .samples_per_pixel(16)
+        .max_depth(8)
+        .threads(1)
+        .seed(Some(SEED))
+        .quiet(true)
+}
+
+fn final_scene() -> (HittableList, Camera) {
+    seed_rng(SEED);
+    let camera = camera()
+        .vfov(20.)
+        .lookfrom(Point3::new(13., 2., 3.))
+        .lookat(Point3::new(0., 0., 0.))
+        .defocus_angle(0.6)
+        .build()
+        .unwrap();
+    (random_spheres().unwrap(), camera)
+}
+
+fn three_spheres() -> (HittableList, Camera) {
+    let preset = ScenePreset::ThreeSpheres;
+    let camera = preset.camera().apply(camera()).build().unwrap();
+    (preset.build().unwrap(), camera)
+}
+
+fn spectral_prism() -> (HittableList, Camera) {
+    let mut world = HittableList::default();
+    let ground = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    world.add(Sphere::new(&Point3::new(0., -100.5, -1.), 100., ground).unwrap());
+    let glass = Arc::new(Dielectric::with_dispersion(RefractiveIndex::SF11).unwrap());
+    world.add(Sphere::new(&Point3::new(0., 0., -1.), 0.5, glass).unwrap());
+
+    let environment = "constant:1,1,1"
+        .parse::<EnvironmentSpec>()
+        .unwrap()
+        .load(0., 1.)
+        .unwrap();
+    let camera = camera()
+        .samples_per_pixel(64)
+        .vfov(60.)
+        .lookfrom(Point3::new(0., 0.5, 1.))
+        .lookat(Point3::new(0., 0., -1.))
+        .spectral(true)
+        .environment(environment)
+        .build()
+        .unwrap();
+    (world, camera)
+}
+
+fn fisheye() -> (HittableList, Camera) {
+    let (world, _) = three_spheres();
+    let camera = camera()
+        .aspect_ratio(1.)
+        .vfov(180.)
+        .lookfrom(Point3::new(0., 0.5, 0.5))
+        .lookat(Point3::new(0., 0., -1.))
+        .projection(Projection::FisheyeEquisolid)
+        .build()
+        .unwrap();
+    (world, camera)
+}
+
+fn render(golden: &Golden) -> Image {
+    let (world, mut camera) = (golden.scene)();
+    let (framebuffer, _) = camera.render_image(&BvhNode::new(world));
+    Image::from_framebuffer(&framebuffer)
+}
+
+fn reference_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.ppm"))
+}
+
+fn check(name: &str) {
+    let golden = GOLDENS.iter().find(|g| g.name == name).unwrap();
+    let actual = render(golden);
+
+    let path = reference_path(name);
+    let reference = Image::parse(
+        &fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display())),
+    );
+
+    let error = actual.rmse(&reference);
+    if error > TOLERANCE {
+        let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        fs::create_dir_all(&out).unwrap();
+        let actual_path = out.join(format!("{name}.actual.ppm"));
+        let diff_path = out.join(format!("{name}.diff.ppm"));
+        fs::write(&actual_path, actual.to_ppm()).unwrap();
+        fs::write(&diff_path, actual.diff(&reference).to_ppm()).unwrap();
+
+        panic!(
+            "{name} differs from its reference (rmse {error:.4} > {TOLERANCE}), see {} and {}",
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+#[test]
+fn final_scene_matches_reference() {
+    check("final_scene");
+}
+
+#[test]
+fn three_spheres_matches_reference() {
+    check("three_spheres");
+}
+
+#[test]
+fn spectral_prism_matches_reference() {
+    check("spectral_prism");
+}
+
+#[test]
+fn fisheye_matches_reference() {
+    check("fisheye");
+}
+
+// Rewrites every reference from the current renderer. Run through `cargo bless`.
+#[test]
+#[ignore]
+fn bless() {
+    for golden in &GOLDENS {
+        fs::write(reference_path(golden.name), render(golden).to_ppm()).unwrap();
+    }
+}
+
+// 8-bit RGB image as stored in the references.
+struct Image {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Image {
+    fn from_framebuffer(framebuffer: &Framebuffer) -> Self {
+        let mut ppm = Vec::new();
+        framebuffer.write_ppm(&mut ppm).unwrap();
+        Self::parse(&String::from_utf8(ppm).unwrap())
+    }
+
+    fn parse(ppm: &str) -> Self {
+        let mut tokens = ppm.split_whitespace();
+        assert_eq!(tokens.next(), Some("P3"), "only P3 images are supported");
+        let mut number = || -> usize { tokens.next().unwrap().parse().unwrap() };
+        let (width, height, max) = (number(), number(), number());
+        assert_eq!(max, 255);
+        let data: Vec<u8> = (0..width * height * 3).map(|_| number() as u8).collect();
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    fn to_ppm(&self) -> String {
+        let mut ppm = format!("P3\n {}  {}\n255\n", self.width, self.height);
+        for pixel in self.data.chunks(3) {
+            ppm.push_str(&format!("{}  {}  {}\n", pixel[0], pixel[1], pixel[2]));
+        }
+        ppm
+    }
+
+    fn rmse(&self, other: &Image) -> f64 {
+        if (self.width, self.height) != (other.width, other.height) {
+            return f64::INFINITY;
+        }
+
+        let (a, b) = (self.block_means(), other.block_means());
+        let sum: f64 = a.iter().zip(&b).map(|(a, b)| (a - b).powi(2)).sum();
+        (sum / a.len() as f64).sqrt()
+    }
+
+    // Per-channel averages of every complete BLOCK x BLOCK block, in 0..1.
+    fn block_means(&self) -> Vec<f64> {
+        let mut means = Vec::new();
+        for by in (0..self.height / BLOCK).map(|y| y * BLOCK) {
+            for bx in (0..self.width / BLOCK).map(|x| x * BLOCK) {
+                for channel in 0..3 {
+                    let sum: f64 = (by..by + BLOCK)
+                        .flat_map(|y| (bx..bx + BLOCK).map(move |x| (x, y)))
+                        .map(|(x, y)| f64::from(self.data[(y * self.width + x) * 3 + channel]))
+                        .sum();
+                    means.push(sum / (BLOCK * BLOCK) as f64 / 255.);
+                }
+            }
+        }
+        means
+    }
+
+    // Absolute difference per channel, scaled up so small errors stay visible.
+    fn diff(&self, other: &Image) -> Image {
+        let data = self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(&a, &b)| a.abs_diff(b).saturating_mul(4))
+            .collect();
+        Image {
+            width: self.width,
+            height: self.height,
+            data,
+        }
+    }
+}
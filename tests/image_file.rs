@@ -0,0 +1,87 @@
+// Checks that image headers claiming absurd sizes are refused instead of allocating for them, and
+// that run-length encoded Radiance scanlines decode like flat ones.
+
+use std::io::{self, Cursor};
+
+use raytracing::{Color, HdrImage, ImageFormat};
+
+const HDR_HEADER: &[u8] = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 8\n";
+
+#[test]
+fn huge_headers_are_refused() {
+    for (format, header) in [
+        (ImageFormat::Ppm, "P6 4000000000 4000000000 255\n"),
+        (ImageFormat::Ppm, "P3 100000 100000 255\n"),
+        (ImageFormat::Pfm, "PF\n4000000000 4000000000\n-1.0\n"),
+        (
+            ImageFormat::Hdr,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 4000000000 +X 4000000000\n",
+        ),
+    ] {
+        let error = format
+            .read(&mut Cursor::new(header))
+            .err()
+            .unwrap_or_else(|| panic!("{header:?} was read"));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{header:?}");
+        assert!(error.to_string().contains("too large"), "{error}");
+    }
+}
+
+// Two scanlines of 8 pixels. Each channel is encoded separately as runs (a count above 128 and one
+// value) and literals (a count and that many values).
+fn rle_hdr() -> Vec<u8> {
+    let mut data = HDR_HEADER.to_vec();
+    data.extend([2, 2, 0, 8]);
+    data.extend([136, 128]);
+    data.extend([8, 0, 16, 32, 48, 64, 80, 96, 112]);
+    data.extend([132, 64, 132, 32]);
+    data.extend([136, 129]);
+
+    data.extend([2, 2, 0, 8]);
+    data.extend([3, 255, 0, 7, 133, 9]);
+    data.extend([136, 1]);
+    data.extend([1, 200, 135, 100]);
+    data.extend([2, 130, 130, 134, 0]);
+    data
+}
+
+#[test]
+fn run_length_encoded_radiance_pictures() {
+    let mut flat = HDR_HEADER.to_vec();
+    for x in 0..8u8 {
+        flat.extend([128, 16 * x, if x < 4 { 64 } else { 32 }, 129]);
+    }
+    let blues = [200, 100, 100, 100, 100, 100, 100, 100];
+    for (x, red) in [255, 0, 7, 9, 9, 9, 9, 9].into_iter().enumerate() {
+        let exponent = if x < 2 { 130 } else { 0 };
+        flat.extend([red, 1, blues[x], exponent]);
+    }
+
+    let rle = HdrImage::read_radiance(&mut Cursor::new(rle_hdr())).unwrap();
+    let flat = HdrImage::read_radiance(&mut Cursor::new(flat)).unwrap();
+    assert_eq!((rle.width, rle.height), (8, 2));
+    assert_eq!(rle.pixels, flat.pixels);
+    // Bytes are scaled by 2^(exponent - 136), from the middle of their step.
+    assert_eq!(rle.pixel(0, 0), Color::new(128.5, 0.5, 64.5) / 128.);
+    assert_eq!(rle.pixel(7, 1), Color::default());
+}
+
+#[test]
+fn broken_runs_are_refused() {
+    // A run past the end of the scanline, and a scanline claiming another width.
+    let mut long_run = rle_hdr();
+    long_run[HDR_HEADER.len() + 4] = 137;
+    let mut narrow = rle_hdr();
+    narrow[HDR_HEADER.len() + 3] = 9;
+
+    for (data, message) in [
+        (long_run, "bad scanline run"),
+        (narrow, "scanline width mismatch"),
+    ] {
+        let error = HdrImage::read_radiance(&mut Cursor::new(data))
+            .err()
+            .expect("the picture was read");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains(message), "{error}");
+    }
+}
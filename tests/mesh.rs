@@ -0,0 +1,168 @@
+// Checks that the ASCII and binary variants of PLY and STL files load as the same mesh, and that
+// scene files find their meshes next to them, also once they are saved somewhere else.
+
+use std::{env, fs, io::Cursor};
+
+use raytracing::{
+    Color, HitRecord, Hittable, Interval, MeshFormat, Point3, Ray, SceneFile, TriangleMesh, Vec3,
+};
+
+const ASCII_PLY: &str = "ply
+format ascii 1.0
+comment a unit square split by a fan
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0
+1 0 0 0 255 0
+1 1 0 0 0 255
+0 1 0 255 255 255
+4 0 1 2 3
+";
+
+const ASCII_STL: &str = "solid triangle
+facet normal 0 0 1
+  outer loop
+    vertex 0 0 0
+    vertex 1 0 0
+    vertex 1 1 0
+  endloop
+endfacet
+endsolid triangle
+";
+
+fn read(format: MeshFormat, bytes: &[u8]) -> TriangleMesh {
+    format.read(&mut Cursor::new(bytes)).unwrap()
+}
+
+fn binary_ply() -> Vec<u8> {
+    let header = ASCII_PLY
+        .split("end_header\n")
+        .next()
+        .unwrap()
+        .replace("format ascii", "format binary_little_endian");
+    let mut bytes = format!("{header}end_header\n").into_bytes();
+    let vertices = [
+        ([0., 0., 0.], [255, 0, 0]),
+        ([1., 0., 0.], [0, 255, 0]),
+        ([1., 1., 0.], [0, 0, 255]),
+        ([0., 1., 0.], [255, 255, 255]),
+    ];
+    for (position, color) in vertices {
+        for coordinate in position {
+            bytes.extend((coordinate as f32).to_le_bytes());
+        }
+        bytes.extend(color);
+    }
+    bytes.push(4);
+    for index in [0i32, 1, 2, 3] {
+        bytes.extend(index.to_le_bytes());
+    }
+    bytes
+}
+
+fn binary_stl() -> Vec<u8> {
+    let mut bytes = vec![0; 80];
+    bytes.extend(1u32.to_le_bytes());
+    for value in [0., 0., 1., 0., 0., 0., 1., 0., 0., 1., 1., 0.] {
+        bytes.extend((value as f32).to_le_bytes());
+    }
+    bytes.extend([0, 0]);
+    bytes
+}
+
+#[test]
+fn ply_variants_agree() {
+    let ascii = read(MeshFormat::Ply, ASCII_PLY.as_bytes());
+    let binary = read(MeshFormat::Ply, &binary_ply());
+    for mesh in [&ascii, &binary] {
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.triangles, [[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(mesh.colors[0], Color::new(1., 0., 0.));
+        assert_eq!(mesh.colors[3], Color::new(1., 1., 1.));
+        assert!(mesh.normals.is_empty());
+    }
+    assert_eq!(ascii.positions, binary.positions);
+
+    let mut mesh = ascii;
+    mesh.compute_normals();
+    assert!(mesh.normals.iter().all(|&n| n == Vec3::new(0., 0., 1.)));
+}
+
+#[test]
+fn stl_variants_agree() {
+    let ascii = read(MeshFormat::Stl, ASCII_STL.as_bytes());
+    let binary = read(MeshFormat::Stl, &binary_stl());
+    for mesh in [ascii, binary] {
+        assert_eq!(
+            mesh.positions,
+            [
+                Point3::new(0., 0., 0.),
+                Point3::new(1., 0., 0.),
+                Point3::new(1., 1., 0.)
+            ]
+        );
+        assert_eq!(mesh.triangles, [[0, 1, 2]]);
+        assert!(mesh.colors.is_empty());
+    }
+}
+
+#[test]
+fn scene_files_find_meshes_next_to_them() {
+    let dir = env::temp_dir().join(format!("raytracing-mesh-{}", std::process::id()));
+    fs::create_dir_all(dir.join("models")).unwrap();
+    fs::write(dir.join("models").join("square.ply"), ASCII_PLY).unwrap();
+    let scene = dir.join("scene.json");
+    fs::write(
+        &scene,
+        r#"{"objects": [{"type": "mesh", "path": "models/square.ply"}]}"#,
+    )
+    .unwrap();
+
+    let world = SceneFile::load(&scene).and_then(|scene| scene.build());
+    fs::remove_dir_all(&dir).unwrap();
+    let world = world.unwrap();
+    let ray = Ray::new(Point3::new(0.5, 0.5, 1.), Vec3::new(0., 0., -1.));
+    let mut record = HitRecord::default();
+    assert!(world.hit(&ray, Interval::new(0.001, 10.), &mut record));
+}
+
+#[test]
+fn saved_scenes_keep_finding_their_meshes() {
+    let dir = env::temp_dir().join(format!("raytracing-saved-mesh-{}", std::process::id()));
+    fs::create_dir_all(dir.join("models")).unwrap();
+    fs::create_dir_all(dir.join("elsewhere")).unwrap();
+    fs::write(dir.join("models").join("square.ply"), ASCII_PLY).unwrap();
+    let scene = dir.join("scene.json");
+    fs::write(
+        &scene,
+        r#"{"objects": [{"type": "mesh", "path": "models/square.ply"}]}"#,
+    )
+    .unwrap();
+
+    let saved = |name: &str| {
+        let path = dir.join(name);
+        SceneFile::load(&scene)?.save(&path)?;
+        let text = fs::read_to_string(&path).unwrap();
+        SceneFile::load(&path)?.build().map(|world| (text, world))
+    };
+    let beside = saved("copy.toml");
+    let elsewhere = saved("elsewhere/copy.toml");
+    fs::remove_dir_all(&dir).unwrap();
+
+    // Next to the original the path stays relative.
+    let (text, _) = beside.unwrap();
+    assert!(text.contains("path = \"models/square.ply\""), "{text}");
+    let (text, world) = elsewhere.unwrap();
+    assert!(!text.contains("path = \"models/square.ply\""), "{text}");
+    let ray = Ray::new(Point3::new(0.5, 0.5, 1.), Vec3::new(0., 0., -1.));
+    let mut record = HitRecord::default();
+    assert!(world.hit(&ray, Interval::new(0.001, 10.), &mut record));
+}
@@ -0,0 +1,59 @@
+// Checks where rays cross the analytic primitives when they are not aligned with the world axes.
+
+use std::sync::Arc;
+
+use raytracing::{
+    unit_vector, Color, Cone, Cylinder, Float, Hittable, Lambertian, Point3, Ray, Torus, Vec3,
+};
+
+// The entry and exit distances of every span along the ray.
+fn spans(object: &impl Hittable, ray: &Ray) -> Vec<(Float, Float)> {
+    object
+        .spans(ray)
+        .unwrap()
+        .iter()
+        .map(|span| (span.entry.t, span.exit.t))
+        .collect()
+}
+
+// The torus solves a quartic, which keeps far fewer digits under f32.
+const TOLERANCE: Float = if cfg!(feature = "f32") { 1e-4 } else { 1e-9 };
+
+fn assert_spans(actual: Vec<(Float, Float)>, expected: &[(Float, Float)]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?}");
+    for ((t0, t1), (e0, e1)) in actual.iter().zip(expected) {
+        assert!(
+            (t0 - e0).abs() < TOLERANCE && (t1 - e1).abs() < TOLERANCE,
+            "{actual:?}"
+        );
+    }
+}
+
+#[test]
+fn tilted_primitives_are_crossed_exactly() {
+    let mat = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let axis = unit_vector(Vec3::new(1., 1., 0.));
+    let across = Vec3::new(0., 0., 1.);
+
+    // Straight through the middle of the side and along the axis through both caps.
+    let cylinder = Cylinder::new(Point3::default(), 2. * axis, 1., mat.clone()).unwrap();
+    assert_spans(
+        spans(&cylinder, &Ray::new(axis - 5. * across, across)),
+        &[(4., 6.)],
+    );
+    assert_spans(spans(&cylinder, &Ray::new(-5. * axis, axis)), &[(5., 7.)]);
+
+    // Halfway up, the cone is half as wide as its base.
+    let cone = Cone::new(Point3::default(), 2. * axis, 1., mat.clone()).unwrap();
+    assert_spans(
+        spans(&cone, &Ray::new(axis - 5. * across, across)),
+        &[(4.5, 5.5)],
+    );
+
+    // Across the ring the ray goes through the tube twice.
+    let torus = Torus::new(Point3::default(), across, 1., 0.25, mat).unwrap();
+    assert_spans(
+        spans(&torus, &Ray::new(-5. * axis, axis)),
+        &[(3.75, 4.25), (5.75, 6.25)],
+    );
+}
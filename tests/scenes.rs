@@ -0,0 +1,25 @@
+// Builds every built-in scene with its own view and environment and validates the result.
+
+use clap::ValueEnum;
+use raytracing::{CameraBuilder, Hittable, ScenePreset};
+
+#[test]
+fn every_preset_builds_and_validates() {
+    for preset in ScenePreset::value_variants() {
+        let environment = preset.environment().load(0., 1.).unwrap();
+        let builder = CameraBuilder::default()
+            .quiet(true)
+            .environment(environment);
+        let cam = preset
+            .camera()
+            .apply(builder)
+            .build()
+            .unwrap_or_else(|e| panic!("{preset:?}: {e}"));
+        cam.validate().unwrap_or_else(|e| panic!("{preset:?}: {e}"));
+
+        let world = preset.build().unwrap_or_else(|e| panic!("{preset:?}: {e}"));
+        world
+            .validate()
+            .unwrap_or_else(|e| panic!("{preset:?}: {e}"));
+    }
+}
@@ -0,0 +1,164 @@
+// Sphere traces the signed distance shapes and checks where rays hit them and the normals found
+// from the gradient of the distance.
+
+use std::sync::Arc;
+
+use raytracing::{
+    unit_vector, Color, Float, HitRecord, Hittable, Interval, Lambertian, Point3, Ray, Sdf,
+    SdfCapsule, SdfHittable, SdfRoundedBox, SdfTorus, SmoothUnion, Vec3,
+};
+
+// The surface is found to a fraction of the size of the shape, and the normal from differences of
+// the distance across that fraction, which lose digits under f32.
+const TOLERANCE: Float = if cfg!(feature = "f32") { 1e-2 } else { 1e-4 };
+
+fn hittable(sdf: impl Sdf + 'static) -> SdfHittable {
+    SdfHittable::new(
+        Arc::new(sdf),
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )
+}
+
+fn hit(object: &SdfHittable, origin: Point3, direction: Vec3, t_min: Float) -> Option<HitRecord> {
+    let mut record = HitRecord::default();
+    let ray = Ray::new(origin, direction);
+    object
+        .hit(&ray, Interval::new(t_min, Float::INFINITY), &mut record)
+        .then_some(record)
+}
+
+// The ray from `origin` along `direction` hits the outside at `point` with outward `normal`.
+fn assert_hit(object: &SdfHittable, origin: Point3, direction: Vec3, point: Point3, normal: Vec3) {
+    let record = hit(object, origin, direction, 0.001).expect("the ray missed");
+    assert!(
+        (record.p - point).length() < TOLERANCE,
+        "hit {:?} instead of {point:?}",
+        record.p
+    );
+    assert!(record.front_face);
+    assert!(
+        (record.normal - unit_vector(normal)).length() < TOLERANCE,
+        "normal {:?} instead of {normal:?}",
+        record.normal
+    );
+}
+
+#[test]
+fn torus() {
+    let torus = hittable(SdfTorus::new(Point3::new(0., 0., 0.), 1., 0.25));
+    let outer = Point3::new(0., 0., 1.25);
+    assert_hit(
+        &torus,
+        Point3::new(0., 0., 5.),
+        Vec3::new(0., 0., -1.),
+        outer,
+        Vec3::new(0., 0., 1.),
+    );
+    assert_hit(
+        &torus,
+        Point3::new(1., 5., 0.),
+        Vec3::new(0., -2., 0.),
+        Point3::new(1., 0.25, 0.),
+        Vec3::new(0., 1., 0.),
+    );
+    // Straight down through the hole.
+    assert!(hit(
+        &torus,
+        Point3::new(0., 5., 0.),
+        Vec3::new(0., -1., 0.),
+        0.001
+    )
+    .is_none());
+}
+
+#[test]
+fn rounded_box() {
+    let rounded = hittable(SdfRoundedBox::new(
+        Point3::new(0., 0., 0.),
+        Vec3::new(1., 1., 1.),
+        0.2,
+    ));
+    assert_hit(
+        &rounded,
+        Point3::new(0.3, 0.2, 5.),
+        Vec3::new(0., 0., -1.),
+        Point3::new(0.3, 0.2, 1.),
+        Vec3::new(0., 0., 1.),
+    );
+    // Toward the edge along x = y, which is a quarter circle around (0.8, 0.8).
+    let edge = 0.8 + 0.2 / Float::sqrt(2.);
+    assert_hit(
+        &rounded,
+        Point3::new(5., 5., 0.),
+        Vec3::new(-1., -1., 0.),
+        Point3::new(edge, edge, 0.),
+        Vec3::new(1., 1., 0.),
+    );
+}
+
+#[test]
+fn capsule() {
+    let capsule = hittable(SdfCapsule::new(
+        Point3::new(0., -1., 0.),
+        Point3::new(0., 1., 0.),
+        0.5,
+    ));
+    assert_hit(
+        &capsule,
+        Point3::new(5., 0.3, 0.),
+        Vec3::new(-1., 0., 0.),
+        Point3::new(0.5, 0.3, 0.),
+        Vec3::new(1., 0., 0.),
+    );
+    assert_hit(
+        &capsule,
+        Point3::new(0., 5., 0.),
+        Vec3::new(0., -1., 0.),
+        Point3::new(0., 1.5, 0.),
+        Vec3::new(0., 1., 0.),
+    );
+    // The cap is a half sphere around the end.
+    let cap = Vec3::new(1., 1., 0.);
+    assert_hit(
+        &capsule,
+        Point3::new(0., 1., 0.) + 5. * cap,
+        -cap,
+        Point3::new(0., 1., 0.) + 0.5 * unit_vector(cap),
+        cap,
+    );
+}
+
+#[test]
+fn smooth_union_fills_the_gap() {
+    // Two balls 0.2 apart, so a ray between them misses their plain union.
+    let ball = |x: Float| -> Arc<dyn Sdf> {
+        let center = Point3::new(x, 0., 0.);
+        Arc::new(SdfCapsule::new(center, center, 0.5))
+    };
+    let down = Vec3::new(0., -1., 0.);
+    let origin = Point3::new(0., 5., 0.);
+
+    let plain = hittable(SmoothUnion::new(ball(-0.6), ball(0.6), 0.));
+    assert!(hit(&plain, origin, down, 0.001).is_none());
+
+    let smooth = hittable(SmoothUnion::new(ball(-0.6), ball(0.6), 0.5));
+    let record = hit(&smooth, origin, down, 0.001).expect("the fillet was missed");
+    assert!(record.p.y() > 0. && record.p.y() < 0.5, "{:?}", record.p);
+    // The fillet is symmetric, so its normal at the middle points straight up.
+    assert!((record.normal - Vec3::new(0., 1., 0.)).length() < TOLERANCE);
+}
+
+#[test]
+fn rays_starting_on_the_surface() {
+    let torus = hittable(SdfTorus::new(Point3::new(0., 0., 0.), 1., 0.25));
+    let on_surface = Point3::new(0., 0., 1.25);
+
+    // Leaving the surface there is nothing else to hit.
+    assert!(hit(&torus, on_surface, Vec3::new(0., 0., 1.), 0.).is_none());
+
+    // Entering it, the ray crosses the tube and leaves on its far side, facing the hole.
+    let record = hit(&torus, on_surface, Vec3::new(0., 0., -1.), 0.).expect("the ray missed");
+    assert!((record.t - 0.5).abs() < TOLERANCE, "{}", record.t);
+    assert!(!record.front_face);
+    assert!((record.normal - Vec3::new(0., 0., 1.)).length() < TOLERANCE);
+}
@@ -0,0 +1,212 @@
+// Drives the render server over HTTP: submits a job, polls it, fetches the image, cancels a long
+// job, checks that oversized jobs and requests and files outside of the server are refused, and
+// that old finished jobs are forgotten.
+
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use raytracing::{serve, ServeArgs};
+use serde_json::{json, Value};
+
+fn scene(width: u32, samples_per_pixel: u32) -> Value {
+    json!({
+        "scene": {
+            "camera": {"look_from": [0, 1, 3], "look_at": [0, 0, -1], "vfov": 60, "seed": 1},
+            "objects": [
+                {"type": "sphere", "center": [0, 0, -1], "radius": 0.5,
+                 "material": {"type": "lambertian", "albedo": [0.8, 0.3, 0.3]}}
+            ]
+        },
+        "settings": {
+            "image_width": width, "aspect_ratio": 2, "samples_per_pixel": samples_per_pixel,
+            "max_depth": 5
+        }
+    })
+}
+
+fn start(keep_finished: usize) -> String {
+    // Port 0 cannot be passed on, since the server does not report the port it got.
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let args = ServeArgs {
+        address: address.clone(),
+        concurrency: 1,
+        threads: 1,
+        keep_finished,
+    };
+    thread::spawn(move || serve(&args).unwrap());
+
+    let start = Instant::now();
+    while TcpStream::connect(&address).is_err() {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "the server did not start"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+    address
+}
+
+// Sends one request and returns the status and the body of the response.
+fn request(address: &str, method: &str, path: &str, body: &str) -> (u16, Vec<u8>) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\
+         Content-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+
+    let end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .expect("no end of the headers");
+    let head = String::from_utf8_lossy(&response[..end]);
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, response[end + 4..].to_vec())
+}
+
+fn json(address: &str, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let (status, body) = request(address, method, path, body);
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn wait_for(address: &str, id: u64, state: &str) -> Value {
+    let start = Instant::now();
+    loop {
+        let (status, job) = json(address, "GET", &format!("/jobs/{id}"), "");
+        assert_eq!(status, 200);
+        if job["state"] == state {
+            return job;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "the job never got {state}: {job}"
+        );
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn jobs_render_and_cancel() {
+    let address = start(100);
+
+    let (status, job) = json(&address, "POST", "/jobs", &scene(16, 2).to_string());
+    assert_eq!(status, 201, "{job}");
+    let id = job["id"].as_u64().unwrap();
+    let done = wait_for(&address, id, "done");
+    assert_eq!(done["rows_done"], 8);
+
+    let (status, image) = request(&address, "GET", &format!("/jobs/{id}/image"), "");
+    assert_eq!(status, 200);
+    assert!(image.starts_with(b"P3\n 16  8\n255\n"));
+
+    let (status, job) = json(&address, "POST", "/jobs", &scene(1000, 500).to_string());
+    assert_eq!(status, 201, "{job}");
+    let id = job["id"].as_u64().unwrap();
+    let (status, _) = json(&address, "DELETE", &format!("/jobs/{id}"), "");
+    assert_eq!(status, 200);
+    wait_for(&address, id, "cancelled");
+    let (status, _) = request(&address, "GET", &format!("/jobs/{id}/image"), "");
+    assert_eq!(status, 409);
+
+    let (status, _) = request(&address, "DELETE", &format!("/jobs/{id}"), "");
+    assert_eq!(status, 204);
+    let (status, _) = request(&address, "GET", &format!("/jobs/{id}"), "");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn oversized_jobs_are_refused() {
+    let address = start(100);
+
+    for job in [
+        scene(100_000, 1),
+        scene(4_000_000_000, 1),
+        scene(16, 1_000_000),
+        json!({"scene": {"camera": {"max_depth": 100_000}, "objects": []}}),
+    ] {
+        let (status, reply) = json(&address, "POST", "/jobs", &job.to_string());
+        assert_eq!(status, 400, "{job}");
+        assert!(
+            reply["error"].as_str().unwrap().contains("at most"),
+            "{reply}"
+        );
+    }
+    let (_, jobs) = json(&address, "GET", "/jobs", "");
+    assert_eq!(jobs, json!([]));
+}
+
+#[test]
+fn large_requests_are_refused() {
+    let address = start(100);
+    let padding = " ".repeat(2 << 20);
+    let (status, reply) = json(
+        &address,
+        "POST",
+        "/jobs",
+        &format!("{}{padding}", scene(16, 1)),
+    );
+    assert_eq!(status, 413, "{reply}");
+}
+
+#[test]
+fn files_outside_of_the_server_are_refused() {
+    let address = start(100);
+    let detail = |path: &str| {
+        json!({"type": "lambertian", "albedo": [0.5, 0.5, 0.5],
+               "detail": {"type": "normal-map", "path": path}})
+    };
+
+    for scene in [
+        json!({"objects": [{"type": "mesh", "path": "/etc/passwd"}]}),
+        json!({"objects": [{"type": "gltf", "path": "../model.glb"}]}),
+        json!({"objects": [{"type": "sphere", "center": [0, 0, 0], "radius": 1,
+                            "material": detail("maps/../../normals.png")}]}),
+        json!({"environment": {"map": "/srv/sky.hdr"}, "objects": []}),
+    ] {
+        let job = json!({ "scene": scene });
+        let (status, reply) = json(&address, "POST", "/jobs", &job.to_string());
+        assert_eq!(status, 400, "{job}");
+        assert!(
+            reply["error"].as_str().unwrap().contains("without '..'"),
+            "{reply}"
+        );
+    }
+    let (_, jobs) = json(&address, "GET", "/jobs", "");
+    assert_eq!(jobs, json!([]));
+}
+
+#[test]
+fn old_finished_jobs_are_forgotten() {
+    let address = start(2);
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let (status, job) = json(&address, "POST", "/jobs", &scene(4, 1).to_string());
+        assert_eq!(status, 201, "{job}");
+        let id = job["id"].as_u64().unwrap();
+        wait_for(&address, id, "done");
+        ids.push(id);
+    }
+
+    let (status, _) = request(&address, "GET", &format!("/jobs/{}", ids[0]), "");
+    assert_eq!(status, 404);
+    let (_, jobs) = json(&address, "GET", "/jobs", "");
+    let listed: Vec<_> = jobs
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| &job["id"])
+        .collect();
+    assert_eq!(listed, [&json!(ids[1]), &json!(ids[2])]);
+}
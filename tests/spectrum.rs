@@ -0,0 +1,46 @@
+// Upsamples RGB colors to spectra and integrates them back against the CIE observer, and checks
+// the dispersion formula of BK7 against its catalog value.
+
+use raytracing::{
+    cie_xyz, rgb_to_spectrum, spectral_to_rgb, Color, RefractiveIndex, Vec3, LAMBDA_MAX, LAMBDA_MIN,
+};
+
+// The color a surface of reflectance `rgb` shows under the equal-energy illuminant.
+fn round_trip(rgb: Color) -> Color {
+    let mut xyz = Vec3::default();
+    let mut wavelength = LAMBDA_MIN + 0.5;
+    while wavelength < LAMBDA_MAX {
+        xyz += rgb_to_spectrum(rgb, wavelength) * cie_xyz(wavelength);
+        wavelength += 1.;
+    }
+    spectral_to_rgb(xyz)
+}
+
+#[test]
+fn colors_survive_the_round_trip() {
+    // White and the primaries, the corners of the Smits basis.
+    for rgb in [
+        Color::new(1., 1., 1.),
+        Color::new(1., 0., 0.),
+        Color::new(0., 1., 0.),
+        Color::new(0., 0., 1.),
+    ] {
+        let back = round_trip(rgb);
+        for i in 0..3 {
+            assert!(
+                (back[i] - rgb[i]).abs() < 0.02,
+                "{rgb:?} came back as {back:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn bk7_matches_the_catalog() {
+    // n_d, at the helium d line.
+    let n = RefractiveIndex::BK7.at(587.6);
+    assert!((n - 1.5168).abs() < 1e-4, "{n}");
+    // Blue light is bent more than red.
+    assert!(RefractiveIndex::BK7.at(450.) > RefractiveIndex::BK7.at(650.));
+    assert!(RefractiveIndex::BK7.is_dispersive());
+}
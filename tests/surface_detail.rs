@@ -0,0 +1,157 @@
+// Checks the surface derivatives that orient normal and bump maps, and which way the maps bend the
+// normal.
+
+use std::sync::Arc;
+
+use raytracing::{
+    dot, unit_vector, Color, DetailedMaterial, Disk, Float, HitRecord, Hittable, Interval,
+    Lambertian, Material, Mesh, Point3, Quad, Ray, SdfHittable, SdfTorus, SolidColor, Sphere,
+    SurfaceDetail, Texture, TriangleMesh, Vec3,
+};
+
+// Heights rising along u.
+struct Ramp;
+
+impl Texture for Ramp {
+    fn value(&self, u: Float, _v: Float, _p: &Point3) -> Color {
+        Color::new(u, u, u)
+    }
+}
+
+fn hit(object: &impl Hittable, origin: Point3, direction: Vec3) -> HitRecord {
+    let mut record = HitRecord::default();
+    assert!(object.hit(
+        &Ray::new(origin, direction),
+        Interval::new(0.001, 100.),
+        &mut record
+    ));
+    record
+}
+
+// Two nearby hits must be apart by the derivatives times the change of (u, v).
+fn assert_derivatives(object: &impl Hittable, origin: Point3, direction: Vec3) {
+    let a = hit(object, origin, direction);
+    let b = hit(object, origin + Vec3::new(1e-4, 1e-4, 0.), direction);
+    let expected = (b.u - a.u) * a.dpdu + (b.v - a.v) * a.dpdv;
+    let moved = b.p - a.p;
+    assert!(
+        (moved - expected).length() < 1e-3 * moved.length(),
+        "{moved:?} {expected:?}"
+    );
+    assert!(dot(a.dpdu, a.normal).abs() < 1e-6 * a.dpdu.length());
+}
+
+#[test]
+fn surfaces_report_their_derivatives() {
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let toward = Vec3::new(0., 0., -1.);
+
+    let sphere = Sphere::new(&Point3::new(0., 0., 0.), 2., mat.clone()).unwrap();
+    assert_derivatives(&sphere, Point3::new(0.3, 0.7, 5.), toward);
+
+    let quad = Quad::new(
+        Point3::new(-1., -1., 0.),
+        Vec3::new(2., 1., 0.),
+        Vec3::new(0., 3., 0.),
+        mat.clone(),
+    )
+    .unwrap();
+    assert_derivatives(&quad, Point3::new(0.2, 0.3, 5.), toward);
+
+    // Texture coordinates that are rotated and stretched against the positions.
+    let mesh = TriangleMesh {
+        positions: vec![
+            Point3::new(-1., -1., 0.),
+            Point3::new(1., -1., 0.),
+            Point3::new(0., 1., 0.),
+        ],
+        texcoords: vec![(0., 0.), (0., 2.), (-1., 1.)],
+        triangles: vec![[0, 1, 2]],
+        ..TriangleMesh::default()
+    };
+    let mesh = Mesh::new(mesh, Some(mat.clone())).unwrap();
+    assert_derivatives(&mesh, Point3::new(0.1, -0.2, 5.), toward);
+
+    let disk = Disk::new(Point3::new(0., 0., 0.), Vec3::new(0., 0., 1.), 2., 0.5, mat).unwrap();
+    assert_derivatives(&disk, Point3::new(0.3, 0.7, 5.), toward);
+}
+
+// A record is reused from hit to hit, so surfaces must not leave the derivatives of the previous
+// one in place.
+#[test]
+fn derivatives_are_not_left_over() {
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let sphere = Sphere::new(&Point3::new(0., 0., 10.), 2., mat.clone()).unwrap();
+    let disk = Disk::new(
+        Point3::new(0., 0., 0.),
+        Vec3::new(0., 0., 1.),
+        2.,
+        0.,
+        mat.clone(),
+    )
+    .unwrap();
+    let torus = SdfHittable::new(
+        Arc::new(SdfTorus::new(Point3::new(0., 0., 0.), 1., 0.25)),
+        mat,
+    );
+
+    let reused = |object: &dyn Hittable, origin: Point3| {
+        let mut record = HitRecord::default();
+        let sphere_ray = Ray::new(Point3::new(0.5, 0.5, 20.), Vec3::new(0., 0., -1.));
+        assert!(sphere.hit(&sphere_ray, Interval::new(0.001, 100.), &mut record));
+        let ray = Ray::new(origin, Vec3::new(0., -1., -1.));
+        assert!(object.hit(&ray, Interval::new(0.001, 100.), &mut record));
+        record
+    };
+
+    let on_disk = reused(&disk, Point3::new(0.3, 1.7, 1.));
+    assert!(dot(on_disk.dpdu, on_disk.normal).abs() < 1e-9);
+    assert!(dot(on_disk.dpdv, on_disk.normal).abs() < 1e-9);
+    assert!(on_disk.dpdu.length() > 0. && on_disk.dpdv.length() > 0.);
+
+    // Signed distance surfaces have no coordinates, so detail falls back to an arbitrary frame.
+    let on_torus = reused(&torus, Point3::new(1., 5., 4.75));
+    assert_eq!(
+        (on_torus.dpdu, on_torus.dpdv),
+        (Vec3::default(), Vec3::default())
+    );
+}
+
+#[test]
+fn detail_bends_the_normal() {
+    let base: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let quad = |detail| {
+        let mat = Arc::new(DetailedMaterial::new(base.clone(), detail));
+        Quad::new(
+            Point3::new(-1., -1., 0.),
+            Vec3::new(2., 0., 0.),
+            Vec3::new(0., 2., 0.),
+            mat,
+        )
+        .unwrap()
+    };
+    let shade = |object: &Quad| {
+        let record = hit(object, Point3::new(0., 0., 5.), Vec3::new(0., 0., -1.));
+        record.mat.shading_normal(&record)
+    };
+    let normal_map = |color| SurfaceDetail::NormalMap {
+        map: Arc::new(SolidColor::new(color)),
+        strength: 1.,
+    };
+
+    // A flat normal map keeps the normal; one leaning along u tilts it toward +x.
+    let flat = shade(&quad(normal_map(Color::new(0.5, 0.5, 1.))));
+    assert!((flat - Vec3::new(0., 0., 1.)).length() < 1e-9);
+    let leaning = shade(&quad(normal_map(Color::new(1., 0.5, 1.))));
+    assert!((leaning - unit_vector(Vec3::new(1., 0., 1.))).length() < 1e-6);
+
+    // Heights rising by 2 over the quad, which is 2 wide along u, make a 45 degree slope facing -x.
+    let bump = shade(&quad(SurfaceDetail::Bump {
+        height: Arc::new(Ramp),
+        strength: 2.,
+    }));
+    assert!(
+        (bump - unit_vector(Vec3::new(-1., 0., 1.))).length() < 1e-3,
+        "{bump:?}"
+    );
+}